@@ -3,7 +3,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, ItemFn};
 
-use crate::util::utils::get_attributes;
+use crate::util::utils::{get_attributes, parse_size};
 
 const DEFAULT_IP : &str = "127.0.0.1";
 const DEFAULT_PORT : &str = "3000";
@@ -12,7 +12,8 @@ const DEFAULT_PORT : &str = "3000";
 struct MacroServerParams
 {
     ip : Option<String>,
-    port : Option<String>
+    port : Option<String>,
+    max_body : Option<syn::LitStr>
 }
 
 impl MacroServerParams
@@ -22,7 +23,8 @@ impl MacroServerParams
         Self
         {
             ip : None,
-            port : None
+            port : None,
+            max_body : None
         }
     }
 
@@ -69,9 +71,19 @@ pub fn awpak_main_impl( args: TokenStream, item: TokenStream ) -> TokenStream
 
     let statements = block.stmts;
 
-    let server_statement = get_server_statement( args );
+    let server_statement = match get_server_statement( args )
+    {
+        Ok( v ) => v,
+        Err( e ) => return e.to_compile_error().into()
+    };
+
+    let initialize_middlewares = quote! { awpak_rs::initialize_middlewares()?; };
+
+    let initialize_endpoints = quote! { awpak_rs::initialize_endpoints()?; };
+
+    let initialize_codecs = quote! { awpak_rs::initialize_codecs()?; };
 
-    let initialize_middlewares = quote! { awpak_rs::initialize_middlewares(); };
+    let initialize_error_handlers = quote! { awpak_rs::initialize_error_handlers()?; };
 
     quote! {
         #uses
@@ -81,6 +93,12 @@ pub fn awpak_main_impl( args: TokenStream, item: TokenStream ) -> TokenStream
         #signature
         {
             #initialize_middlewares
+
+            #initialize_endpoints
+
+            #initialize_codecs
+
+            #initialize_error_handlers
             
             #(#statements)*
 
@@ -89,7 +107,7 @@ pub fn awpak_main_impl( args: TokenStream, item: TokenStream ) -> TokenStream
     }.into()
 }
 
-fn get_server_statement( args : TokenStream ) -> proc_macro2::TokenStream
+fn get_server_statement( args : TokenStream ) -> Result<proc_macro2::TokenStream, syn::Error>
 {
     let server_params = get_server_params( args );
 
@@ -97,7 +115,16 @@ fn get_server_statement( args : TokenStream ) -> proc_macro2::TokenStream
 
     let port = server_params.get_token_port();
 
-    generate_server_statement( ip, port )
+    match &server_params.max_body
+    {
+        Some( m ) => {
+
+            let max_body = parse_size( &m.value() ).map_err( | e | syn::Error::new( m.span(), e ) )?;
+
+            Ok( generate_server_statement_with_max_body( ip, port, max_body ) )
+        },
+        None => Ok( generate_server_statement( ip, port ) )
+    }
 }
 
 fn get_server_params( args : TokenStream ) -> MacroServerParams
@@ -116,6 +143,15 @@ fn generate_server_statement( ip : proc_macro2::TokenStream, port : proc_macro2:
     }
 }
 
+fn generate_server_statement_with_max_body( ip : proc_macro2::TokenStream, port : proc_macro2::TokenStream, max_body : usize ) -> proc_macro2::TokenStream
+{
+    quote! {
+        awpak_rs::server::server::server_with_params(
+            awpak_rs::server::server::ServerParams::new( #ip, #port ).with_max_body( #max_body )
+        ).await
+    }
+}
+
 fn get_uses() -> proc_macro2::TokenStream
 {
     quote! {
@@ -0,0 +1,73 @@
+use darling::FromMeta;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, Item};
+
+use crate::util::utils::get_attributes;
+
+#[derive(FromMeta)]
+struct MacroBodyCodecData
+{
+    mime : syn::LitStr
+}
+
+pub fn body_codec_impl( args: TokenStream, item: TokenStream ) -> TokenStream
+{
+    let MacroBodyCodecData { mime } = match get_attributes( args ) {
+        Ok( v ) => v,
+        Err( e ) => return e
+    };
+
+    let mime_type = match get_mime_type( &mime.value() )
+    {
+        Ok( m ) => m,
+        Err( e ) => return syn::Error::new( mime.span(), e ).to_compile_error().into()
+    };
+
+    let item = syn::parse_macro_input!( item as Item );
+
+    let ident = match &item
+    {
+        Item::Struct( s ) => &s.ident,
+        Item::Enum( e ) => &e.ident,
+        _ => return syn::Error::new( item.span(), "body_codec can only be applied to a struct or an enum" ).to_compile_error().into()
+    };
+
+    quote! {
+
+        #item
+
+        awpak_rs::inventory::submit! {
+            awpak_rs::BodyCodec::new( #mime_type, <#ident as awpak_rs::Codec>::decode, <#ident as awpak_rs::Codec>::encode )
+        }
+    }.into()
+}
+
+// The media type is stored in lowercase, without parameters
+fn get_mime_type( mime : &str ) -> Result<String, String>
+{
+    let is_token = | s : &str | ! s.is_empty() && s.chars().all( | c | c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains( c ) );
+
+    match mime.trim().split_once( "/" )
+    {
+        Some( ( t, s ) ) if is_token( t ) && is_token( s ) && t != "*" && s != "*" => Ok( mime.trim().to_lowercase() ),
+        _ => Err( format!( "Invalid media type: \"{}\", expected a type like \"application/x-protobuf\"", mime ) )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_mime_type()
+    {
+        assert_eq!( get_mime_type( "Application/X-Protobuf" ).unwrap(), "application/x-protobuf" );
+        assert_eq!( get_mime_type( "application/vnd.api+json" ).unwrap(), "application/vnd.api+json" );
+
+        assert!( get_mime_type( "application" ).is_err() );
+        assert!( get_mime_type( "application/json; charset=utf-8" ).is_err() );
+        assert!( get_mime_type( "application/*" ).is_err() );
+        assert!( get_mime_type( "/json" ).is_err() );
+    }
+}
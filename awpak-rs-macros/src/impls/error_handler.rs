@@ -0,0 +1,83 @@
+use proc_macro::TokenStream;
+use darling::FromMeta;
+use quote::quote;
+use syn::ItemFn;
+
+use crate::util::utils::get_attributes;
+
+#[derive(FromMeta)]
+struct MacroErrorHandlerData
+{
+    status : Option<u16>,
+    error : Option<syn::Path>
+}
+
+pub fn error_handler_impl( args: TokenStream, item: TokenStream ) -> TokenStream
+{
+    let MacroErrorHandlerData { status, error } = match get_attributes( args ) {
+        Ok( v ) => v,
+        Err( e ) => return e
+    };
+
+    let ItemFn {
+        block,
+        attrs,
+        mut sig,
+        vis,
+        ..
+
+    } = syn::parse_macro_input!( item as ItemFn );
+
+    if status.is_some() && error.is_some()
+    {
+        return syn::Error::new( sig.ident.span(), "error_handler accepts status or error, but not both" ).to_compile_error().into()
+    }
+
+    sig.output = syn::parse( quote! {
+        -> awpak_rs::ErrorHandlerReturnType
+    }.into() ).unwrap();
+
+    let fnc_ident = sig.ident.clone();
+
+    let status = match status
+    {
+        Some( v ) => quote! { Some( #v ) },
+        _ => quote! { None }
+    };
+
+    // The variant is checked at compile time by the pattern
+    let ( variant, error ) = match error
+    {
+        Some( v ) => {
+            let name = quote! { #v }.to_string().replace( ' ', "" );
+
+            (
+                quote! { Some( #name ) },
+                quote! {
+                    Some( | __error : &awpak_rs::Error | matches!( __error, awpak_rs::Error::#v { .. } ) )
+                }
+            )
+        },
+        _ => ( quote! { None }, quote! { None } )
+    };
+
+    quote! {
+        #(#attrs)*
+        #vis #sig
+        {
+            std::boxed::Box::pin( async move {
+                let __io : awpak_rs::io::io::IO = async move #block.await;
+                __io
+            } )
+        }
+
+        awpak_rs::inventory::submit! {
+            awpak_rs::ErrorHandler::new(
+                #status,
+                #variant,
+                #error,
+                | __error_handler_io | { #fnc_ident( __error_handler_io ) }
+            )
+        }
+    }.into()
+}
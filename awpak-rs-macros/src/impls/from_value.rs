@@ -12,7 +12,7 @@ pub fn from_value_impl( item : TokenStream ) -> TokenStream
 
         impl awpak_rs::from_value::FromValue for #ident
         {
-            fn from_value( value : &awpak_rs::Value ) -> Option<Self>
+            fn from_value( value : &awpak_rs::Value ) -> Result<Self, String>
             {
                 awpak_rs::parse_from_value( value )
             }
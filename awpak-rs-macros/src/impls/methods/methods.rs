@@ -1,19 +1,28 @@
 use darling::FromMeta;
 use proc_macro::TokenStream;
-use quote::{quote, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{spanned::Spanned, FnArg, Ident, ItemFn, PatIdent, PatType, ReturnType, Signature, Visibility};
 
-use crate::util::utils::get_attributes;
+use crate::util::utils::{get_attributes, parse_size, PathArray, StringArray};
+
+// Extractors that need the body buffered, so they can't be used with `#[body_stream]`.
+const BUFFERED_BODY_VARIABLES : [ &str; 5 ] = [ "request_body", "body_param", "raw_body", "part_file", "part_files" ];
 
 #[derive(FromMeta)]
 struct MacroEndpointData
 {
-    url : String
+    url : String,
+    case_insensitive : Option<bool>,
+    middleware : Option<PathArray>,
+    max_body : Option<syn::LitStr>,
+    produces : Option<StringArray>,
+    not_acceptable : Option<bool>,
+    xml_root : Option<syn::LitStr>
 }
 
 pub fn methods_impl( args: TokenStream, item: TokenStream, method : &str ) -> TokenStream
 {
-    let MacroEndpointData { url } = match get_attributes( args ) {
+    let MacroEndpointData { url, case_insensitive, middleware, max_body, produces, not_acceptable, xml_root } = match get_attributes( args ) {
         Ok( v ) => v,
         Err( e ) => return e
     };
@@ -28,23 +37,88 @@ pub fn methods_impl( args: TokenStream, item: TokenStream, method : &str ) -> To
 
     let output = sig.output.clone();
 
-    let return_type = match output {
-        ReturnType::Type( _a, b ) => quote! {#b},
-        _ => quote! {()}
+    let return_type : syn::Type = match &output {
+        ReturnType::Type( _a, b ) => *b.clone(),
+        _ => syn::parse_quote! {()}
     };
     
     let ( variables, post_variables ) = get_variables( &sig, &url );
 
+    let case_insensitive = case_insensitive.unwrap_or( false );
+
+    let middlewares = get_middlewares( middleware );
+
+    let body_stream = match get_body_stream( &sig )
+    {
+        Ok( true ) => quote! { .with_body_stream() },
+        Ok( false ) => quote! {},
+        Err( e ) => return e.to_compile_error().into()
+    };
+
+    let max_body = match max_body.map( | m | parse_size( &m.value() ).map_err( | e | syn::Error::new( m.span(), e ) ) )
+    {
+        Some( Ok( v ) ) => quote! { .with_max_body( #v ) },
+        Some( Err( e ) ) => return e.to_compile_error().into(),
+        None => quote! {}
+    };
+
+    let produces = match produces
+    {
+        Some( p ) => {
+
+            let produces = p.0;
+
+            quote! { .with_produces( &[ #( #produces ),* ] ) }
+        },
+        None => quote! {}
+    };
+
+    let not_acceptable = match not_acceptable
+    {
+        Some( n ) => quote! { .with_not_acceptable( #n ) },
+        None => quote! {}
+    };
+
+    let xml_root = match xml_root.map( | r | get_xml_root( &r.value() ).map_err( | e | syn::Error::new( r.span(), e ) ) )
+    {
+        Some( Ok( v ) ) => quote! { .with_xml_root( #v ) },
+        Some( Err( e ) ) => return e.to_compile_error().into(),
+        None => quote! {}
+    };
+
     let new_signature = get_signature( &vis, &sig.ident );
     let new_ident = sig.ident;
 
-    let salida = quote! {
+    let handler_name = new_ident.to_string();
 
-        __io.response.body = awpak_rs::serialize_value::<#return_type>( __result );
+    let salida = match get_result_types( &output )
+    {
+        Some( ( ok_type, err_type ) ) => {
 
-        __io.response.headers.replace_headers( __response_headers );
+            let ok_response = get_response( &ok_type, quote! { __ok } );
 
-        Ok( __io )
+            quote! {
+
+                __io.response.headers.replace_headers( __response_headers );
+
+                match __result
+                {
+                    Ok( __ok ) => #ok_response,
+                    Err( __err ) => Ok( <#err_type as awpak_rs::IntoErrorResponse>::into_error_response( __err, __io ) )
+                }
+            }
+        },
+        _ => {
+
+            let response = get_response( &return_type, quote! { __result } );
+
+            quote! {
+
+                __io.response.headers.replace_headers( __response_headers );
+
+                #response
+            }
+        }
     };
 
     quote! {
@@ -58,7 +132,7 @@ pub fn methods_impl( args: TokenStream, item: TokenStream, method : &str ) -> To
                 
                 let mut __response_headers = awpak_rs::io::headers::headers::Headers::new();
 
-                let __result = async #block.await;
+                let __result : #return_type = async #block.await;
 
                 #post_variables
 
@@ -68,11 +142,131 @@ pub fn methods_impl( args: TokenStream, item: TokenStream, method : &str ) -> To
         }
         
         awpak_rs::inventory::submit! {
-            awpak_rs::endpoint::endpoint::Endpoint::new( #url, #method, | __awpak_rs_param_a | { #new_ident( __awpak_rs_param_a ) } )
+            awpak_rs::endpoint::endpoint::Endpoint::new( #url, #method, | __awpak_rs_param_a | { #new_ident( __awpak_rs_param_a ) }, #case_insensitive )
+            .with_source( concat!( module_path!(), "::", #handler_name ), file!(), line!() )
+            #middlewares
+            #body_stream
+            #max_body
+            #produces
+            #not_acceptable
+            #xml_root
         }
     }.into()
 }
 
+// Returns true if a parameter is `#[body_stream]`, so the body of the endpoint must not be buffered.
+fn get_body_stream( sig : &Signature ) -> Result<bool, syn::Error>
+{
+    let attributes = sig.inputs.iter().filter_map( | i | match i
+    {
+        FnArg::Typed( argument ) => get_variable_attribute( argument ).map( | a | ( a, argument.span() ) ),
+        _ => None
+    } ).collect::<Vec<_>>();
+
+    if ! attributes.iter().any( | ( a, _ ) | a == "body_stream" )
+    {
+        return Ok( false )
+    }
+
+    match attributes.iter().find( | ( a, _ ) | BUFFERED_BODY_VARIABLES.contains( &a.as_str() ) )
+    {
+        Some( ( a, span ) ) => Err( syn::Error::new( *span, format!( "#[{}] can't be used with #[body_stream]", a ) ) ),
+        _ => Ok( true )
+    }
+}
+
+// The root element of XML responses must be a valid XML name, like `user` or `order-list`
+fn get_xml_root( name : &str ) -> Result<String, String>
+{
+    let mut chars = name.chars();
+
+    let valid = match chars.next()
+    {
+        Some( c ) if c.is_alphabetic() || c == '_' => chars.all( | c | c.is_alphanumeric() || "_-.".contains( c ) ),
+        _ => false
+    };
+
+    if valid
+    {
+        Ok( name.to_string() )
+    }
+    else
+    {
+        Err( format!( "Invalid XML element name: \"{}\"", name ) )
+    }
+}
+
+// Sets the value returned by the endpoint in the response.
+// Types that implement `IntoResponse`, like `Response`, `Redirect`, raw bytes and streams, set the whole response.
+// Other types are serialized as the body. The choice is made by the traits of the type, not by its name (see `awpak_rs::ResponseKind`).
+// The headers of `#[response_headers]` must be applied before, so the ones of a `Response` replace them.
+fn get_response( ty : &syn::Type, value : proc_macro2::TokenStream ) -> proc_macro2::TokenStream
+{
+    quote! {
+        {
+            #[allow(unused_imports)]
+            use awpak_rs::{IntoResponseKind as _, SerializeKind as _};
+
+            Ok( ( &awpak_rs::ResponseKind::<#ty>( std::marker::PhantomData ) ).response_kind().send( #value, __io ) )
+        }
+    }
+}
+
+// Returns the `T` and `E` of a `Result<T, E>` return type.
+// A `Result` alias with a single type argument is serialized like any other type.
+fn get_result_types( output : &ReturnType ) -> Option<( syn::Type, syn::Type )>
+{
+    let ReturnType::Type( _, ty ) = output else { return None };
+
+    let syn::Type::Path( path ) = ty.as_ref() else { return None };
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Result"
+    {
+        return None
+    }
+
+    let syn::PathArguments::AngleBracketed( args ) = &segment.arguments else { return None };
+
+    let mut types = args.args.iter().filter_map( | a | match a
+    {
+        syn::GenericArgument::Type( t ) => Some( t.clone() ),
+        _ => None
+    } );
+
+    match ( types.next(), types.next(), types.next() )
+    {
+        ( Some( ok_type ), Some( err_type ), None ) => Some( ( ok_type, err_type ) ),
+        _ => None
+    }
+}
+
+pub fn get_middlewares( middleware : Option<PathArray> ) -> proc_macro2::TokenStream
+{
+    match middleware
+    {
+        Some( PathArray( v ) ) if ! v.is_empty() => {
+
+            // Spanned so an unknown name, or a function without `#[middleware]`, is reported on the name itself.
+            // A global middleware already runs for every endpoint, so attaching it fails to compile.
+            let middlewares = v.iter().map( | p | quote_spanned! { p.span() => {
+                const _ : () = assert!(
+                    ! #p::GLOBAL,
+                    concat!( "`", stringify!( #p ), "` is a global middleware and already runs for every endpoint, add `global = false` to its #[middleware] to attach it" )
+                );
+
+                #p::MIDDLEWARE
+            } } );
+
+            quote! {
+                .with_middlewares( &[ #( #middlewares ),* ] )
+            }
+        },
+        _ => quote! {}
+    }
+}
+
 fn get_signature( vis : &Visibility, sig : &Ident) -> proc_macro2::TokenStream
 {
     quote! {
@@ -97,7 +291,7 @@ fn get_variables( sig : &Signature, url : &String ) -> ( proc_macro2::TokenStrea
     ( variables, post_variables )
 }
 
-fn get_variable( arg : &FnArg, sig : &Signature, url : &String ) -> ( proc_macro2::TokenStream, proc_macro2::TokenStream )
+pub fn get_variable( arg : &FnArg, sig : &Signature, url : &String ) -> ( proc_macro2::TokenStream, proc_macro2::TokenStream )
 {
     if let syn::FnArg::Typed( argument ) = arg {
         
@@ -147,6 +341,8 @@ fn declare_variable(
         "request_cookies" => declare_variable_cookies( ty, fake_attr, pat_ident, true ),
         "response_cookies" => declare_variable_cookies( ty, fake_attr, pat_ident, false ),
         "query_param" => declare_variable_query_param( ty, priv_pat_ident, fake_attr, pat_ident ),
+        "raw_body" => declare_variable_raw_body( ty, fake_attr, pat_ident ),
+        "body_stream" => declare_variable_body_stream( ty, fake_attr, pat_ident ),
         _ => unreachable!()
     }
 }
@@ -157,30 +353,54 @@ fn declare_variable_query_param(
 {
     let name = pat_ident.ident.to_string();
 
-    let optional_part = quote! {
-        if #priv_pat_ident.is_none()
-        {
-            println!( "Parse query param value err" );
-            
-            return Err( awpak_rs::Error::ParserError( format!( "Query param error: {}", #name ) ) )
-        }
-    };
-
     let final_assign = quote! {
-        let #pat_ident = #priv_pat_ident.unwrap();
+        let #pat_ident = match #priv_pat_ident
+        {
+            Ok( v ) => v,
+            Err( e ) => return Ok( __io.with_error( awpak_rs::Error::BadRequest( awpak_rs::ExtractionError::new( #name, awpak_rs::ExtractionSource::Query, e ) ) ) )
+        };
     };
 
     (
         quote! {
             #fake_attr!();
             let #priv_pat_ident = awpak_rs::parse_query_param_value::<#ty>( &__io, #name );
-            #optional_part
             #final_assign
         },
         quote! {}
     )
 }
 
+fn declare_variable_raw_body(
+    ty : Box<syn::Type>, 
+    fake_attr : Ident, 
+    pat_ident : PatIdent
+) -> ( proc_macro2::TokenStream, proc_macro2::TokenStream )
+{
+    (
+        quote! {
+            #fake_attr!();
+            let #pat_ident : #ty = <#ty as std::convert::From<awpak_rs::Bytes>>::from( __io.request.body.raw.clone().unwrap_or_default() );
+        },
+        quote! {}
+    )
+}
+
+fn declare_variable_body_stream(
+    ty : Box<syn::Type>, 
+    fake_attr : Ident, 
+    pat_ident : PatIdent
+) -> ( proc_macro2::TokenStream, proc_macro2::TokenStream )
+{
+    (
+        quote! {
+            #fake_attr!();
+            let #pat_ident : #ty = __io.request.take_body_stream();
+        },
+        quote! {}
+    )
+}
+
 fn declare_variable_cookies(
     ty : Box<syn::Type>, 
     fake_attr : Ident, 
@@ -301,20 +521,36 @@ fn declare_variable_path(
     url : &String
 ) -> ( proc_macro2::TokenStream, proc_macro2::TokenStream )
 {
-    let name = pat_ident.to_token_stream().to_string();
+    let name = pat_ident.ident.to_string();
 
-    match get_ind_path_variable( name.clone(), url )
+    match check_path_variable( &name, url )
     {
-        Ok( v ) => {
+        Ok( wildcard ) => {
+            let parse = if wildcard
+            {
+                quote! { awpak_rs::parse_path_wildcard::<#ty>( &__io, #name ) }
+            }
+            else
+            {
+                quote! {
+                    {
+                        #[allow(unused_imports)]
+                        use awpak_rs::{FromStrKind as _, FromAsyncStrKind as _};
+
+                        ( &awpak_rs::PathVariableKind::<#ty>( std::marker::PhantomData ) ).path_variable_kind().parse::<#ty>( &__io, #name ).await
+                    }
+                }
+            };
+
             (
                 quote! {
                     #fake_attr!();
-                    let #priv_pat_ident = awpak_rs::parse_path_variable::<#ty>( &__io, #v ).await;
-                    if #priv_pat_ident.is_none()
+                    let #priv_pat_ident = #parse;
+                    let #pat_ident = match #priv_pat_ident
                     {
-                        return Err( awpak_rs::Error::ParserError( format!( "Path variable error: {}", #name ) ) )
-                    }
-                    let #pat_ident = #priv_pat_ident.unwrap();
+                        Ok( v ) => v,
+                        Err( e ) => return Ok( __io.with_error( awpak_rs::Error::BadRequest( awpak_rs::ExtractionError::new( #name, awpak_rs::ExtractionSource::Path, e ) ) ) )
+                    };
                 },
                 quote! {}
             )
@@ -323,15 +559,20 @@ fn declare_variable_path(
     }
 }
 
-fn get_ind_path_variable( name : String, url : &String ) -> Result<usize, String>
+// Returns true if the variable is a `{*wildcard}` segment.
+fn check_path_variable( name : &str, url : &str ) -> Result<bool, String>
 {
-    let name = format!( "{{{}}}", name );
+    if url.contains( &format!( "{{*{}}}", name ) )
+    {
+        return Ok( true )
+    }
 
-    match url.split( "/" ).enumerate().find(  | v | v.1 == name ).map( | v | v.0 )
+    if url.contains( &format!( "{{{}}}", name ) ) || url.contains( &format!( "{{{}:", name ) )
     {
-        Some( v ) => Ok( v ),
-        _ => Err( "".to_string() )
+        return Ok( false )
     }
+
+    Err( format!( "Path variable {} not found in url {}", name, url ) )
 }
 
 fn declare_variable_body_param( 
@@ -340,22 +581,18 @@ fn declare_variable_body_param(
 {
     let name = pat_ident.ident.to_string();
 
-    let optional_part = quote! {
-        if #priv_pat_ident.is_none()
-        {
-            return Err( awpak_rs::Error::ParserError( format!( "Body param error: {}", #name ) ) )
-        }
-    };
-
     let final_assign = quote! {
-        let #pat_ident = #priv_pat_ident.unwrap();
+        let #pat_ident = match #priv_pat_ident
+        {
+            Ok( v ) => v,
+            Err( e ) => return Ok( __io.with_error( awpak_rs::Error::BadRequest( awpak_rs::ExtractionError::new( #name, awpak_rs::ExtractionSource::Body, e ) ) ) )
+        };
     };
 
     (
         quote! {
             #fake_attr!();
             let #priv_pat_ident = awpak_rs::parse_body_param_value::<#ty>( &__io, #name );
-            #optional_part
             #final_assign
         },
         quote! {}
@@ -375,22 +612,18 @@ fn declare_variable_file(
         };
     };
 
-    let optional_part = quote! {
-        if #priv_pat_ident.is_err()
-        {
-            return Err( awpak_rs::Error::ParserError( format!( "File param error: {}", #filename ) ) )
-        }
-    };
-
     let final_assign = quote! {
-        let #pat_ident = #priv_pat_ident.unwrap();
+        let #pat_ident = match #priv_pat_ident
+        {
+            Ok( v ) => v,
+            Err( _ ) => return Ok( __io.with_error( awpak_rs::Error::BadRequest( awpak_rs::ExtractionError::new( #filename, awpak_rs::ExtractionSource::File, "missing file".to_string() ) ) ) )
+        };
     };
 
     (
         quote! {
             #fake_attr!();
             #priv_pat_ident_assign
-            #optional_part
             #final_assign
         },
         quote! {}
@@ -403,6 +636,15 @@ fn declare_variable_object(
 {
     let name = pat_ident.ident.to_string();
 
+    let source = if from == "query_params"
+    {
+        quote! { awpak_rs::ExtractionSource::Query }
+    }
+    else
+    {
+        quote! { awpak_rs::ExtractionSource::Body }
+    };
+
     (
         quote! {
 
@@ -410,18 +652,17 @@ fn declare_variable_object(
 
             let #priv_pat_ident = awpak_rs::parse_value::<#ty>( &__io, #from );
 
-            if #priv_pat_ident.is_none()
+            let #pat_ident = match #priv_pat_ident
             {
-                return Err( awpak_rs::Error::ParserError( format!( "{} error: {}", #from, #name ) ) )
-            }
-
-            let #pat_ident = #priv_pat_ident.unwrap();
+                Ok( v ) => v,
+                Err( e ) => return Ok( __io.with_error( awpak_rs::Error::BadRequest( awpak_rs::ExtractionError::new( #name, #source, e ) ) ) )
+            };
         },
         quote! {}
     )
 }
 
-fn get_variable_attribute( arg : &PatType ) -> Option<String>
+pub fn get_variable_attribute( arg : &PatType ) -> Option<String>
 {
     for i in 0..arg.attrs.len()
     {
@@ -451,6 +692,46 @@ fn match_variable_attribute( attr : &str ) -> Option<String>
         "request_cookies" => Some( "request_cookies".to_string() ),
         "response_cookies" => Some( "response_cookies".to_string() ),
         "query_param" => Some( "query_param".to_string() ),
+        "raw_body" => Some( "raw_body".to_string() ),
+        "body_stream" => Some( "body_stream".to_string() ),
         _ => None
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_result_types()
+    {
+        let types = | output : ReturnType | get_result_types( &output ).map( | ( t, e ) | ( t.to_token_stream().to_string(), e.to_token_stream().to_string() ) );
+
+        assert_eq!( types( syn::parse_quote! { -> Result<User, MyError> } ), Some( ( "User".to_string(), "MyError".to_string() ) ) );
+        assert_eq!( types( syn::parse_quote! { -> std::result::Result<Vec<u8>, awpak_rs::Error> } ), Some( ( "Vec < u8 >".to_string(), "awpak_rs :: Error".to_string() ) ) );
+        assert_eq!( types( syn::parse_quote! { -> Result<User> } ), None );
+        assert_eq!( types( syn::parse_quote! { -> Option<User> } ), None );
+        assert_eq!( types( ReturnType::Default ), None );
+    }
+
+    #[test]
+    fn test_get_body_stream()
+    {
+        let body_stream = | sig : Signature | get_body_stream( &sig ).map_err( | e | e.to_string() );
+
+        assert_eq!( body_stream( syn::parse_quote! { fn a( #[body_stream] body : RequestBodyStream, #[query_param] id : u64 ) } ), Ok( true ) );
+        assert_eq!( body_stream( syn::parse_quote! { fn a( #[raw_body] bytes : Bytes ) } ), Ok( false ) );
+        assert_eq!( body_stream( syn::parse_quote! { fn a( #[body_stream] body : RequestBodyStream, #[raw_body] bytes : Bytes ) } ), Err( "#[raw_body] can't be used with #[body_stream]".to_string() ) );
+    }
+
+    #[test]
+    fn test_get_xml_root()
+    {
+        assert_eq!( get_xml_root( "user" ), Ok( "user".to_string() ) );
+        assert_eq!( get_xml_root( "_order-list.v2" ), Ok( "_order-list.v2".to_string() ) );
+
+        assert!( get_xml_root( "" ).is_err() );
+        assert!( get_xml_root( "1user" ).is_err() );
+        assert!( get_xml_root( "user list" ).is_err() );
+        assert!( get_xml_root( "<user>" ).is_err() );
+    }
+}
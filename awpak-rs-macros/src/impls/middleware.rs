@@ -1,7 +1,7 @@
 use proc_macro::TokenStream;
 use darling::FromMeta;
 use quote::quote;
-use syn::{ItemFn, LitStr, Signature};
+use syn::{ItemFn, LitStr, Signature, Visibility};
 
 use crate::util::utils::get_attributes;
 
@@ -11,7 +11,9 @@ struct MacroMiddlewareData
     urls : Option<Vec<LitStr>>,
     order : Option<usize>,
     execute_after : Option<bool>,
-    method : Option<String>
+    method : Option<String>,
+    global : Option<bool>,
+    around : Option<bool>
 }
 
 pub fn middleware_impl( args: TokenStream, item: TokenStream ) -> TokenStream
@@ -25,32 +27,69 @@ pub fn middleware_impl( args: TokenStream, item: TokenStream ) -> TokenStream
         block,
         attrs,
         mut sig,
+        vis,
         ..
 
     } = syn::parse_macro_input!( item as ItemFn );
 
-    sig.output = syn::parse( quote! {
-        -> awpak_rs::MiddlewareResponseType
-    }.into() ).unwrap();
+    let around = middleware_data.around.unwrap_or( false );
+
+    if around && middleware_data.execute_after.is_some()
+    {
+        return syn::Error::new( sig.ident.span(), "around middlewares can't use execute_after" ).to_compile_error().into()
+    }
+
+    // The declared return type of an around middleware is checked against the body
+    let body = match ( around, &sig.output )
+    {
+        ( true, syn::ReturnType::Type( _, ty ) ) => quote! {
+            std::boxed::Box::pin( async move {
+                let __result : #ty = async move #block.await;
+                __result
+            } )
+        },
+        _ => quote! {
+            std::boxed::Box::pin( async move #block )
+        }
+    };
+
+    sig.output = if around
+    {
+        syn::parse( quote! {
+            -> awpak_rs::endpoint::types::EndpointReturnType
+        }.into() ).unwrap()
+    }
+    else
+    {
+        syn::parse( quote! {
+            -> awpak_rs::MiddlewareResponseType
+        }.into() ).unwrap()
+    };
 
     let inventory = get_inventory( 
         &sig,
+        &vis,
         &middleware_data
     );
 
     quote! {
         #(#attrs)*
-        #sig
+        #vis #sig
         {
-            std::boxed::Box::pin( async move #block )
+            #body
         }
 
         #inventory
     }.into()
 }
 
+// Besides the inventory entry, generates a struct with the same name as the function
+// with the middleware data, so scopes and endpoints can attach the middleware by name (`middleware = [fnc]`).
+// A struct with braces only lives in the type namespace and the function in the value namespace,
+// so both can be imported with a single `use`, and both work inside function bodies.
 fn get_inventory( 
     sig : &Signature, 
+    vis : &Visibility,
     data : &MacroMiddlewareData
 ) -> proc_macro2::TokenStream
 {
@@ -86,9 +125,35 @@ fn get_inventory(
         _ => quote! { None }
     };
 
-    quote!
+    let global = data.global.unwrap_or( true );
+
+    let submit = if global
     {
-        awpak_rs::inventory::submit! {
+        quote! {
+            awpak_rs::inventory::submit! {
+                #fnc_ident::MIDDLEWARE
+            }
+        }
+    }
+    else
+    {
+        quote! {}
+    };
+
+    let middleware = if data.around.unwrap_or( false )
+    {
+        quote! {
+            awpak_rs::Middleware::around(
+                #regex, 
+                #order,
+                #method,
+                | __middleware_io, __middleware_next | { #fnc_ident( __middleware_io, __middleware_next ) }
+            )
+        }
+    }
+    else
+    {
+        quote! {
             awpak_rs::Middleware::new(
                 #regex, 
                 #order,
@@ -97,6 +162,22 @@ fn get_inventory(
                 #exec_order
             )
         }
+    };
+
+    quote!
+    {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types, dead_code)]
+        #vis struct #fnc_ident {}
+
+        impl #fnc_ident
+        {
+            pub const MIDDLEWARE : awpak_rs::Middleware = #middleware;
+
+            pub const GLOBAL : bool = #global;
+        }
+
+        #submit
     }
 }
 
@@ -3,4 +3,8 @@ pub mod methods;
 pub mod from_value;
 pub mod middleware;
 pub mod set_status_code;
-pub mod redirect_to;
\ No newline at end of file
+pub mod redirect_to;
+pub mod scope;
+pub mod error_handler;
+pub mod websocket;
+pub mod body_codec;
\ No newline at end of file
@@ -0,0 +1,224 @@
+use darling::FromMeta;
+use proc_macro::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{parse::Parser, punctuated::Punctuated, spanned::Spanned, Attribute, Expr, ExprArray, Item, ItemMod, Lit, LitStr, Meta, Token};
+
+use crate::util::utils::{get_attributes, PathArray};
+
+const ROUTE_MACROS : [ &str; 10 ] = [ "get", "post", "put", "delete", "patch", "head", "options", "connect", "trace", "websocket" ];
+
+#[derive(FromMeta)]
+struct MacroScopeData
+{
+    prefix : Option<String>,
+    middleware : Option<PathArray>
+}
+
+pub fn scope_impl( args : TokenStream, item : TokenStream ) -> TokenStream
+{
+    let MacroScopeData { prefix, middleware } = match get_attributes( args ) {
+        Ok( v ) => v,
+        Err( e ) => return e
+    };
+
+    let mut item_mod = syn::parse_macro_input!( item as ItemMod );
+
+    let prefix = prefix.unwrap_or_default();
+
+    let middleware = middleware.map( | m | m.0 ).unwrap_or_default().iter().map( get_parent_path ).collect::<Vec<syn::Path>>();
+
+    match &mut item_mod.content
+    {
+        Some( ( _, items ) ) => apply_scope( items, &prefix, &middleware ),
+        None => return syn::Error::new( item_mod.span(), "scope can only be applied to inline modules (mod name { ... })" ).to_compile_error().into()
+    };
+
+    item_mod.into_token_stream().into()
+}
+
+// Adds the prefix and the middlewares to the route macros of the module.
+// Nested modules are scanned too. If a nested module has its own `scope`, the prefix and middlewares
+// are added to that `scope` instead, so they are combined when it expands.
+// The middleware paths are relative to the module of `items`.
+fn apply_scope( items : &mut [Item], prefix : &str, middleware : &[syn::Path] )
+{
+    for item in items
+    {
+        match item
+        {
+            Item::Fn( f ) => for attr in f.attrs.iter_mut()
+            {
+                if is_macro( attr, &ROUTE_MACROS )
+                {
+                    scope_attribute( attr, "url", prefix, middleware );
+                }
+            },
+            Item::Mod( m ) => match m.attrs.iter_mut().find( | a | is_macro( a, &[ "scope" ] ) )
+            {
+                Some( attr ) => scope_attribute( attr, "prefix", prefix, middleware ),
+                None => if let Some( ( _, items ) ) = &mut m.content
+                {
+                    let middleware = middleware.iter().map( get_parent_path ).collect::<Vec<syn::Path>>();
+
+                    apply_scope( items, prefix, &middleware );
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+// Middleware paths are written relative to the module where the scope is declared,
+// so they need a `super` for each module level. Absolute paths are kept.
+fn get_parent_path( path : &syn::Path ) -> syn::Path
+{
+    let first = path.segments.first().map( | s | s.ident.to_string() ).unwrap_or_default();
+
+    if path.leading_colon.is_some() || first == "crate"
+    {
+        return path.clone()
+    }
+
+    let mut parent = path.clone();
+
+    if first == "self"
+    {
+        parent.segments[ 0 ].ident = syn::Ident::new( "super", parent.segments[ 0 ].ident.span() );
+    }
+    else
+    {
+        parent.segments.insert( 0, syn::parse_quote! { super } );
+    }
+
+    parent
+}
+
+fn is_macro( attr : &Attribute, names : &[ &str ] ) -> bool
+{
+    match attr.path().segments.last()
+    {
+        Some( s ) => names.contains( &s.ident.to_string().as_str() ),
+        _ => false
+    }
+}
+
+// Rewrites `#[get(url = "/x", middleware = [b])]` as `#[get(url = "/prefix/x", middleware = [a, b])]`.
+// Attributes that can't be parsed are left unchanged, so the route macro reports the error.
+fn scope_attribute( attr : &mut Attribute, url_key : &str, prefix : &str, middleware : &[syn::Path] )
+{
+    let Meta::List( list ) = &mut attr.meta else { return };
+
+    let mut args = match Punctuated::<Meta, Token![,]>::parse_terminated.parse2( list.tokens.clone() )
+    {
+        Ok( v ) => v,
+        _ => return
+    };
+
+    let mut has_url = false;
+    let mut has_middleware = false;
+
+    for arg in args.iter_mut()
+    {
+        let Meta::NameValue( nv ) = arg else { continue };
+
+        if nv.path.is_ident( url_key )
+        {
+            if let Expr::Lit( l ) = &mut nv.value
+            {
+                if let Lit::Str( s ) = &l.lit
+                {
+                    l.lit = Lit::Str( LitStr::new( &join_url( prefix, &s.value() ), s.span() ) );
+                }
+            }
+
+            has_url = true;
+        }
+        else if nv.path.is_ident( "middleware" )
+        {
+            if let Expr::Array( a ) = &mut nv.value
+            {
+                let own = std::mem::take( &mut a.elems );
+
+                let array : ExprArray = syn::parse_quote! { [ #( #middleware, )* #own ] };
+
+                a.elems = array.elems;
+            }
+
+            has_middleware = true;
+        }
+    }
+
+    if ! has_url && url_key == "prefix"
+    {
+        args.push( syn::parse_quote! { prefix = #prefix } );
+    }
+
+    if ! has_middleware && ! middleware.is_empty()
+    {
+        args.push( syn::parse_quote! { middleware = [ #( #middleware ),* ] } );
+    }
+
+    list.tokens = quote! { #args };
+}
+
+fn join_url( prefix : &str, url : &str ) -> String
+{
+    let prefix = prefix.trim_end_matches( '/' );
+
+    let url = url.trim_start_matches( '/' );
+
+    match ( prefix.is_empty(), url.is_empty() )
+    {
+        ( true, _ ) => format!( "/{}", url ),
+        ( false, true ) => prefix.to_string(),
+        _ => format!( "{}/{}", prefix, url )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_url()
+    {
+        assert_eq!( join_url( "/api/v1", "/users" ), "/api/v1/users" );
+        assert_eq!( join_url( "/api/v1/", "users" ), "/api/v1/users" );
+        assert_eq!( join_url( "/api/v1", "/" ), "/api/v1" );
+        assert_eq!( join_url( "", "/users" ), "/users" );
+    }
+
+    #[test]
+    fn test_get_parent_path()
+    {
+        let paths : Vec<syn::Path> = vec![
+            syn::parse_quote! { auth },
+            syn::parse_quote! { self::auth },
+            syn::parse_quote! { super::auth },
+            syn::parse_quote! { crate::auth },
+            syn::parse_quote! { ::other::auth }
+        ];
+
+        let paths = paths.iter().map( | p | get_parent_path( p ).to_token_stream().to_string() ).collect::<Vec<String>>();
+
+        assert_eq!( paths, vec![ "super :: auth", "super :: auth", "super :: super :: auth", "crate :: auth", ":: other :: auth" ] );
+    }
+
+    #[test]
+    fn test_scope_attribute()
+    {
+        let middleware : Vec<syn::Path> = vec![ syn::parse_quote! { auth } ];
+
+        let mut attr : Attribute = syn::parse_quote! { #[get( url = "/users/{id}", middleware = [ audit ] )] };
+
+        scope_attribute( &mut attr, "url", "/api", &middleware );
+
+        assert_eq!( attr.to_token_stream().to_string(), quote! { #[get( url = "/api/users/{id}", middleware = [ auth, audit ] )] }.to_string() );
+
+        let mut attr : Attribute = syn::parse_quote! { #[scope( prefix = "/v1" )] };
+
+        scope_attribute( &mut attr, "prefix", "/api", &middleware );
+
+        assert_eq!( attr.to_token_stream().to_string(), quote! { #[scope( prefix = "/api/v1", middleware = [ auth ] )] }.to_string() );
+    }
+}
@@ -0,0 +1,99 @@
+use darling::FromMeta;
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{spanned::Spanned, FnArg, ItemFn, ReturnType};
+
+use crate::util::utils::{get_attributes, PathArray, StringArray};
+
+use super::methods::methods::{get_middlewares, get_variable, get_variable_attribute};
+
+// The parameters are extracted before the upgrade and moved into the socket handler,
+// so the ones that borrow the `IO` or change the response can't be used.
+const WEBSOCKET_VARIABLES : [ &str; 5 ] = [ "path_variable", "query_param", "query_params", "request_headers", "request_cookies" ];
+
+#[derive(FromMeta)]
+struct MacroWebSocketData
+{
+    url : String,
+    case_insensitive : Option<bool>,
+    middleware : Option<PathArray>,
+    protocols : Option<StringArray>
+}
+
+pub fn websocket_impl( args: TokenStream, item: TokenStream ) -> TokenStream
+{
+    let MacroWebSocketData { url, case_insensitive, middleware, protocols } = match get_attributes( args ) {
+        Ok( v ) => v,
+        Err( e ) => return e
+    };
+
+    let ItemFn {
+        block,
+        attrs,
+        sig,
+        vis
+
+    } = syn::parse_macro_input!( item as ItemFn );
+
+    if let ReturnType::Type( _, ty ) = &sig.output
+    {
+        return syn::Error::new( ty.span(), "websocket endpoints can't return a value" ).to_compile_error().into()
+    }
+
+    let mut variables = quote! {};
+    let mut socket = None;
+
+    for input in sig.inputs.iter()
+    {
+        let FnArg::Typed( argument ) = input else { continue };
+
+        match get_variable_attribute( argument )
+        {
+            Some( from ) if WEBSOCKET_VARIABLES.contains( &from.as_str() ) => variables.extend( get_variable( input, &sig, &url ).0 ),
+            Some( from ) => return syn::Error::new( argument.span(), format!( "#[{}] can't be used in websocket endpoints", from ) ).to_compile_error().into(),
+            None if socket.is_none() => socket = Some( ( argument.pat.clone(), argument.ty.clone() ) ),
+            None => return syn::Error::new( argument.span(), "websocket endpoints only have one parameter without attribute, the WebSocket" ).to_compile_error().into()
+        }
+    }
+
+    let Some( ( socket_pat, socket_ty ) ) = socket else {
+        return syn::Error::new( sig.ident.span(), "websocket endpoints need a WebSocket parameter" ).to_compile_error().into()
+    };
+
+    let protocols = protocols.map( | p | p.0 ).unwrap_or_default();
+
+    let case_insensitive = case_insensitive.unwrap_or( false );
+
+    let middlewares = get_middlewares( middleware );
+
+    let new_ident = sig.ident;
+
+    let handler_name = new_ident.to_string();
+
+    quote! {
+
+        #(#attrs)*
+        #vis fn #new_ident( mut __io : awpak_rs::io::io::IO ) -> awpak_rs::endpoint::types::EndpointReturnType
+        {
+            std::boxed::Box::pin( async move {
+
+                #variables
+
+                let __upgrade = match awpak_rs::WebSocketUpgrade::from_io( &mut __io, &[ #( #protocols ),* ] )
+                {
+                    Ok( v ) => v,
+                    Err( e ) => return Ok( __io.with_error( e ) )
+                };
+
+                Ok( __upgrade.on_upgrade( __io, move | #socket_pat : #socket_ty | async move #block ) )
+
+            } )
+        }
+
+        awpak_rs::inventory::submit! {
+            awpak_rs::endpoint::endpoint::Endpoint::new( #url, "get", | __awpak_rs_param_a | { #new_ident( __awpak_rs_param_a ) }, #case_insensitive )
+            .with_source( concat!( module_path!(), "::", #handler_name ), file!(), line!() )
+            #middlewares
+        }
+    }.into()
+}
@@ -1,4 +1,4 @@
-use impls::{awpak_main::awpak_main_impl, from_value::from_value_impl, methods::{connect::connect_impl, delete::delete_impl, get::get_impl, head::head_impl, options::options_impl, patch::patch_impl, post::post_impl, put::put_impl, trace::trace_impl}, middleware::middleware_impl, redirect_to::redirect_to_impl, set_status_code::set_status_code_impl};
+use impls::{awpak_main::awpak_main_impl, body_codec::body_codec_impl, error_handler::error_handler_impl, from_value::from_value_impl, methods::{connect::connect_impl, delete::delete_impl, get::get_impl, head::head_impl, options::options_impl, patch::patch_impl, post::post_impl, put::put_impl, trace::trace_impl}, middleware::middleware_impl, redirect_to::redirect_to_impl, scope::scope_impl, set_status_code::set_status_code_impl, websocket::websocket_impl};
 use proc_macro::TokenStream;
 use quote::quote;
 
@@ -18,6 +18,16 @@ mod util;
 /// - `port` *(optional, default: `"3000"`)*  
 ///   Specifies the port on which the server will listen.
 ///
+/// - `max_body` *(optional, default: `"2MB"`)*  
+///   Maximum size of a request body, like `max_body = "10MB"`. The units are `B`, `KB`, `MB` and `GB`, in multiples of 1024.
+///   Endpoints can change it with their own `max_body`.
+///   A larger body is rejected with `413 Payload Too Large`: before reading it if the `Content-Length` is over the limit
+///   (so a client that sends `Expect: 100-continue` doesn't upload it), or as soon as the limit is crossed while reading.
+///
+///   **Behavior change:** request bodies used to be unlimited. They are now limited to 2MB by default,
+///   so an application that receives larger bodies must raise the limit. `max_body = "unlimited"`
+///   restores unlimited bodies, for the server or for a single endpoint.
+///
 /// # Example
 ///
 /// ```ignore
@@ -27,6 +37,11 @@ mod util;
 ///
 /// In this example, the web server will start on `127.0.0.1:3001`.  
 /// If no parameters are provided, the server will default to `127.0.0.1:3000`.
+///
+/// Before the server starts, the routes of all endpoints and the `urls` patterns of all middlewares
+/// are validated. `main` returns an error if a url or a pattern is invalid, or if two endpoints with the same method match the same requests,
+/// like `#[get(url = "/user/{id}")]` and `#[get(url = "/user/{name}")]`.
+/// The route table can be listed at runtime with `awpak_rs::routes()`.
 #[proc_macro_attribute]
 pub fn awpak_main( args: TokenStream, item: TokenStream ) -> TokenStream
 {
@@ -50,6 +65,29 @@ pub fn awpak_main( args: TokenStream, item: TokenStream ) -> TokenStream
 /// - `url` *(required)*  
 ///   The URL pattern for this endpoint.
 ///
+/// - `case_insensitive` *(optional, default: `false`)*  
+///   If `true`, the static segments of the URL are matched ignoring case.
+///
+/// - `middleware` *(optional)*  
+///   Functions annotated with `#[middleware]` that only run for this endpoint, like `middleware = [check_admin, rate_limit]`.
+///   See the `middleware` macro.
+///
+/// - `max_body` *(optional, default: the limit of the server)*  
+///   Maximum size of the request body, like `max_body = "10MB"`. The units are `B`, `KB`, `MB` and `GB`. `"unlimited"` removes the limit.
+///   A larger body is rejected with `413 Payload Too Large`. See the `awpak_main` macro.
+///
+/// - `produces` *(optional)*  
+///   Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The content type of the response is the one with the highest quality in the `Accept` header,
+///   chosen before the endpoint runs. Types without a serializer need a raw body, like `Bytes`.
+///
+/// - `not_acceptable` *(optional, default: `true`)*  
+///   If the client accepts none of the `produces` types, the request is rejected with `406 Not Acceptable`
+///   and the endpoint doesn't run. With `not_acceptable = false`, the first type of `produces` is used instead.
+///
+/// - `xml_root` *(optional, default: `"response"`)*  
+///   Name of the root element of the XML responses, like `xml_root = "user"`. Needs the `xml` feature of `awpak-rs`.
+///
 /// # Example
 ///
 /// ```ignore
@@ -73,6 +111,22 @@ pub fn post( args: TokenStream, item: TokenStream ) -> TokenStream
 /// 
 /// The return type of the function can be any Rust primitive or any type that implements `Serialize` from `serde`.
 ///
+/// It can also be a `Result<T, E>`, where `E` implements `awpak_rs::IntoErrorResponse` (like `awpak_rs::Error`,
+/// `String` or `&'static str`). Other error types fail to compile; see `IntoErrorResponse` to migrate them.
+/// `Ok(T)` is sent as any other return value, and `Err(E)` builds the response of the error.
+/// The `?` operator can be used in the function.
+///
+/// To set the status, headers or cookies from the return value, return `awpak_rs::Response<T>`,
+/// `Created<T>`, `NoContent` or `Redirect` (also as the `T` of a `Result`). Any type that implements
+/// `awpak_rs::IntoResponse` sets the whole response, and the other types are serialized as the body,
+/// so a type of the application named like one of these is serialized as usual.
+///
+/// `Vec<u8>`, `awpak_rs::Bytes` and `awpak_rs::Binary` are sent verbatim, not serialized.
+/// The content type of `Binary` is its own. For `Vec<u8>` and `Bytes`, it's the `content-type` set in the response headers,
+/// or `application/octet-stream`.
+///
+/// `awpak_rs::ByteStream` and `awpak_rs::Sse` (Server-Sent Events) are sent in chunks, as their streams produce them.
+///
 /// # Asynchronous Execution
 ///
 /// Functions annotated with `get` are executed asynchronously.  
@@ -84,6 +138,29 @@ pub fn post( args: TokenStream, item: TokenStream ) -> TokenStream
 /// - `url` *(required)*  
 ///   The URL pattern for this endpoint.
 ///
+/// - `case_insensitive` *(optional, default: `false`)*  
+///   If `true`, the static segments of the URL are matched ignoring case.
+///
+/// - `middleware` *(optional)*  
+///   Functions annotated with `#[middleware]` that only run for this endpoint, like `middleware = [check_admin, rate_limit]`.
+///   See the `middleware` macro.
+///
+/// - `max_body` *(optional, default: the limit of the server)*  
+///   Maximum size of the request body, like `max_body = "10MB"`. The units are `B`, `KB`, `MB` and `GB`. `"unlimited"` removes the limit.
+///   A larger body is rejected with `413 Payload Too Large`. See the `awpak_main` macro.
+///
+/// - `produces` *(optional)*  
+///   Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The content type of the response is the one with the highest quality in the `Accept` header,
+///   chosen before the endpoint runs. Types without a serializer need a raw body, like `Bytes`.
+///
+/// - `not_acceptable` *(optional, default: `true`)*  
+///   If the client accepts none of the `produces` types, the request is rejected with `406 Not Acceptable`
+///   and the endpoint doesn't run. With `not_acceptable = false`, the first type of `produces` is used instead.
+///
+/// - `xml_root` *(optional, default: `"response"`)*  
+///   Name of the root element of the XML responses, like `xml_root = "user"`. Needs the `xml` feature of `awpak-rs`.
+///
 /// # Example
 ///
 /// ```ignore
@@ -94,6 +171,9 @@ pub fn post( args: TokenStream, item: TokenStream ) -> TokenStream
 /// ```
 ///
 /// In this example, a request to `GET /get_echo` will return `"Hello, world!"`.
+///
+/// Unless a `head` endpoint is defined for the same URL, `HEAD` requests are served
+/// by this endpoint and the response is sent without body.
 #[proc_macro_attribute]
 pub fn get( args: TokenStream, item: TokenStream ) -> TokenStream
 {
@@ -113,6 +193,13 @@ pub fn get( args: TokenStream, item: TokenStream ) -> TokenStream
 /// 
 /// # Parameters
 /// - `url`: *(required)* The endpoint URL pattern.
+/// - `case_insensitive`: *(optional, default: `false`)* Match the static segments of the URL ignoring case.
+/// - `middleware`: *(optional)* Functions annotated with `#[middleware]` that only run for this endpoint.
+/// - `max_body`: *(optional)* Maximum size of the request body, like `max_body = "10MB"` or `"unlimited"`. A larger body is rejected with a `413`.
+/// - `produces`: *(optional)* Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The type is chosen with the `Accept` header, and the request is rejected with a `406` if none is acceptable.
+/// - `not_acceptable`: *(optional, default: `true`)* If `false`, the first type of `produces` is used instead of rejecting the request with a `406`.
+/// - `xml_root`: *(optional, default: `"response"`)* Name of the root element of the XML responses, with the `xml` feature.
 ///
 /// # Return Type
 /// The function's return type can be any Rust primitive or a type implementing `Serialize` from `serde`.
@@ -143,6 +230,13 @@ pub fn connect( args: TokenStream, item: TokenStream ) -> TokenStream
 /// 
 /// # Parameters
 /// - `url`: *(required)* The endpoint URL pattern.
+/// - `case_insensitive`: *(optional, default: `false`)* Match the static segments of the URL ignoring case.
+/// - `middleware`: *(optional)* Functions annotated with `#[middleware]` that only run for this endpoint.
+/// - `max_body`: *(optional)* Maximum size of the request body, like `max_body = "10MB"` or `"unlimited"`. A larger body is rejected with a `413`.
+/// - `produces`: *(optional)* Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The type is chosen with the `Accept` header, and the request is rejected with a `406` if none is acceptable.
+/// - `not_acceptable`: *(optional, default: `true`)* If `false`, the first type of `produces` is used instead of rejecting the request with a `406`.
+/// - `xml_root`: *(optional, default: `"response"`)* Name of the root element of the XML responses, with the `xml` feature.
 ///
 /// # Return Type
 /// The function's return type can be any Rust primitive or a type implementing `Serialize` from `serde`.
@@ -173,6 +267,13 @@ pub fn delete( args: TokenStream, item: TokenStream ) -> TokenStream
 /// 
 /// # Parameters
 /// - `url`: *(required)* The endpoint URL pattern.
+/// - `case_insensitive`: *(optional, default: `false`)* Match the static segments of the URL ignoring case.
+/// - `middleware`: *(optional)* Functions annotated with `#[middleware]` that only run for this endpoint.
+/// - `max_body`: *(optional)* Maximum size of the request body, like `max_body = "10MB"` or `"unlimited"`. A larger body is rejected with a `413`.
+/// - `produces`: *(optional)* Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The type is chosen with the `Accept` header, and the request is rejected with a `406` if none is acceptable.
+/// - `not_acceptable`: *(optional, default: `true`)* If `false`, the first type of `produces` is used instead of rejecting the request with a `406`.
+/// - `xml_root`: *(optional, default: `"response"`)* Name of the root element of the XML responses, with the `xml` feature.
 ///
 /// # Return Type
 /// The function's return type can be any Rust primitive or a type implementing `Serialize` from `serde`.
@@ -201,6 +302,13 @@ pub fn head( args: TokenStream, item: TokenStream ) -> TokenStream
 /// 
 /// # Parameters
 /// - `url`: *(required)* The endpoint URL pattern.
+/// - `case_insensitive`: *(optional, default: `false`)* Match the static segments of the URL ignoring case.
+/// - `middleware`: *(optional)* Functions annotated with `#[middleware]` that only run for this endpoint.
+/// - `max_body`: *(optional)* Maximum size of the request body, like `max_body = "10MB"` or `"unlimited"`. A larger body is rejected with a `413`.
+/// - `produces`: *(optional)* Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The type is chosen with the `Accept` header, and the request is rejected with a `406` if none is acceptable.
+/// - `not_acceptable`: *(optional, default: `true`)* If `false`, the first type of `produces` is used instead of rejecting the request with a `406`.
+/// - `xml_root`: *(optional, default: `"response"`)* Name of the root element of the XML responses, with the `xml` feature.
 ///
 /// # Return Type
 /// The function's return type can be any Rust primitive or a type implementing `Serialize` from `serde`.
@@ -212,6 +320,9 @@ pub fn head( args: TokenStream, item: TokenStream ) -> TokenStream
 ///     "Allowed methods: GET, POST".to_string()
 /// }
 /// ```
+///
+/// Without an `options` endpoint, `OPTIONS` requests to a known URL are answered automatically
+/// with a `204` status and an `Allow` header listing the methods registered for it.
 #[proc_macro_attribute]
 pub fn options( args: TokenStream, item: TokenStream ) -> TokenStream
 {
@@ -231,6 +342,13 @@ pub fn options( args: TokenStream, item: TokenStream ) -> TokenStream
 /// 
 /// # Parameters
 /// - `url`: *(required)* The endpoint URL pattern.
+/// - `case_insensitive`: *(optional, default: `false`)* Match the static segments of the URL ignoring case.
+/// - `middleware`: *(optional)* Functions annotated with `#[middleware]` that only run for this endpoint.
+/// - `max_body`: *(optional)* Maximum size of the request body, like `max_body = "10MB"` or `"unlimited"`. A larger body is rejected with a `413`.
+/// - `produces`: *(optional)* Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The type is chosen with the `Accept` header, and the request is rejected with a `406` if none is acceptable.
+/// - `not_acceptable`: *(optional, default: `true`)* If `false`, the first type of `produces` is used instead of rejecting the request with a `406`.
+/// - `xml_root`: *(optional, default: `"response"`)* Name of the root element of the XML responses, with the `xml` feature.
 ///
 /// # Return Type
 /// The function's return type can be any Rust primitive or a type implementing `Serialize` from `serde`.
@@ -261,6 +379,13 @@ pub fn patch( args: TokenStream, item: TokenStream ) -> TokenStream
 ///
 /// # Parameters
 /// - `url`: *(required)* The endpoint URL pattern.
+/// - `case_insensitive`: *(optional, default: `false`)* Match the static segments of the URL ignoring case.
+/// - `middleware`: *(optional)* Functions annotated with `#[middleware]` that only run for this endpoint.
+/// - `max_body`: *(optional)* Maximum size of the request body, like `max_body = "10MB"` or `"unlimited"`. A larger body is rejected with a `413`.
+/// - `produces`: *(optional)* Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The type is chosen with the `Accept` header, and the request is rejected with a `406` if none is acceptable.
+/// - `not_acceptable`: *(optional, default: `true`)* If `false`, the first type of `produces` is used instead of rejecting the request with a `406`.
+/// - `xml_root`: *(optional, default: `"response"`)* Name of the root element of the XML responses, with the `xml` feature.
 ///
 /// # Return Type
 /// The function's return type can be any Rust primitive or a type implementing `Serialize` from `serde`.
@@ -291,6 +416,13 @@ pub fn put( args: TokenStream, item: TokenStream ) -> TokenStream
 /// 
 /// # Parameters
 /// - `url`: *(required)* The endpoint URL pattern.
+/// - `case_insensitive`: *(optional, default: `false`)* Match the static segments of the URL ignoring case.
+/// - `middleware`: *(optional)* Functions annotated with `#[middleware]` that only run for this endpoint.
+/// - `max_body`: *(optional)* Maximum size of the request body, like `max_body = "10MB"` or `"unlimited"`. A larger body is rejected with a `413`.
+/// - `produces`: *(optional)* Media types of the responses, in order of preference, like `produces = ["application/json", "text/plain"]`.
+///   The type is chosen with the `Accept` header, and the request is rejected with a `406` if none is acceptable.
+/// - `not_acceptable`: *(optional, default: `true`)* If `false`, the first type of `produces` is used instead of rejecting the request with a `406`.
+/// - `xml_root`: *(optional, default: `"response"`)* Name of the root element of the XML responses, with the `xml` feature.
 ///
 /// # Return Type
 /// The function's return type can be any Rust primitive or a type implementing `Serialize` from `serde`.
@@ -331,6 +463,56 @@ pub fn request_body( _args : TokenStream ) -> TokenStream
     quote! {}.into()
 }
 
+/// The `raw_body` macro gives the exact bytes of the request body, before they are parsed.
+///
+/// This macro should be applied to a function parameter of type `awpak_rs::Bytes` or `Vec<u8>`.
+/// It can be used with other body extractors, like `request_body`, since the body is parsed anyway.
+///
+/// # Example
+///
+/// ```ignore
+/// #[post(url = "/webhook")]
+/// fn webhook(#[request_headers] headers: Headers, #[raw_body] bytes: Bytes) -> bool {
+///     verify_signature(headers.get_value("x-signature"), &bytes)
+/// }
+/// ```
+#[proc_macro]
+pub fn raw_body( _args : TokenStream ) -> TokenStream
+{
+    quote! {}.into()
+}
+
+/// The `body_stream` macro gives the request body as an async stream of chunks, as they arrive.
+///
+/// This macro should be applied to a function parameter of type `awpak_rs::RequestBodyStream`,
+/// a `Stream` of `Result<Bytes, awpak_rs::Error>`.
+///
+/// The body of the endpoint is not buffered, so it can't be used with the other body extractors
+/// (`request_body`, `body_param`, `raw_body`, `part_file` and `part_files`),
+/// and `io.request.body` is empty in the middlewares.
+///
+/// # Example
+///
+/// ```ignore
+/// use futures::StreamExt;
+///
+/// #[post(url = "/upload")]
+/// async fn upload(#[body_stream] mut body: RequestBodyStream) -> usize {
+///     let mut len = 0;
+///
+///     while let Some(Ok(chunk)) = body.next().await {
+///         len += chunk.len();
+///     }
+///
+///     len
+/// }
+/// ```
+#[proc_macro]
+pub fn body_stream( _args : TokenStream ) -> TokenStream
+{
+    quote! {}.into()
+}
+
 /// The `query_params` macro deserializes multiple query parameters into a struct.
 ///
 /// This macro should be applied to a function parameter that implements `Deserialize` and `FromValue`.
@@ -442,6 +624,33 @@ pub fn body_param( _args : TokenStream ) -> TokenStream
 ///
 /// A request like `GET /user/john` will return `"john"`.
 ///
+/// The value is percent-decoded before the conversion, so `GET /user/john%20doe` will return `"john doe"`.
+///
+/// A segment can restrict its values with a type (`{id:u64}`, `{n:i32}`, `{flag:bool}`...) or
+/// with a regular expression that must match the whole segment (`{code:[A-Z]{3}}`).
+/// If the value does not satisfy the constraint, the next candidate route is tried:
+///
+/// ```ignore
+/// #[get(url = "/item/{id:u64}")]
+/// fn get_item_by_id(#[path_variable] id: u64) -> u64 { id }
+///
+/// #[get(url = "/item/{slug}")]
+/// fn get_item_by_slug(#[path_variable] slug: String) -> String { slug }
+/// ```
+///
+/// `GET /item/33` is served by `get_item_by_id` and `GET /item/abc` by `get_item_by_slug`.
+///
+/// A `{*name}` segment, which must be the last one, matches the rest of the path.
+/// It can be received as a `String` or as a `Vec<String>` with one item per segment:
+///
+/// ```ignore
+/// #[get(url = "/static/{*path}")]
+/// fn static_file(#[path_variable] path: String) -> String { path }
+/// ```
+///
+/// `GET /static/css/main.css` will return `"css/main.css"`. Invalid constraints and wildcards
+/// that are not the last segment make the server fail at startup.
+///
 /// You can also deserialize the path variable into a more complex type that
 /// implements `FromAsyncStr`:
 ///
@@ -697,6 +906,67 @@ pub fn derive_from_value( item : TokenStream ) -> TokenStream
 /// - `method`: *(optional, default: all methods)*  
 ///   Restricts the middleware to a specific HTTP method (e.g., `method = "get"`).
 ///
+/// - `around`: *(optional, default: `false`)*  
+///   If present, the middleware wraps the rest of the chain instead of running before or after it.
+///   See "Around middlewares" below.
+///
+/// - `global`: *(optional, default: `true`)*  
+///   If `false`, the middleware only runs for the endpoints that attach it by name with
+///   `middleware = [...]`, in the route macro or in a `scope`. A middleware can only be attached if it has `global = false`.
+///
+/// # Around middlewares
+///
+/// A middleware declared with `#[middleware(around)]` receives the `IO` and a `Next` handle
+/// with the rest of the chain: the following `around` middlewares and the endpoint.
+/// It returns `Result<IO, (IO, Error)>`, usually the result of `next.run(io).await`, which it can inspect or replace.
+/// If the endpoint fails, `next.run(io)` returns `Ok` with the error in `io.error`.
+///
+/// A failing `around` middleware returns `Err` with its `IO` and the error, so the request and the context
+/// still reach the error handlers and the POST middlewares. `?` forwards the `Err` of `next.run(io)`.
+///
+/// ```ignore
+/// #[middleware(around, urls = ["/api/.*"])]
+/// fn require_key(io: IO, next: Next) -> Result<IO, (IO, Error)> {
+///     if io.request.headers.get_value("x-api-key").is_none() {
+///         return Err((io, Error::EndpointExecution("Missing API key".to_string())));
+///     }
+///     let mut io = next.run(io).await?;
+///     io.response.headers.replace_header("x-api".to_string(), "1".to_string());
+///     Ok(io)
+/// }
+/// ```
+///
+/// Global `around` middlewares run, sorted by `order`, after the PRE middlewares and before the POST middlewares.
+/// Attached `around` middlewares wrap only the endpoint, after the attached PRE middlewares.
+/// `execute_after` can't be used with `around`.
+///
+/// # Attaching a middleware to endpoints
+///
+/// Route macros and `scope` accept a list of middleware functions, referenced by name or path:
+///
+/// ```ignore
+/// #[middleware(global = false)]
+/// fn check_admin(mut io: IO) -> MiddlewareResponse {
+///     MiddlewareResponse::Next(io)
+/// }
+///
+/// #[middleware(global = false, execute_after = true)]
+/// fn audit(mut io: IO) -> MiddlewareResponse {
+///     MiddlewareResponse::Next(io)
+/// }
+///
+/// #[delete(url = "/user/{id}", middleware = [check_admin, audit])]
+/// fn delete_user(#[path_variable] id: u64) {}
+/// ```
+///
+/// Attached middlewares run in the order they are listed, before or after the endpoint according to
+/// their own `execute_after`: global PRE middlewares, `check_admin`, `delete_user`, `audit`, global POST middlewares.
+/// Their `urls` and `method` filters still apply. If an attached middleware returns `Cancel`,
+/// the endpoint is skipped, but the global POST middlewares still run.
+///
+/// A name that is not a function annotated with `#[middleware]` is a compile error, and so is a middleware
+/// without `global = false`, because a global middleware already runs for every endpoint and would run twice.
+///
 /// # Middleware Response
 ///
 /// Middleware functions must return a `MiddlewareResponse`, which can be:
@@ -735,6 +1005,217 @@ pub fn middleware( args: TokenStream, item: TokenStream ) -> TokenStream
     middleware_impl( args, item )
 }
 
+/// The `error_handler` macro registers a function that builds the response of a failed request.
+///
+/// The function receives the `IO` of the request, with the error in `io.error`, and returns the `IO` to send.
+/// The status, headers and body of the response are already set from the error
+/// (the body is `"Server Error"`, or the message of the error for a `BadRequest`, a `NotAcceptable` or a `PayloadTooLarge`), so the handler only changes what it needs.
+/// POST middlewares run after the error handler.
+///
+/// # Parameters
+///
+/// - `error`: *(optional)*  
+///   A variant of `awpak_rs::Error`, like `BadRequest`. The handler runs for that variant only.
+///
+/// - `status`: *(optional)*  
+///   A status code, like `404`. The handler runs for the errors with that status only.
+///
+/// Without parameters, the handler runs for every error.
+/// A handler for the variant of the error is preferred over one for its status, and both over one for every error.
+/// Two handlers for the same variant, for the same status or for every error make `awpak_main` fail at startup
+/// with an `ErrorHandlerConflict`.
+///
+/// The server doesn't log the errors of the requests. An error handler can log the errors it handles.
+///
+/// # Example
+///
+/// ```ignore
+/// #[error_handler(status = 404)]
+/// fn not_found(mut io: IO) -> IO {
+///     io.response.body = Some(Value::from("Nothing here").into());
+///     io
+/// }
+///
+/// #[error_handler]
+/// fn log_errors(io: IO) -> IO {
+///     eprintln!("{} {}: {:?}", io.request.method, io.request.uri.path, io.error);
+///     io
+/// }
+/// ```
+///
+/// # Problem details
+///
+/// `awpak_rs::problem_details` replaces the response with an `application/problem+json` document (RFC 7807)
+/// with `type`, `title`, `status`, `detail` and `instance`. `detail` is the message of an error caused by the client,
+/// like `"Body param error: x: invalid digit found in string"` for a `BadRequest`. Server errors have no `detail`.
+///
+/// ```ignore
+/// #[error_handler]
+/// fn problems(io: IO) -> IO {
+///     awpak_rs::problem_details(io)
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn error_handler( args: TokenStream, item: TokenStream ) -> TokenStream
+{
+    error_handler_impl( args, item )
+}
+
+/// Defines a WebSocket endpoint.
+///
+/// The endpoint is registered for `GET` requests, so middlewares, including endpoint and `scope` middlewares,
+/// run on the upgrade request like on any other request. A middleware that cancels the request,
+/// like a failed authentication, rejects the connection with its own response.
+///
+/// After the middlewares, the handshake is validated (`Upgrade`, `Connection`, `Sec-WebSocket-Version: 13`
+/// and `Sec-WebSocket-Key`) and the `101 Switching Protocols` response is sent with its `Sec-WebSocket-Accept`.
+/// An invalid handshake is answered with a `400` and the header that failed, like `"Header error: sec-websocket-version: expected 13"`.
+///
+/// The body of the function runs once the connection is upgraded, with an `awpak_rs::WebSocket`
+/// to `send` and `recv` text, binary, ping, pong and close messages (`awpak_rs::Message`).
+///
+/// # Parameters
+///
+/// - `url` *(required)*  
+///   The URL pattern for this endpoint.
+///
+/// - `case_insensitive` *(optional, default: `false`)*  
+///   If `true`, the static segments of the URL are matched ignoring case.
+///
+/// - `middleware` *(optional)*  
+///   Functions annotated with `#[middleware]` that only run for this endpoint. See the `middleware` macro.
+///
+/// - `protocols` *(optional)*  
+///   The subprotocols supported by the endpoint, like `protocols = ["chat.v2", "chat.v1"]`.
+///   The first protocol of the client's `Sec-WebSocket-Protocol` header that is supported is sent back,
+///   and is available with `WebSocket::protocol()`. If none is supported, the header is not sent.
+///
+/// # Function parameters
+///
+/// The function takes one parameter without attribute, the `WebSocket`. It can also take
+/// `#[path_variable]`, `#[query_param]`, `#[query_params]`, `#[request_headers]` and `#[request_cookies]` parameters,
+/// extracted before the handshake. The function can't return a value.
+///
+/// # Example
+///
+/// ```ignore
+/// #[websocket(url = "/chat/{room}", middleware = [auth], protocols = ["chat.v1"])]
+/// async fn chat(#[path_variable] room: String, mut socket: WebSocket) {
+///     while let Some(Ok(message)) = socket.recv().await {
+///         match message {
+///             Message::Text(text) => {
+///                 let _ = socket.send(Message::text(format!("{}: {}", room, text))).await;
+///             },
+///             Message::Close(_) => break,
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn websocket( args: TokenStream, item: TokenStream ) -> TokenStream
+{
+    websocket_impl( args, item )
+}
+
+/// Registers a body format for a media type, to read request bodies and write response bodies.
+///
+/// The macro is applied to a struct or an enum that implements `awpak_rs::Codec`.
+/// The media type joins the built-in ones (`text/plain`, `application/json`, ...):
+///
+/// - A request with that `Content-Type` is decoded with the codec, for `#[request_body]` and `#[body_param]`.
+///   Like the built-in types, the body is converted to UTF-8 before if the `Content-Type` has another `charset`.
+/// - The type can be chosen for the response by the `Accept` header, and is kept if an endpoint sets it
+///   in the `Content-Type` header or in `produces`. The value returned by the endpoint is encoded with the codec.
+///
+/// The media type can't be a built-in one, like `application/json`, or the one of another codec:
+/// `awpak_main` fails at startup.
+///
+/// # Parameters
+///
+/// - `mime` *(required)*  
+///   The media type, without parameters, like `mime = "application/x-protobuf"`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[body_codec(mime = "application/x-lines")]
+/// struct Lines;
+///
+/// impl Codec for Lines {
+///     fn decode(bytes: Bytes) -> Result<Value, Error> {
+///         let text = String::from_utf8(bytes.to_vec()).map_err(|e| Error::ParserError(e.to_string()))?;
+///
+///         Ok(Value::from(text.lines().collect::<Vec<&str>>()))
+///     }
+///
+///     fn encode(value: Value) -> Result<Bytes, Error> {
+///         let lines = Vec::<String>::deserialize(value).map_err(|e| Error::ParserError(e.to_string()))?;
+///
+///         Ok(lines.join("\n").into())
+///     }
+/// }
+///
+/// #[post(url = "/lines", produces = ["application/x-lines", "application/json"])]
+/// fn sort_lines(#[request_body] mut lines: Vec<String>) -> Vec<String> {
+///     lines.sort();
+///     lines
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn body_codec( args: TokenStream, item: TokenStream ) -> TokenStream
+{
+    body_codec_impl( args, item )
+}
+
+/// Groups the endpoints of an inline module under a common URL prefix and shared middlewares.
+///
+/// Every route macro (`get`, `post`, `websocket`, ...) inside the module, including nested modules,
+/// gets the prefix added to its `url` and the middlewares added before its own `middleware` list.
+/// Nested scopes are combined with the outer ones.
+///
+/// # Parameters
+///
+/// - `prefix`: *(optional, default: `""`)*  
+///   Prefix added to the URL of every endpoint in the module.
+///
+/// - `middleware`: *(optional)*  
+///   Functions annotated with `#[middleware]` that run for every endpoint in the module,
+///   in the order they are listed. They run before or after the endpoint according to their own `execute_after`,
+///   after the global PRE middlewares and before the global POST middlewares.
+///   They must be declared with `global = false`, or the scope fails to compile.
+///
+/// # Example
+///
+/// ```ignore
+/// #[middleware(global = false)]
+/// fn auth(mut io: IO) -> MiddlewareResponse {
+///     MiddlewareResponse::Next(io)
+/// }
+///
+/// #[scope(prefix = "/api/v1", middleware = [auth])]
+/// mod api {
+///     use awpak_rs::*;
+///
+///     #[get(url = "/users/{id}")]
+///     fn get_user(#[path_variable] id: u64) -> u64 {
+///         id
+///     }
+/// }
+/// ```
+///
+/// In this example, `get_user` is served at `GET /api/v1/users/{id}` and `auth` runs before it.
+///
+/// Middleware paths are resolved from the module where the `scope` attribute is written,
+/// so `auth` doesn't need to be imported inside `api`.
+///
+/// The macro can only be applied to inline modules (`mod api { ... }`).
+#[proc_macro_attribute]
+pub fn scope( args: TokenStream, item: TokenStream ) -> TokenStream
+{
+    scope_impl( args, item )
+}
+
 #[proc_macro]
 pub fn set_status_code( item : TokenStream ) -> TokenStream
 {
@@ -1,6 +1,7 @@
 
 use darling::{ast::NestedMeta, Error, FromMeta};
 use proc_macro::TokenStream;
+use syn::{Expr, ExprLit, Lit, Path};
 
 pub fn get_attributes<T>( args : TokenStream ) -> Result<T, TokenStream>
 where T: FromMeta
@@ -26,6 +27,74 @@ where T: FromMeta
     Ok( salida )
 }
 
+/// List of paths written as an array, like `middleware = [auth, crate::audit]`.
+pub struct PathArray( pub Vec<Path> );
+
+impl FromMeta for PathArray
+{
+    fn from_expr( expr : &Expr ) -> darling::Result<Self>
+    {
+        match expr
+        {
+            Expr::Array( a ) => a.elems.iter().map( | e | match e
+            {
+                Expr::Path( p ) => Ok( p.path.clone() ),
+                _ => Err( Error::custom( "Expected a function name" ).with_span( e ) )
+            } ).collect::<darling::Result<Vec<Path>>>().map( PathArray ),
+            _ => Err( Error::unexpected_expr_type( expr ) )
+        }
+    }
+}
+
+/// List of strings written as an array, like `protocols = ["chat.v1", "chat.v2"]`.
+pub struct StringArray( pub Vec<String> );
+
+impl FromMeta for StringArray
+{
+    fn from_expr( expr : &Expr ) -> darling::Result<Self>
+    {
+        match expr
+        {
+            Expr::Array( a ) => a.elems.iter().map( | e | match e
+            {
+                Expr::Lit( ExprLit { lit : Lit::Str( s ), .. } ) => Ok( s.value() ),
+                _ => Err( Error::custom( "Expected a string" ).with_span( e ) )
+            } ).collect::<darling::Result<Vec<String>>>().map( StringArray ),
+            _ => Err( Error::unexpected_expr_type( expr ) )
+        }
+    }
+}
+
+/// Parses a size like `"10MB"`, `"512 KB"` or `"1024"` into bytes.
+///
+/// The units are `B`, `KB`, `MB` and `GB`, in multiples of 1024, and are case insensitive.
+pub fn parse_size( size : &str ) -> Result<usize, String>
+{
+    let size = size.trim();
+
+    if size.eq_ignore_ascii_case( "unlimited" )
+    {
+        return Ok( usize::MAX )
+    }
+
+    let split = size.find( | c : char | ! c.is_ascii_digit() ).unwrap_or( size.len() );
+
+    let ( number, unit ) = size.split_at( split );
+
+    let number = number.parse::<usize>().map_err( | _ | format!( "Invalid size: {}", size ) )?;
+
+    let multiplier : usize = match unit.trim().to_uppercase().as_str()
+    {
+        "" | "B" => 1,
+        "KB" => 1024,
+        "MB" => 1024 * 1024,
+        "GB" => 1024 * 1024 * 1024,
+        _ => return Err( format!( "Invalid size unit: {}. Expected B, KB, MB or GB", unit.trim() ) )
+    };
+
+    number.checked_mul( multiplier ).ok_or( format!( "Size too large: {}", size ) )
+}
+
 // pub fn get_params_fn( item_fn : &ItemFn, span : Option<Span> ) -> ( proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream )
 // {
 //     let types_fn = item_fn.sig.inputs.iter().map(|param|{
@@ -119,3 +188,22 @@ where T: FromMeta
 
 //     re.is_match( ty.as_str() )
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size()
+    {
+        assert_eq!( parse_size( "1024" ), Ok( 1024 ) );
+        assert_eq!( parse_size( "10B" ), Ok( 10 ) );
+        assert_eq!( parse_size( "512 KB" ), Ok( 512 * 1024 ) );
+        assert_eq!( parse_size( "10MB" ), Ok( 10 * 1024 * 1024 ) );
+        assert_eq!( parse_size( "1gb" ), Ok( 1024 * 1024 * 1024 ) );
+        assert!( parse_size( "MB" ).is_err() );
+        assert!( parse_size( "10TB" ).is_err() );
+        assert!( parse_size( "1.5MB" ).is_err() );
+        assert_eq!( parse_size( "unlimited" ), Ok( usize::MAX ) );
+    }
+}
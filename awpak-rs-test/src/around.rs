@@ -0,0 +1,82 @@
+use awpak_rs::{get, io::io::IO, middleware, path_variable, Error, MiddlewareResponse, Next};
+
+#[middleware( around, urls = [ "/around/.*" ], order = 1 )]
+fn around_outer( mut io : IO, next : Next ) -> Result<IO, ( IO, Error )>
+{
+    io.response.headers.replace_header( "x-around".to_string(), "outer".to_string() );
+
+    let mut io = next.run( io ).await?;
+
+    let trace = format!( "{},outer", io.response.headers.get_value( "x-around" ).cloned().unwrap_or_default() );
+
+    io.response.headers.replace_header( "x-around".to_string(), trace );
+
+    Ok( io )
+}
+
+#[middleware( around, urls = [ "/around/error/.*" ], order = 2 )]
+fn around_recover( io : IO, next : Next ) -> Result<IO, ( IO, Error )>
+{
+    let mut io = next.run( io ).await?;
+
+    if io.error.take().is_some()
+    {
+        io.response.status = 503;
+        io.response.body = Some( awpak_rs::Value::from( "recovered" ).into() );
+    }
+
+    Ok( io )
+}
+
+#[middleware( around, global = false )]
+fn around_attached( io : IO, next : Next ) -> Result<IO, ( IO, Error )>
+{
+    let mut io = next.run( io ).await?;
+
+    io.response.status = 202;
+
+    Ok( io )
+}
+
+#[middleware( around, urls = [ "^/around/reject$" ], order = 3 )]
+fn around_reject( io : IO, _next : Next ) -> Result<IO, ( IO, Error )>
+{
+    Err( ( io, Error::EndpointExecution( "Rejected".to_string() ) ) )
+}
+
+#[middleware( urls = [ "^/around/reject$" ], execute_after = true )]
+fn around_reject_path( mut io : IO ) -> MiddlewareResponse
+{
+    let path = io.request.uri.path.clone();
+
+    io.response.headers.replace_header( "x-path".to_string(), path );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[get( url = "/around/hello" )]
+fn around_hello() -> String
+{
+    "hello".to_string()
+}
+
+#[get( url = "/around/error/{id}" )]
+fn around_error(
+    #[path_variable]
+    id : u8
+) -> u8
+{
+    id
+}
+
+#[get( url = "/around/attached", middleware = [ around_attached ] )]
+fn around_attached_endpoint() -> String
+{
+    "attached".to_string()
+}
+
+#[get( url = "/around/reject" )]
+fn around_reject_endpoint() -> String
+{
+    "not rejected".to_string()
+}
@@ -0,0 +1,138 @@
+use awpak_rs::{io::{headers::headers::Headers, io::IO}, response_headers, middleware, post, raw_body, body_param, body_stream, request_body, Bytes, FromValue, MiddlewareResponse, RequestBodyStream};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::Point;
+
+#[middleware( urls = [ "/bodies/.*" ] )]
+fn bodies_buffered( mut io : IO ) -> MiddlewareResponse
+{
+    let buffered = io.request.body.raw.is_some().to_string();
+
+    io.response.headers.replace_header( "x-buffered".to_string(), buffered );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[post( url = "/bodies/raw" )]
+fn bodies_raw(
+    #[raw_body]
+    bytes : Bytes
+) -> Bytes
+{
+    bytes
+}
+
+#[post( url = "/bodies/raw_and_json" )]
+fn bodies_raw_and_json(
+    #[raw_body]
+    raw : Vec<u8>,
+    #[request_body]
+    point : Point
+) -> String
+{
+    format!( "{} bytes, y={}", raw.len(), point.y )
+}
+
+#[post( url = "/bodies/stream" )]
+async fn bodies_stream(
+    #[body_stream]
+    mut body : RequestBodyStream
+) -> usize
+{
+    let mut len = 0;
+
+    while let Some( Ok( chunk ) ) = body.next().await
+    {
+        len += chunk.len();
+    }
+
+    len
+}
+
+#[post( url = "/bodies/limited", max_body = "1KB" )]
+fn bodies_limited(
+    #[raw_body]
+    bytes : Bytes
+) -> usize
+{
+    bytes.len()
+}
+
+#[post( url = "/bodies/stream_limited", max_body = "1KB" )]
+async fn bodies_stream_limited(
+    #[body_stream]
+    mut body : RequestBodyStream
+) -> String
+{
+    let mut len = 0;
+
+    while let Some( chunk ) = body.next().await
+    {
+        match chunk
+        {
+            Ok( c ) => len += c.len(),
+            Err( e ) => return e.to_string()
+        }
+    }
+
+    len.to_string()
+}
+
+#[post( url = "/bodies/form/params" )]
+fn bodies_form_params(
+    #[body_param]
+    name : String,
+    #[body_param]
+    age : u8,
+    #[body_param]
+    tag : Vec<String>
+) -> String
+{
+    format!( "{} ({}): {}", name, age, tag.join( ", " ) )
+}
+
+#[derive(Serialize, Deserialize, FromValue)]
+pub struct FormAddress
+{
+    city : String
+}
+
+#[derive(Serialize, Deserialize, FromValue)]
+pub struct FormUser
+{
+    name : String,
+    address : FormAddress,
+    roles : Vec<String>
+}
+
+#[post( url = "/bodies/form/nested" )]
+fn bodies_form_nested(
+    #[body_param]
+    user : FormUser
+) -> FormUser
+{
+    user
+}
+
+#[post( url = "/bodies/charset/point" )]
+fn bodies_charset_point(
+    #[request_body]
+    point : Point
+) -> String
+{
+    format!( "x={}, y={}", point.x.unwrap_or_default(), point.y )
+}
+
+#[post( url = "/bodies/charset/text" )]
+fn bodies_charset_text(
+    #[request_body]
+    text : String,
+    #[response_headers]
+    mut headers : Headers
+) -> String
+{
+    headers.replace_header( "content-type".to_string(), "text/plain; charset=ISO-8859-1".to_string() );
+
+    text
+}
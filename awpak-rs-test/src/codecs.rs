@@ -0,0 +1,44 @@
+use awpak_rs::{body_codec, post, request_body, Bytes, Codec, Error, Value};
+use serde::Deserialize;
+
+#[body_codec( mime = "application/x-list" )]
+pub struct List;
+
+impl Codec for List
+{
+    fn decode( bytes : Bytes ) -> Result<Value, Error>
+    {
+        let text = String::from_utf8( bytes.to_vec() ).map_err( | e | Error::ParserError( e.to_string() ) )?;
+
+        Ok( Value::from( text.split( "," ).map( | s | s.trim() ).collect::<Vec<&str>>() ) )
+    }
+
+    fn encode( value : Value ) -> Result<Bytes, Error>
+    {
+        let items = Vec::<String>::deserialize( value ).map_err( | e | Error::ParserError( e.to_string() ) )?;
+
+        Ok( items.join( "," ).into() )
+    }
+}
+
+#[post( url = "/codecs/sort" )]
+fn codecs_sort(
+    #[request_body]
+    mut items : Vec<String>
+) -> Vec<String>
+{
+    items.sort();
+
+    items
+}
+
+#[post( url = "/codecs/produces", produces = [ "application/x-list", "application/json" ] )]
+fn codecs_produces(
+    #[request_body]
+    mut items : Vec<String>
+) -> Vec<String>
+{
+    items.reverse();
+
+    items
+}
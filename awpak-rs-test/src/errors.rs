@@ -0,0 +1,173 @@
+use awpak_rs::{error_handler, get, io::io::IO, middleware, path_variable, problem_details, query_param, query_params, Error, FromValue, IntoErrorResponse, MiddlewareResponse, Value};
+use serde::Deserialize;
+
+struct ErrorsContext
+{
+    value : String
+}
+
+#[middleware( urls = [ "/errors/.*" ] )]
+fn errors_set_context( mut io : IO ) -> MiddlewareResponse
+{
+    io.set_context( ErrorsContext { value : "from_context".to_string() } );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[middleware( urls = [ "/errors/.*" ], execute_after = true )]
+fn errors_inspect( mut io : IO ) -> MiddlewareResponse
+{
+    let error = match &io.error
+    {
+        Some( e ) => e.to_string(),
+        _ => "none".to_string()
+    };
+
+    let context = match io.get_context::<ErrorsContext>()
+    {
+        Some( c ) => c.value.clone(),
+        _ => "none".to_string()
+    };
+
+    let request = format!( "{} {}", io.request.method, io.request.uri.path );
+
+    io.response.headers.replace_header( "x-error".to_string(), error );
+    io.response.headers.replace_header( "x-error-context".to_string(), context );
+    io.response.headers.replace_header( "x-error-request".to_string(), request );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[get( url = "/errors/path_variable/{id}" )]
+fn errors_path_variable(
+    #[path_variable]
+    id : u8
+) -> u8
+{
+    id
+}
+
+#[get( url = "/errors/query_param" )]
+fn errors_query_param(
+    #[query_param]
+    y : f32
+) -> f32
+{
+    y
+}
+
+#[derive(Deserialize, FromValue)]
+struct ErrorsPoint
+{
+    x : i32,
+    y : i32
+}
+
+#[get( url = "/errors/query_params" )]
+fn errors_query_params(
+    #[query_params]
+    point : ErrorsPoint
+) -> i32
+{
+    point.x + point.y
+}
+
+#[get( url = "/errors/problem/{id}" )]
+fn errors_problem(
+    #[path_variable]
+    id : u8
+) -> u8
+{
+    id
+}
+
+#[error_handler( error = BadRequest )]
+fn errors_parser_handler( io : IO ) -> IO
+{
+    if ! io.request.uri.path.starts_with( "/errors/problem/" )
+    {
+        return io
+    }
+
+    problem_details( io )
+}
+
+#[error_handler( status = 404 )]
+fn errors_not_found_handler( mut io : IO ) -> IO
+{
+    if io.request.uri.path.starts_with( "/errors/handlers/" )
+    {
+        io.response.body = Some( Value::from( "Nothing here" ).into() );
+    }
+
+    io
+}
+
+enum ErrorsUserError
+{
+    NotFound( u8 )
+}
+
+impl IntoErrorResponse for ErrorsUserError
+{
+    fn into_error_response( self, mut io : IO ) -> IO
+    {
+        match self
+        {
+            ErrorsUserError::NotFound( id ) => {
+                io.response.status = 409;
+                io.response.body = Some( Value::from( format!( "User {} not found", id ) ).into() );
+            }
+        }
+
+        io
+    }
+}
+
+fn errors_find_user( id : u8 ) -> Result<String, ErrorsUserError>
+{
+    match id
+    {
+        1 => Ok( "user_1".to_string() ),
+        _ => Err( ErrorsUserError::NotFound( id ) )
+    }
+}
+
+#[get( url = "/errors/result/{id}" )]
+async fn errors_result(
+    #[path_variable]
+    id : u8
+) -> Result<String, ErrorsUserError>
+{
+    let user = errors_find_user( id )?;
+
+    Ok( format!( "found {}", user ) )
+}
+
+#[get( url = "/errors/result_error/{id}" )]
+async fn errors_result_error(
+    #[path_variable]
+    id : u8
+) -> Result<u8, Error>
+{
+    if id == 0
+    {
+        Err( Error::EndpointExecution( "Zero".to_string() ) )?;
+    }
+
+    Ok( id )
+}
+
+#[get( url = "/errors/result_string/{id}" )]
+fn errors_result_string(
+    #[path_variable]
+    id : u8
+) -> Result<u8, String>
+{
+    if id == 0
+    {
+        return Err( format!( "Zero {}", id ) )
+    }
+
+    Ok( id )
+}
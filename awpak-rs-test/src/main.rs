@@ -10,6 +10,15 @@ mod cookies;
 mod status_code;
 mod query_param;
 mod redirects;
+mod methods;
+mod scopes;
+mod around;
+mod errors;
+mod responses;
+mod websockets;
+mod bodies;
+mod negotiation;
+mod codecs;
 
 #[awpak_main( ip = "127.0.0.1", port = "3001" )]
 // #[awpak_main]
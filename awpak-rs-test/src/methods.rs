@@ -0,0 +1,34 @@
+use awpak_rs::{get, options, post};
+
+#[get( url = "/methods/get_only" )]
+fn methods_get_only() -> String
+{
+    "get_only".to_string()
+}
+
+#[post( url = "/methods/{id}/detail" )]
+fn methods_post_variable() -> String
+{
+    "post_variable".to_string()
+}
+
+#[get( url = "/methods/explicit_options" )]
+fn methods_explicit_options_get() -> String
+{
+    "get".to_string()
+}
+
+#[options( url = "/methods/explicit_options" )]
+fn methods_explicit_options() -> String
+{
+    "explicit_options".to_string()
+}
+
+#[get( url = "/methods/routes" )]
+fn methods_routes() -> Vec<String>
+{
+    awpak_rs::routes().into_iter()
+    .filter( | r | r.url.starts_with( "/methods/" ) )
+    .map( | r | format!( "{} {} {}", r.method, r.url, r.handler ) )
+    .collect()
+}
@@ -39,7 +39,7 @@ fn post_add_one_to_x_in_middleware(
 )]
 fn middleware_add_z( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 o.insert( "z".to_string(), awpak_rs::Value::from( 333 ) );
@@ -61,7 +61,7 @@ fn middleware_add_z( mut io : IO ) -> MiddlewareResponse
 )]
 fn middleware_add_one_to_x( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 let x = o.get_mut( "x" );
@@ -96,7 +96,7 @@ fn middleware_add_one_to_x( mut io : IO ) -> MiddlewareResponse
 )]
 fn middleware_regex_add_one_to_y( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 let y = o.get_mut( "y" );
@@ -150,7 +150,7 @@ fn add_one_to_x(
 )]
 fn middleware_add_one_to_y_get( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 let y = o.get_mut( "y" );
@@ -186,7 +186,7 @@ fn middleware_add_one_to_y_get( mut io : IO ) -> MiddlewareResponse
 )]
 fn middleware_add_one_to_x_post( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 let x = o.get_mut( "x" );
@@ -240,7 +240,7 @@ fn add_z_test_order_false(
 )]
 fn middleware_add_z_test_order_false( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 o.insert( "z".to_string(), awpak_rs::Value::from( 333 ) );
@@ -262,7 +262,7 @@ fn middleware_add_z_test_order_false( mut io : IO ) -> MiddlewareResponse
 )]
 fn middleware_add_z_test_order( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 o.insert( "z".to_string(), awpak_rs::Value::from( 333 ) );
@@ -285,7 +285,7 @@ fn middleware_add_z_test_order( mut io : IO ) -> MiddlewareResponse
 )]
 fn middleware_test_z_exists( mut io : IO ) -> MiddlewareResponse
 {
-    match &mut io.response.body {
+    match io.response.body_value_mut() {
         Some( b ) => match b.as_object_mut() {
             Some( o ) => {
                 let z = o.get( "z" );
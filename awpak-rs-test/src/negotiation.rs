@@ -0,0 +1,38 @@
+use awpak_rs::{get, Bytes};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct Greeting
+{
+    message : String
+}
+
+#[get( url = "/negotiation/produces", produces = [ "application/json", "text/plain" ] )]
+fn negotiation_produces() -> Greeting
+{
+    Greeting { message : "hello".to_string() }
+}
+
+#[get( url = "/negotiation/fallback", produces = [ "application/json" ], not_acceptable = false )]
+fn negotiation_fallback() -> Greeting
+{
+    Greeting { message : "hello".to_string() }
+}
+
+#[get( url = "/negotiation/csv", produces = [ "text/csv" ] )]
+fn negotiation_csv() -> Bytes
+{
+    Bytes::from_static( b"message,hello" )
+}
+
+#[get( url = "/negotiation/xml", produces = [ "application/json", "application/xml" ], xml_root = "greeting" )]
+fn negotiation_xml() -> Greeting
+{
+    Greeting { message : "hello".to_string() }
+}
+
+#[get( url = "/negotiation/xml_default" )]
+fn negotiation_xml_default() -> Greeting
+{
+    Greeting { message : "hello".to_string() }
+}
@@ -3,6 +3,8 @@ use awpak_rs::from_async_str::FromAsyncStr;
 use awpak_rs::io::io::IO;
 use awpak_rs::get;
 use awpak_rs::path_variable;
+use awpak_rs::middleware;
+use awpak_rs::MiddlewareResponse;
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -13,12 +15,17 @@ struct ObjectPathVariable
 
 impl FromAsyncStr<ObjectPathVariable> for ObjectPathVariable
 {
-    async fn from_async_str( _io : &IO, s : &str ) -> Result<ObjectPathVariable, ()>
+    async fn from_async_str( io : &IO, s : &str ) -> Result<ObjectPathVariable, ()>
+    {
+        Self::try_from_async_str( io, s ).await.map_err( | _ | () )
+    }
+
+    async fn try_from_async_str( _io : &IO, s : &str ) -> Result<ObjectPathVariable, String>
     {
         match s.parse::<usize>()
         {
             Ok( x ) => Ok( ObjectPathVariable { x } ),
-            _ => Err( () )
+            Err( e ) => Err( e.to_string() )
         }
     }
 }
@@ -50,4 +57,72 @@ fn get_echo_path_variable_object(
 ) -> ObjectPathVariable
 {
     variable
-}
\ No newline at end of file
+}
+#[get( url = "/get_echo/path_variable/string/static" )]
+fn get_echo_path_variable_string_static() -> String
+{
+    "static".to_string()
+}
+
+#[get( url = "/Case_Insensitive/{variable}", case_insensitive = true )]
+fn get_echo_path_variable_case_insensitive(
+    #[path_variable]
+    variable : String
+) -> String
+{
+    variable
+}
+
+#[get( url = "/typed/{id:u64}" )]
+fn get_echo_path_variable_typed_u64(
+    #[path_variable]
+    id : u64
+) -> String
+{
+    format!( "u64 {}", id )
+}
+
+#[get( url = "/typed/{code:[A-Z]{3}}" )]
+fn get_echo_path_variable_typed_regex(
+    #[path_variable]
+    code : String
+) -> String
+{
+    format!( "code {}", code )
+}
+
+#[get( url = "/typed/{slug}" )]
+fn get_echo_path_variable_typed_any(
+    #[path_variable]
+    slug : String
+) -> String
+{
+    format!( "slug {}", slug )
+}
+
+#[get( url = "/wildcard/string/{*path}" )]
+fn get_echo_path_variable_wildcard_string(
+    #[path_variable]
+    path : String
+) -> String
+{
+    path
+}
+
+#[get( url = "/wildcard/vec/{*path}" )]
+fn get_echo_path_variable_wildcard_vec(
+    #[path_variable]
+    path : Vec<String>
+) -> Vec<String>
+{
+    path
+}
+
+// The endpoint is found for the path after the PRE middlewares, even if the request was looked up before
+#[middleware( urls = [ "/rewrite/.*" ] )]
+fn path_variables_rewrite( mut io : IO ) -> MiddlewareResponse
+{
+    io.request.uri.path = io.request.uri.path.replacen( "/rewrite/", "/get_echo/path_variable/string/", 1 );
+
+    MiddlewareResponse::Next( io )
+}
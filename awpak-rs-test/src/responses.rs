@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use awpak_rs::{get, io::headers::headers::Headers, path_variable, post, response_headers, Binary, ByteStream, Bytes, Created, Error, NoContent, Redirect, Response, Sse, SseEvent};
+use futures::StreamExt;
+
+fn responses_find( id : u8 ) -> Response<String>
+{
+    Response::new( format!( "item {}", id ) )
+    .header( "x-item", &id.to_string() )
+    .cookie( "last_item=1; Path=/" )
+}
+
+#[get( url = "/responses/item/{id}" )]
+fn responses_item(
+    #[path_variable]
+    id : u8
+) -> Response<String>
+{
+    responses_find( id )
+}
+
+#[post( url = "/responses/item" )]
+fn responses_created() -> Created<u8>
+{
+    Created( 7 )
+}
+
+#[post( url = "/responses/custom" )]
+fn responses_custom() -> Response<u8>
+{
+    Response::created( 8 ).status( 202 )
+}
+
+#[get( url = "/responses/no_content" )]
+fn responses_no_content() -> NoContent
+{
+    NoContent
+}
+
+#[post( url = "/responses/login" )]
+fn responses_login() -> Redirect
+{
+    Redirect::see_other( "/responses/item/1" )
+}
+
+#[get( url = "/responses/result/{id}" )]
+fn responses_result(
+    #[path_variable]
+    id : u8
+) -> Result<Response<String>, Error>
+{
+    match id
+    {
+        0 => Err( Error::EndpointExecution( "Zero".to_string() ) ),
+        _ => Ok( responses_find( id ).status( 203 ) )
+    }
+}
+
+#[get( url = "/responses/bytes" )]
+fn responses_bytes() -> Vec<u8>
+{
+    vec![ 0, 1, 254, 255 ]
+}
+
+#[get( url = "/responses/bytes/csv" )]
+fn responses_bytes_csv(
+    #[response_headers]
+    mut headers : Headers
+) -> Bytes
+{
+    headers.replace_header( "content-type".to_string(), "text/csv".to_string() );
+
+    Bytes::from_static( b"a,b" )
+}
+
+#[get( url = "/responses/binary" )]
+fn responses_binary() -> Binary
+{
+    Binary::new( "<p>\"hello\"</p>", "text/html" )
+}
+
+#[get( url = "/responses/stream" )]
+fn responses_stream() -> ByteStream
+{
+    let chunks = futures::stream::iter( 1..=3 ).then( | i | async move {
+
+        awpak_rs::tokio::time::sleep( Duration::from_millis( 10 ) ).await;
+
+        Bytes::from( format!( "chunk{};", i ) )
+    } );
+
+    ByteStream::new( chunks, "text/plain" )
+}
+
+#[get( url = "/responses/sse" )]
+fn responses_sse() -> Sse
+{
+    let events = futures::stream::iter( 1..=2 ).then( | i | async move {
+
+        awpak_rs::tokio::time::sleep( Duration::from_millis( if i == 2 { 300 } else { 0 } ) ).await;
+
+        SseEvent::new( &format!( "value {}", i ) ).id( &i.to_string() ).event( "tick" )
+    } );
+
+    Sse::new( events ).keep_alive( Some( Duration::from_millis( 200 ) ) )
+}
+
+mod dto
+{
+    use serde::Serialize;
+
+    // A type of the application with the name of `awpak_rs::Response`
+    #[derive(Serialize)]
+    pub struct Response
+    {
+        pub id : u8
+    }
+}
+
+#[get( url = "/responses/dto/{id}" )]
+fn responses_dto(
+    #[path_variable]
+    id : u8
+) -> dto::Response
+{
+    dto::Response { id }
+}
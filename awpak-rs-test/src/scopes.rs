@@ -0,0 +1,115 @@
+use awpak_rs::{get, io::io::IO, middleware, scope, MiddlewareResponse};
+
+fn append_trace( io : &mut IO, name : &str )
+{
+    let trace = match io.response.headers.get_value( "x-trace" )
+    {
+        Some( v ) => format!( "{},{}", v, name ),
+        _ => name.to_string()
+    };
+
+    io.response.headers.replace_header( "x-trace".to_string(), trace );
+}
+
+#[middleware( global = false )]
+fn scope_outer( mut io : IO ) -> MiddlewareResponse
+{
+    append_trace( &mut io, "outer" );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[middleware( global = false )]
+fn scope_inner( mut io : IO ) -> MiddlewareResponse
+{
+    append_trace( &mut io, "inner" );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[middleware( global = false, execute_after = true )]
+fn scope_after( mut io : IO ) -> MiddlewareResponse
+{
+    append_trace( &mut io, "after" );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[middleware( global = false )]
+fn scope_cancel( mut io : IO ) -> MiddlewareResponse
+{
+    io.response.status = 401;
+
+    MiddlewareResponse::Cancel( io )
+}
+
+#[middleware( urls = [ "/attached" ] )]
+fn attached_global_pre( mut io : IO ) -> MiddlewareResponse
+{
+    append_trace( &mut io, "global_pre" );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[middleware( urls = [ "/attached" ], execute_after = true )]
+fn attached_global_post( mut io : IO ) -> MiddlewareResponse
+{
+    append_trace( &mut io, "global_post" );
+
+    MiddlewareResponse::Next( io )
+}
+
+#[get( url = "/attached", middleware = [ scope_after, scope_inner, self::scope_outer ] )]
+fn attached() -> String
+{
+    "attached".to_string()
+}
+
+#[scope( prefix = "/scope", middleware = [ scope_outer, scope_after ] )]
+mod scoped
+{
+    use awpak_rs::{get, scope};
+
+    use super::{scope_cancel, scope_inner};
+
+    #[get( url = "/hello" )]
+    fn scope_hello() -> String
+    {
+        "hello".to_string()
+    }
+
+    #[get( url = "/" )]
+    fn scope_root() -> String
+    {
+        "root".to_string()
+    }
+
+    #[scope( prefix = "/inner", middleware = [ scope_inner ] )]
+    mod inner
+    {
+        use awpak_rs::get;
+
+        #[get( url = "/hello" )]
+        fn scope_inner_hello() -> String
+        {
+            "inner hello".to_string()
+        }
+
+        #[get( url = "/cancel", middleware = [ super::scope_cancel ] )]
+        fn scope_inner_cancel() -> String
+        {
+            "not called".to_string()
+        }
+    }
+
+    mod plain
+    {
+        use awpak_rs::get;
+
+        #[get( url = "/plain" )]
+        fn scope_plain() -> String
+        {
+            "plain".to_string()
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use awpak_rs::{io::io::IO, middleware, path_variable, query_param, websocket, Bytes, CloseFrame, Message, MiddlewareResponse, WebSocket};
+
+#[middleware( global = false )]
+fn websockets_auth( mut io : IO ) -> MiddlewareResponse
+{
+    let authorized = match &io.request.uri.query_map
+    {
+        Some( q ) => q.get( "token" ).map( | t | t == "secret" ).unwrap_or( false ),
+        _ => false
+    };
+
+    if authorized
+    {
+        return MiddlewareResponse::Next( io )
+    }
+
+    io.response.status = 401;
+
+    MiddlewareResponse::Cancel( io )
+}
+
+#[websocket( url = "/ws/echo" )]
+async fn websockets_echo( mut socket : WebSocket )
+{
+    while let Some( Ok( message ) ) = socket.recv().await
+    {
+        match message
+        {
+            Message::Text( t ) if t == "close" => {
+                let _ = socket.close( Some( CloseFrame::new( 1000, "bye" ) ) ).await;
+            },
+            Message::Text( _ ) | Message::Binary( _ ) => {
+
+                let sent = socket.send( message ).await;
+
+                if sent.is_err()
+                {
+                    break
+                }
+            },
+            Message::Close( _ ) => break,
+            _ => {}
+        }
+    }
+}
+
+#[websocket( url = "/ws/ping" )]
+async fn websockets_ping( mut socket : WebSocket )
+{
+    if socket.send( Message::Ping( Bytes::from_static( b"hello" ) ) ).await.is_err()
+    {
+        return
+    }
+
+    while let Some( Ok( message ) ) = socket.recv().await
+    {
+        if let Message::Pong( p ) = message
+        {
+            let _ = socket.send( Message::text( format!( "pong: {}", String::from_utf8_lossy( &p ) ) ) ).await;
+
+            break
+        }
+    }
+}
+
+#[websocket( url = "/ws/protocol", protocols = [ "chat.v2", "chat.v1" ] )]
+async fn websockets_protocol( mut socket : WebSocket )
+{
+    let protocol = socket.protocol().unwrap_or( "none" ).to_string();
+
+    let _ = socket.send( Message::text( protocol ) ).await;
+}
+
+#[websocket( url = "/ws/rooms/{room}", middleware = [ websockets_auth ] )]
+async fn websockets_room(
+    #[path_variable]
+    room : String,
+    #[query_param]
+    name : String,
+    mut socket : WebSocket
+)
+{
+    let _ = socket.send( Message::text( format!( "room: {}, name: {}", room, name ) ) ).await;
+}
@@ -26,6 +26,13 @@ cargo test --test test_status_code
 cargo test --test test_query_param
 cargo test --test test_get_2
 cargo test --test test_redirects
+cargo test --test test_methods
+cargo test --test test_scopes
+cargo test --test test_around
+cargo test --test test_errors
+cargo test --test test_responses
+cargo test --test test_websockets
+cargo test --test test_bodies
 fi
 
 kill $PID_SERVER
\ No newline at end of file
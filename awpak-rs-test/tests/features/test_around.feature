@@ -0,0 +1,33 @@
+Feature: Around middlewares feature
+
+  Scenario: An around middleware runs code before and after the endpoint
+
+    When I call GET /around/hello
+    Then status="200"
+    And header x-around="outer,outer"
+    And body="hello"
+
+  Scenario: An around middleware can replace the error of the endpoint
+
+    When I call GET /around/error/33
+    Then status="200"
+    And body="33"
+
+    When I call GET /around/error/1000
+    Then status="503"
+    And header x-around="outer,outer"
+    And body="recovered"
+
+  Scenario: An attached around middleware wraps only its endpoint
+
+    When I call GET /around/attached
+    Then status="202"
+    And header x-around="outer,outer"
+    And body="attached"
+
+  Scenario: An around middleware that fails keeps the request
+
+    When I call GET /around/reject
+    Then status="500"
+    And header x-around="outer"
+    And header x-path="/around/reject"
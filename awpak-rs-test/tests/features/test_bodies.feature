@@ -0,0 +1,173 @@
+Feature: Raw and streamed request bodies
+
+    Scenario: The raw body is the exact bytes of the request
+        When I post '{ "x" : 1,   "y" : 2 }' with content type "application/json" to /bodies/raw
+        Then status="200"
+        And body='{ "x" : 1,   "y" : 2 }'
+        And header x-buffered="true"
+
+    Scenario: The raw body of an unsupported content type is not converted
+        When I post "<a>1</a>" with content type "application/xml" to /bodies/raw
+        Then status="200"
+        And body="<a>1</a>"
+
+    Scenario: The raw body can be used with other body extractors
+        When I post '{ "x" : 1, "y" : 2 }' with content type "application/json" to /bodies/raw_and_json
+        Then status="200"
+        And body="20 bytes, y=2"
+
+    Scenario: The body of a stream endpoint is not buffered
+        When I post 1048576 bytes to /bodies/stream
+        Then status="200"
+        And body="1048576"
+        And header x-buffered="false"
+
+    Scenario: An empty body is an empty stream
+        When I post 0 bytes to /bodies/stream
+        Then status="200"
+        And body="0"
+
+    Scenario: A body under the limit of the endpoint is read
+        When I post 1024 bytes to /bodies/limited
+        Then status="200"
+        And body="1024"
+
+    Scenario: A body over the limit of the endpoint is rejected
+        When I post 1025 bytes to /bodies/limited
+        Then status="413"
+        And body="Request body too large, the limit is 1024 bytes"
+
+    Scenario: A body over the limit of the server is rejected
+        When I post 2097153 bytes to /bodies/raw
+        Then status="413"
+        And body="Request body too large, the limit is 2097152 bytes"
+
+    Scenario: A chunked body is rejected when it crosses the limit
+        When I send a chunked POST of 4096 bytes to /bodies/limited
+        Then status="413"
+        And body="Request body too large, the limit is 1024 bytes"
+
+    Scenario: A chunked body under the limit is read
+        When I send a chunked POST of 1024 bytes to /bodies/limited
+        Then status="200"
+        And body="1024"
+
+    Scenario: The body stream returns an error when it crosses the limit
+        When I send a chunked POST of 4096 bytes to /bodies/stream_limited
+        Then status="200"
+        And body="Request body too large, the limit is 1024 bytes"
+
+    Scenario: A stream endpoint rejects a Content-Length over the limit
+        When I post 1025 bytes to /bodies/stream_limited
+        Then status="413"
+
+    Scenario: A body over the limit is refused before the client sends it
+        When I send the headers of a POST of 1025 bytes to /bodies/limited expecting 100-continue
+        Then status="413"
+
+    Scenario: The client is asked to send a body under the limit
+        When I send the headers of a POST of 1024 bytes to /bodies/limited expecting 100-continue
+        Then status="100"
+
+    Scenario: Form fields are read as body params
+        When I post "name=Ana+Lopez&age=30&tag=a&tag=b" with content type "application/x-www-form-urlencoded" to /bodies/form/params
+        Then status="200"
+        And body="Ana Lopez (30): a, b"
+
+    Scenario: A single value with brackets is read as a list
+        When I post "tag[]=a&name=Ana&age=30" with content type "application/x-www-form-urlencoded" to /bodies/form/params
+        Then status="200"
+        And body="Ana (30): a"
+
+    Scenario: Form fields with brackets are nested objects
+        When I post "user[name]=Ana&user[address][city]=M%C3%A1laga&user[roles][]=admin&user[roles][]=dev" with content type "application/x-www-form-urlencoded" to /bodies/form/nested
+        Then status="200"
+        And body='{"address":{"city":"Málaga"},"name":"Ana","roles":["admin","dev"]}'
+
+    Scenario: The parameters of the content type don't change the parser
+        When I post '{ "x" : 1, "y" : 2 }' with content type "Application/JSON; charset=utf-8" to /bodies/charset/point
+        Then status="200"
+        And body="x=1, y=2"
+
+    Scenario: A malformed body is rejected with the error of the parser
+        When I post '{ "x" : 1, ' with content type "application/json" to /bodies/charset/point
+        Then status="400"
+        And body="Body param error: point: Invalid json: EOF while parsing a value at line 1 column 11"
+
+    Scenario: A form with a charset is parsed as a form
+        When I post "tag[]=a&name=Ana&age=30" with content type "application/x-www-form-urlencoded; charset=UTF-8" to /bodies/form/params
+        Then status="200"
+        And body="Ana (30): a"
+
+    Scenario: A body in another charset is decoded before parsing it
+        When I post '{ "user" : { "name" : "Ana", "address" : { "city" : "Málaga" }, "roles" : [] } }' in latin-1 with content type 'application/json; charset="ISO-8859-1"' to /bodies/form/nested
+        Then status="200"
+        And body='{"address":{"city":"Málaga"},"name":"Ana","roles":[]}'
+
+    Scenario: The response is encoded in the charset of its content type
+        When I post "Málaga" in latin-1 with content type "text/plain; charset=latin1" to /bodies/charset/text
+        Then status="200"
+        And header content-type="text/plain; charset=ISO-8859-1"
+        And body="Málaga"
+
+    Scenario: A registered codec reads the request body
+        When I post "b, c, a" with content type "application/x-list" to /codecs/sort
+        Then status="200"
+        And header content-type="text/plain"
+        And body='["a","b","c"]'
+
+    Scenario: A registered codec is chosen by the Accept header
+        When I post "b, c, a" with content type "application/x-list" accepting "application/x-list" to /codecs/sort
+        Then status="200"
+        And header content-type="application/x-list"
+        And body="a,b,c"
+
+    Scenario: The quality of the Accept header decides between a codec and a built-in type
+        When I post '["b","c","a"]' with content type "application/json" accepting "application/x-list;q=0.5, application/json;q=0.1" to /codecs/sort
+        Then status="200"
+        And header content-type="application/x-list"
+        And body="a,b,c"
+
+    Scenario: A registered codec can be produced by an endpoint
+        When I post "a, b, c" with content type "application/x-list; charset=utf-8" to /codecs/produces
+        Then status="200"
+        And header content-type="application/x-list"
+        And body="c,b,a"
+
+        When I post "a, b, c" with content type "application/x-list" accepting "application/json" to /codecs/produces
+        Then status="200"
+        And header content-type="application/json"
+        And body='["c","b","a"]'
+
+    Scenario: A msgpack body is read by body params
+        When I post the hex bytes "83a46e616d65a3416e61a36167651ea374616791a161" with content type "application/msgpack" accepting "*/*" to /bodies/form/params
+        Then status="200"
+        And header content-type="text/plain"
+        And body="416e6120283330293a2061"
+
+    Scenario: A msgpack body is answered in cbor
+        When I post the hex bytes "81a47573657283a46e616d65a3416e61a76164647265737381a463697479a44c75676fa5726f6c657390" with content type "application/msgpack" accepting "application/cbor" to /bodies/form/nested
+        Then status="200"
+        And header content-type="application/cbor"
+        And body="a36761646472657373a16463697479644c75676f646e616d6563416e6165726f6c657380"
+
+    Scenario: A cbor body is answered in msgpack
+        When I post the hex bytes "a16475736572a3646e616d6563416e616761646472657373a16463697479644c75676f65726f6c657380" with content type "application/cbor" accepting "application/msgpack" to /bodies/form/nested
+        Then status="200"
+        And header content-type="application/msgpack"
+        And body="83a76164647265737381a463697479a44c75676fa46e616d65a3416e61a5726f6c657390"
+
+    Scenario: An XML body is read by body params
+        When I post '<request><name>Ana</name><age>30</age><tag>a</tag><tag>b</tag></request>' with content type "application/xml" to /bodies/form/params
+        Then status="200"
+        And body="Ana (30): a, b"
+
+    Scenario: The attributes of an XML body are not read as child elements
+        When I post '<request><user><name>Ana</name><address city="Lugo"/><roles>admin</roles><roles>dev</roles></user></request>' with content type "application/xml" to /bodies/form/nested
+        Then status="400"
+
+    Scenario: An XML body is answered in XML
+        When I post '<request><user><name>Ana</name><address><city>Lugo</city></address><roles>admin</roles><roles>dev</roles></user></request>' with content type "application/xml; charset=utf-8" accepting "application/xml" to /bodies/form/nested
+        Then status="200"
+        And header content-type="application/xml"
+        And body='<?xml version="1.0"?><response><address><city>Lugo</city></address><name>Ana</name><roles>admin</roles><roles>dev</roles></response>'
@@ -19,4 +19,77 @@ Feature: Content negotiation feature
 
     Given accept="*/json;q=0.95, text/plain, text/html;q=0.9, text/plain;q=0.8"
     When I call /
-    Then content_type="text/plain"
\ No newline at end of file
+    Then content_type="text/plain"
+
+  Scenario: The most specific range sets the quality of a type
+    Given accept="text/*, text/plain;q=0.2, application/json;q=0.5"
+    When I call /negotiation/produces
+    Then status="200"
+    And content_type="application/json"
+    And vary="Accept"
+
+  Scenario: Equal qualities follow the order of produces
+    Given accept="text/plain;q=0.5, application/json;q=0.5"
+    When I call /negotiation/produces
+    Then content_type="application/json"
+
+    Given accept="text/plain; q = 0.6, application/json;q=0.5"
+    When I call /negotiation/produces
+    Then content_type="text/plain"
+
+  Scenario: Ranges with parameters only match types with them
+    Given accept="text/plain;level=1, application/json;q=0.1"
+    When I call /negotiation/produces
+    Then content_type="application/json"
+
+  Scenario: Types with q=0 are not acceptable
+    Given accept="application/json;q=0, text/*;q=0, image/*"
+    When I call /negotiation/produces
+    Then status="406"
+    And content_type="text/plain"
+    And body="Not acceptable, the available media types are: application/json, text/plain"
+
+  Scenario: Raw bodies are sent with the type of produces
+    Given accept="text/csv"
+    When I call /negotiation/csv
+    Then status="200"
+    And content_type="text/csv"
+    And body="message,hello"
+
+    Given accept="application/json"
+    When I call /negotiation/csv
+    Then status="406"
+
+  Scenario: The first type of produces is used if 406 is disabled
+    Given accept="image/png"
+    When I call /negotiation/fallback
+    Then status="200"
+    And content_type="application/json"
+    And body='{"message":"hello"}'
+
+  Scenario: Endpoints without produces use the default type
+    Given accept="image/png"
+    When I call /
+    Then status="200"
+    And content_type="text/plain"
+
+  Scenario: An XML response has the root element of the endpoint
+    Given accept="application/xml"
+    When I call /negotiation/xml
+    Then status="200"
+    And content_type="application/xml"
+    And vary="Accept"
+    And body='<?xml version="1.0"?><greeting><message>hello</message></greeting>'
+
+    Given accept="application/json;q=0.5, application/xml;q=0.4"
+    When I call /negotiation/xml
+    Then status="200"
+    And content_type="application/json"
+    And body='{"message":"hello"}'
+
+  Scenario: An XML response without a root element set is written inside a response element
+    Given accept="text/html, application/xml;q=0.9"
+    When I call /negotiation/xml_default
+    Then status="200"
+    And content_type="application/xml"
+    And body='<?xml version="1.0"?><response><message>hello</message></response>'
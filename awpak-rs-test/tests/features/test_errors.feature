@@ -0,0 +1,82 @@
+Feature: Errors feature
+
+  Scenario: POST middlewares see the original request and context when the endpoint fails
+
+    When I call GET /errors/path_variable/33
+    Then status="200"
+    And header x-error="none"
+    And body="33"
+
+    When I call GET /errors/path_variable/1000
+    Then status="404"
+    And header x-error="Path variable error: id: number too large to fit in target type"
+    And header x-error-context="from_context"
+    And header x-error-request="GET /errors/path_variable/1000"
+
+  Scenario: POST middlewares see the original request when there is no endpoint
+
+    When I call GET /errors/not_found
+    Then status="404"
+    And header x-error="Endpoint not found: /errors/not_found"
+    And header x-error-context="from_context"
+    And header x-error-request="GET /errors/not_found"
+
+  Scenario: Error handlers build the response of failed requests
+
+    When I call GET /errors/handlers/not_found
+    Then status="404"
+    And body="Nothing here"
+    And header x-error="Endpoint not found: /errors/handlers/not_found"
+
+    When I call GET /errors/problem/1000
+    Then status="404"
+    And header content-type="application/problem+json"
+    And body='{"detail":"Path variable error: id: number too large to fit in target type","instance":"/errors/problem/1000","status":404,"title":"Not Found","type":"about:blank"}'
+
+    When I call GET /errors/problem/33
+    Then status="200"
+    And body="33"
+
+  Scenario: Parameters that can't be extracted return 400 with the error
+
+    When I call GET /errors/query_param?y=abc
+    Then status="400"
+    And body="Query param error: y: invalid float literal"
+    And header x-error="Query param error: y: invalid float literal"
+
+    When I call GET /errors/query_param?y=1.5
+    Then status="200"
+    And body="1.5"
+
+    When I call GET /errors/query_params?x=1
+    Then status="400"
+    And body="Query param error: point: missing field `y`"
+
+  Scenario: Endpoints returning Result send the Ok value or the error response
+
+    When I call GET /errors/result/1
+    Then status="200"
+    And body="found user_1"
+
+    When I call GET /errors/result/2
+    Then status="409"
+    And body="User 2 not found"
+    And header x-error="none"
+
+    When I call GET /errors/result_error/7
+    Then status="200"
+    And body="7"
+
+    When I call GET /errors/result_error/0
+    Then status="500"
+    And body="Server Error"
+    And header x-error="Zero"
+
+    When I call GET /errors/result_string/3
+    Then status="200"
+    And body="3"
+
+    When I call GET /errors/result_string/0
+    Then status="500"
+    And body="Server Error"
+    And header x-error="Zero 0"
@@ -0,0 +1,42 @@
+Feature: Methods feature
+
+  Scenario: A path registered under another method returns 405 with the allowed methods
+
+    When I call POST /methods/get_only
+    Then status="405"
+    And header allow="GET, HEAD, OPTIONS"
+
+    When I call PUT /methods/33/detail
+    Then status="405"
+    And header allow="OPTIONS, POST"
+
+    When I call GET /methods/not_found/33
+    Then status="404"
+
+  Scenario: A GET endpoint serves HEAD without body
+
+    When I call HEAD /methods/get_only
+    Then status="200"
+    And header content-length="8"
+    And body=""
+
+    When I call HEAD /responses/no_content
+    Then status="204"
+    And no header content-length
+    And body=""
+
+  Scenario: OPTIONS is answered automatically unless there is an explicit endpoint
+
+    When I call OPTIONS /methods/get_only
+    Then status="204"
+    And header allow="GET, HEAD, OPTIONS"
+
+    When I call OPTIONS /methods/explicit_options
+    Then status="200"
+    And body="explicit_options"
+
+  Scenario: The route table lists every declared endpoint
+
+    When I call GET /methods/routes
+    Then status="200"
+    And body='["GET /methods/explicit_options awpak_rs_test::methods::methods_explicit_options_get","OPTIONS /methods/explicit_options awpak_rs_test::methods::methods_explicit_options","GET /methods/get_only awpak_rs_test::methods::methods_get_only","GET /methods/routes awpak_rs_test::methods::methods_routes","POST /methods/{id}/detail awpak_rs_test::methods::methods_post_variable"]'
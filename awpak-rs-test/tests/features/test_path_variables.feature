@@ -5,6 +5,9 @@ Feature: Path Variable feature
     When I call /get_echo/path_variable/string/hello
     Then response="hello"
 
+    When I call /get_echo/path_variable/string/static
+    Then response="static"
+
     When I call /get_echo/37/string_usize/goodbye
     Then response="goodbye 37"
 
@@ -12,4 +15,68 @@ Feature: Path Variable feature
     Then response='{"x":33}'
 
     When I call /get_echo/path_variable/object/asdf
-    Then response='Server Error'
\ No newline at end of file
+    Then response='Path variable error: variable: invalid digit found in string'
+
+  Scenario: Paths are case sensitive and path variables are percent-decoded
+
+    When I call /get_echo/path_variable/string/hello%20world
+    Then response="hello world"
+
+    When I call /get_echo/path_variable/string/Report.PDF
+    Then response="Report.PDF"
+
+    When I call /get_echo/path_variable/string/%C3%B1
+    Then response="ñ"
+
+    When I call /GET_ECHO/path_variable/string/hello
+    Then response='Server Error'
+
+    When I call /case_insensitive/Report.PDF
+    Then response="Report.PDF"
+
+    When I call /CASE_INSENSITIVE/report.pdf
+    Then response="report.pdf"
+
+  Scenario: Constrained segments fall through to the next candidate
+
+    When I call /typed/33
+    Then response="u64 33"
+
+    When I call /typed/ABC
+    Then response="code ABC"
+
+    When I call /typed/ABCD
+    Then response="slug ABCD"
+
+    When I call /typed/-33
+    Then response="slug -33"
+
+  Scenario: Wildcard segments match the rest of the path
+
+    When I call /wildcard/string/css/main%20file.css
+    Then response="css/main file.css"
+
+    When I call /wildcard/vec/css/main.css
+    Then response='["css","main.css"]'
+
+    When I call /wildcard/vec/
+    Then response='Server Error'
+
+  Scenario: Encoded segments can't leave the path of a wildcard
+
+    When I call /wildcard/string/..%2F..%2Fetc%2Fpasswd
+    Then status="404"
+
+    When I call /wildcard/vec/css/..%2F..%2Fetc%2Fpasswd
+    Then status="404"
+
+    When I call /wildcard/string/..%5C..%5Cetc%5Cpasswd
+    Then status="404"
+
+    When I call /wildcard/string/css/%2e%2e%2fmain.css
+    Then status="404"
+
+  Scenario: A PRE middleware can change the path of the request
+
+    When I call /rewrite/moved
+    Then response="moved"
@@ -0,0 +1,82 @@
+Feature: Responses feature
+
+  Scenario: Endpoints return typed responses with status, headers and cookies
+
+    When I call GET /responses/item/3
+    Then status="200"
+    And header x-item="3"
+    And header set-cookie="last_item=1; Path=/"
+    And body="item 3"
+
+    When I call POST /responses/item
+    Then status="201"
+    And body="7"
+
+    When I call POST /responses/custom
+    Then status="202"
+    And body="8"
+
+    When I call GET /responses/no_content
+    Then status="204"
+    And body=""
+
+    When I call POST /responses/login
+    Then status="303"
+    And header location="/responses/item/1"
+    And body=""
+
+  Scenario: Typed responses can be returned in a Result
+
+    When I call GET /responses/result/4
+    Then status="203"
+    And header x-item="4"
+    And body="item 4"
+
+    When I call GET /responses/result/0
+    Then status="500"
+    And body="Server Error"
+
+  Scenario: A type of the application named Response is serialized as the body
+
+    When I call GET /responses/dto/5
+    Then status="200"
+    And body='{"id":5}'
+
+  Scenario: Raw bytes are sent verbatim
+
+    When I call GET /responses/bytes
+    Then status="200"
+    And header content-type="application/octet-stream"
+    And header content-length="4"
+    And bytes="0, 1, 254, 255"
+
+    When I call GET /responses/bytes/csv
+    Then status="200"
+    And header content-type="text/csv"
+    And body="a,b"
+
+    When I call GET /responses/binary
+    Then status="200"
+    And header content-type="text/html"
+    And header content-length="14"
+    And body='<p>"hello"</p>'
+
+  Scenario: Streams are sent with chunked transfer encoding
+
+    When I call GET /responses/stream
+    Then status="200"
+    And header transfer-encoding="chunked"
+    And header content-type="text/plain"
+    And body="chunk1;chunk2;chunk3;"
+
+    When I call GET /responses/sse
+    Then status="200"
+    And header content-type="text/event-stream"
+    And header cache-control="no-cache"
+    And body line 0="id: 1"
+    And body line 1="event: tick"
+    And body line 2="data: value 1"
+    And body line 3=""
+    And body line 4=":"
+    And body line 6="id: 2"
+    And body line 8="data: value 2"
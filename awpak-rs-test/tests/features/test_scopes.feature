@@ -0,0 +1,43 @@
+Feature: Scopes feature
+
+  Scenario: Endpoints in a scope are served under its prefix with its middlewares
+
+    When I call GET /scope/hello
+    Then status="200"
+    And header x-trace="outer,after"
+    And body="hello"
+
+    When I call GET /scope
+    Then status="200"
+    And body="root"
+
+    When I call GET /scope/plain
+    Then status="200"
+    And header x-trace="outer,after"
+    And body="plain"
+
+    When I call GET /hello
+    Then status="404"
+
+  Scenario: Nested scopes combine prefixes and middlewares
+
+    When I call GET /scope/inner/hello
+    Then status="200"
+    And header x-trace="outer,inner,after"
+    And body="inner hello"
+
+    When I call GET /inner/hello
+    Then status="404"
+
+  Scenario: A cancelled scope middleware skips the endpoint
+
+    When I call GET /scope/inner/cancel
+    Then status="401"
+    And header x-trace="outer,inner"
+
+  Scenario: Middlewares attached to an endpoint are composed with the global chains
+
+    When I call GET /attached
+    Then status="200"
+    And header x-trace="global_pre,inner,outer,after,global_post"
+    And body="attached"
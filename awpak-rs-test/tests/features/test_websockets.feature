@@ -0,0 +1,45 @@
+Feature: WebSocket endpoints
+
+    Scenario: Text and binary messages are echoed
+        When I connect to /ws/echo
+        Then status="101"
+        And header upgrade="websocket"
+        When I send text "hello"
+        Then I receive text "hello"
+        When I send bytes "0, 1, 255"
+        Then I receive bytes "0, 1, 255"
+
+    Scenario: The endpoint closes the connection
+        When I connect to /ws/echo
+        And I send text "close"
+        Then I receive close code=1000 reason="bye"
+
+    Scenario: The endpoint receives the pong of its ping
+        When I connect to /ws/ping
+        Then I receive text "pong: hello"
+
+    Scenario: The first supported protocol of the client is agreed
+        When I connect to /ws/protocol with protocols "chat.v1, chat.v2"
+        Then status="101"
+        And header sec-websocket-protocol="chat.v1"
+        And I receive text "chat.v1"
+
+    Scenario: No protocol is agreed if none is supported
+        When I connect to /ws/protocol
+        Then status="101"
+        And no header sec-websocket-protocol
+        And I receive text "none"
+
+    Scenario: Path variables and query params are extracted before the upgrade
+        When I connect to /ws/rooms/general?token=secret&name=ana
+        Then status="101"
+        And I receive text "room: general, name: ana"
+
+    Scenario: Middlewares run on the upgrade request
+        When I connect to /ws/rooms/general?name=ana
+        Then status="401"
+
+    Scenario: A request that is not a websocket handshake is rejected
+        When I call GET /ws/echo
+        Then status="400"
+        And body="Header error: upgrade: expected websocket"
@@ -0,0 +1,190 @@
+use awpak_rs::tokio;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use cucumber::{then, when, World};
+
+mod util;
+
+#[derive(Debug, Default, World)]
+struct BodiesWorld
+{
+    status : Option<u16>,
+    headers : Option<reqwest::header::HeaderMap>,
+    body : Option<String>
+}
+
+async fn post( world : &mut BodiesWorld, url : String, content_type : &str, body : Vec<u8> )
+{
+    post_accept( world, url, content_type, "*/*", body ).await
+}
+
+async fn post_accept( world : &mut BodiesWorld, url : String, content_type : &str, accept : &str, body : Vec<u8> )
+{
+    let url = format!( "http://127.0.0.1:3001{}", url );
+
+    let response = reqwest::Client::new().post( &url )
+    .header( "Content-Type", content_type )
+    .header( "Accept", accept )
+    .body( body )
+    .send()
+    .await.unwrap();
+
+    world.status = Some( response.status().as_u16() );
+
+    world.headers = Some( response.headers().clone() );
+
+    // `text` decodes the body with the charset of the content type
+    world.body = response.text().await.ok();
+}
+
+#[when( expr = "I post {string} with content type {string} to {word}" )]
+async fn post_body( world : &mut BodiesWorld, body : String, content_type : String, url : String )
+{
+    post( world, url, &content_type, body.into_bytes() ).await
+}
+
+#[when( expr = "I post {string} with content type {string} accepting {string} to {word}" )]
+async fn post_body_accept( world : &mut BodiesWorld, body : String, content_type : String, accept : String, url : String )
+{
+    post_accept( world, url, &content_type, &accept, body.into_bytes() ).await
+}
+
+#[when( expr = "I post the hex bytes {string} with content type {string} accepting {string} to {word}" )]
+async fn post_hex_body_accept( world : &mut BodiesWorld, hex : String, content_type : String, accept : String, url : String )
+{
+    let body = ( 0..hex.len() ).step_by( 2 ).map( | i | u8::from_str_radix( &hex[ i..i + 2 ], 16 ).unwrap() ).collect();
+
+    let url = format!( "http://127.0.0.1:3001{}", url );
+
+    let response = reqwest::Client::new().post( &url )
+    .header( "Content-Type", content_type )
+    .header( "Accept", accept )
+    .body::<Vec<u8>>( body )
+    .send()
+    .await.unwrap();
+
+    world.status = Some( response.status().as_u16() );
+
+    world.headers = Some( response.headers().clone() );
+
+    // Binary bodies are compared as hex
+    world.body = response.bytes().await.ok().map( | b | b.iter().map( | b | format!( "{:02x}", b ) ).collect() );
+}
+
+#[when( expr = "I post {string} in latin-1 with content type {string} to {word}" )]
+async fn post_latin1_body( world : &mut BodiesWorld, body : String, content_type : String, url : String )
+{
+    post( world, url, &content_type, body.chars().map( | c | c as u8 ).collect() ).await
+}
+
+#[when( expr = "I post {int} bytes to {word}" )]
+async fn post_bytes( world : &mut BodiesWorld, len : usize, url : String )
+{
+    post( world, url, "application/octet-stream", vec![ b'a'; len ] ).await
+}
+
+// Requests written by hand, to send a chunked body or to wait for a `100 Continue`
+async fn raw_request( head : String, chunks : Vec<Vec<u8>>, headers_only : bool ) -> ( u16, String )
+{
+    let mut stream = tokio::net::TcpStream::connect( "127.0.0.1:3001" ).await.unwrap();
+
+    stream.write_all( head.as_bytes() ).await.unwrap();
+
+    // The server can answer and close the connection before the whole body is sent
+    for chunk in chunks
+    {
+        let chunk = [ format!( "{:x}\r\n", chunk.len() ).into_bytes(), chunk, b"\r\n".to_vec() ].concat();
+
+        if stream.write_all( &chunk ).await.is_err()
+        {
+            break
+        }
+    }
+
+    let mut response = vec![];
+
+    let mut buffer = [ 0; 4096 ];
+
+    loop
+    {
+        let read = tokio::time::timeout( std::time::Duration::from_secs( 5 ), stream.read( &mut buffer ) ).await;
+
+        match read
+        {
+            Ok( Ok( n ) ) if n > 0 => response.extend_from_slice( &buffer[ ..n ] ),
+            _ => break
+        }
+
+        if headers_only && response.windows( 4 ).any( | w | w == b"\r\n\r\n" )
+        {
+            break
+        }
+    }
+
+    let response = String::from_utf8_lossy( &response ).to_string();
+
+    let status = response.split( " " ).nth( 1 ).unwrap().parse::<u16>().unwrap();
+
+    let body = response.split_once( "\r\n\r\n" ).map( | ( _, b ) | b.to_string() ).unwrap_or_default();
+
+    ( status, body )
+}
+
+#[when( expr = "I send a chunked POST of {int} bytes to {word}" )]
+async fn post_chunked( world : &mut BodiesWorld, len : usize, url : String )
+{
+    let head = format!( "POST {} HTTP/1.1\r\nHost: 127.0.0.1\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n", url );
+
+    let mut chunks = vec![ vec![ b'a'; 256 ]; len / 256 ];
+
+    chunks.push( vec![] );
+
+    let ( status, body ) = raw_request( head, chunks, false ).await;
+
+    world.status = Some( status );
+
+    world.body = Some( body );
+}
+
+#[when( expr = "I send the headers of a POST of {int} bytes to {word} expecting 100-continue" )]
+async fn post_expect_continue( world : &mut BodiesWorld, len : usize, url : String )
+{
+    let head = format!( "POST {} HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Length: {}\r\nExpect: 100-continue\r\n\r\n", url, len );
+
+    let ( status, _ ) = raw_request( head, vec![], true ).await;
+
+    world.status = Some( status );
+}
+
+#[then( expr = "status={string}" )]
+fn check_status( world : &mut BodiesWorld, status : String )
+{
+    assert!( world.status.is_some(), "No response received" );
+
+    assert_eq!( world.status.unwrap().to_string(), status );
+}
+
+#[then( regex = r#"^header +([a-z-]+)="(.*)"$"# )]
+fn check_header( world : &mut BodiesWorld, name : String, value : String )
+{
+    assert!( world.headers.is_some(), "No response received" );
+
+    let header = world.headers.as_ref().unwrap().get( &name );
+
+    assert!( header.is_some(), "Header {} not found", name );
+
+    assert_eq!( header.unwrap().to_str().unwrap(), value );
+}
+
+#[then( expr = "body={string}" )]
+fn check_body( world : &mut BodiesWorld, body : String )
+{
+    assert!( world.body.is_some(), "No response received" );
+
+    assert_eq!( world.body.as_ref().unwrap(), &body );
+}
+
+#[tokio::main]
+async fn main()
+{
+    BodiesWorld::run( "tests/features/test_bodies.feature" ).await;
+}
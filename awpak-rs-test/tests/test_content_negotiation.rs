@@ -8,7 +8,10 @@ mod util;
 struct ContentNegotiationWorld
 {
     accept : String,
-    response : Option<String>
+    response : Option<String>,
+    status : Option<u16>,
+    vary : Option<String>,
+    body : Option<String>
 }
 
 #[given( expr = "accept={string}" )]
@@ -17,6 +20,12 @@ fn define_x_y( world : &mut ContentNegotiationWorld, accept : String )
     world.accept = accept;
 
     world.response = None;
+
+    world.status = None;
+
+    world.vary = None;
+
+    world.body = None;
 }
 
 #[when( regex = r"^I +call +(.+)" )]
@@ -34,21 +43,20 @@ async fn call_url( world : &mut ContentNegotiationWorld, url : String )
     .send()
     .await.unwrap();
 
-    let content_type = response.headers().get( "content-type" );
+    world.status = Some( response.status().as_u16() );
 
-    if content_type.is_none()
-    {
-        return;
-    }
+    world.vary = response.headers().get( "vary" ).and_then( | v | v.to_str().ok() ).map( | v | v.to_string() );
+
+    let content_type = response.headers().get( "content-type" ).and_then( | c | c.to_str().ok() ).map( | c | c.to_string() );
 
-    let content_type = content_type.unwrap().to_str();
+    world.body = response.text().await.ok();
 
-    if content_type.is_err()
+    if content_type.is_none()
     {
         return;
     }
 
-    world.response = Some( content_type.unwrap().to_string() );
+    world.response = content_type;
 }
 
 #[then( expr = "content_type={string}" )]
@@ -59,6 +67,28 @@ fn check_result( world : &mut ContentNegotiationWorld, content_type : String )
     assert_eq!( world.response.as_ref().unwrap(), &content_type );
 }
 
+#[then( expr = "status={string}" )]
+fn check_status( world : &mut ContentNegotiationWorld, status : String )
+{
+    assert!( world.status.is_some(), "No response received" );
+
+    assert_eq!( world.status.unwrap().to_string(), status );
+}
+
+#[then( expr = "vary={string}" )]
+fn check_vary( world : &mut ContentNegotiationWorld, vary : String )
+{
+    assert_eq!( world.vary.as_deref(), Some( vary.as_str() ) );
+}
+
+#[then( expr = "body={string}" )]
+fn check_body( world : &mut ContentNegotiationWorld, body : String )
+{
+    assert!( world.body.is_some(), "No response received" );
+
+    assert_eq!( world.body.as_ref().unwrap(), &body );
+}
+
 #[tokio::main]
 async fn main()
 {
@@ -0,0 +1,74 @@
+use awpak_rs::tokio;
+use cucumber::{then, when, World};
+
+mod util;
+
+#[derive(Debug, Default, World)]
+struct MethodsWorld
+{
+    status : Option<u16>,
+    headers : Option<reqwest::header::HeaderMap>,
+    body : Option<String>
+}
+
+#[when( regex = r"^I +call +([A-Z]+) +(.+)" )]
+async fn call_url( world : &mut MethodsWorld, method : String, url : String )
+{
+    let url = format!( "http://127.0.0.1:3001{}", url );
+
+    let client = reqwest::Client::new();
+
+    let method = reqwest::Method::from_bytes( method.as_bytes() ).unwrap();
+
+    let response = client.request( method, &url )
+    .send()
+    .await.unwrap();
+
+    world.status = Some( response.status().as_u16() );
+
+    world.headers = Some( response.headers().clone() );
+
+    world.body = response.text().await.ok();
+}
+
+#[then( expr = "status={string}" )]
+fn check_status( world : &mut MethodsWorld, status : String )
+{
+    assert!( world.status.is_some(), "No response received" );
+
+    assert_eq!( world.status.unwrap().to_string(), status );
+}
+
+#[then( regex = r#"^header +([a-z-]+)="(.*)"$"# )]
+fn check_header( world : &mut MethodsWorld, name : String, value : String )
+{
+    assert!( world.headers.is_some(), "No response received" );
+
+    let header = world.headers.as_ref().unwrap().get( &name );
+
+    assert!( header.is_some(), "Header {} not found", name );
+
+    assert_eq!( header.unwrap().to_str().unwrap(), value );
+}
+
+#[then( regex = r#"^no header +([a-z-]+)$"# )]
+fn check_no_header( world : &mut MethodsWorld, name : String )
+{
+    assert!( world.headers.is_some(), "No response received" );
+
+    assert!( world.headers.as_ref().unwrap().get( &name ).is_none(), "Header {} found", name );
+}
+
+#[then( expr = "body={string}" )]
+fn check_body( world : &mut MethodsWorld, body : String )
+{
+    assert!( world.body.is_some(), "No response received" );
+
+    assert_eq!( world.body.as_ref().unwrap(), &body );
+}
+
+#[tokio::main]
+async fn main()
+{
+    MethodsWorld::run( "tests/features/test_methods.feature" ).await;
+}
@@ -6,6 +6,7 @@ mod util;
 #[derive(Debug, Default, World)]
 struct PathVariableWord
 {
+    status : Option<u16>,
     response : Option<String>
 }
 
@@ -22,6 +23,8 @@ async fn call_url( world : &mut PathVariableWord, url : String )
     .send()
     .await.unwrap();
 
+    world.status = Some( response.status().as_u16() );
+
     let body = response.text().await;
 
     world.response = match body
@@ -39,6 +42,14 @@ fn check_result( world : &mut PathVariableWord, response : String )
     assert_eq!( world.response.as_ref().unwrap(), &response );
 }
 
+#[then( expr = "status={string}" )]
+fn check_status( world : &mut PathVariableWord, status : String )
+{
+    assert!( world.status.is_some(), "No response received" );
+
+    assert_eq!( world.status.unwrap().to_string(), status );
+}
+
 #[tokio::main]
 async fn main()
 {
@@ -0,0 +1,91 @@
+use awpak_rs::tokio;
+use cucumber::{then, when, World};
+
+mod util;
+
+#[derive(Debug, Default, World)]
+struct ResponsesWorld
+{
+    status : Option<u16>,
+    headers : Option<reqwest::header::HeaderMap>,
+    body : Option<String>,
+    bytes : Option<Vec<u8>>
+}
+
+#[when( regex = r"^I +call +([A-Z]+) +(.+)" )]
+async fn call_url( world : &mut ResponsesWorld, method : String, url : String )
+{
+    let url = format!( "http://127.0.0.1:3001{}", url );
+
+    let client = reqwest::Client::builder()
+    .redirect( reqwest::redirect::Policy::none() )
+    .build().unwrap();
+
+    let method = reqwest::Method::from_bytes( method.as_bytes() ).unwrap();
+
+    let response = client.request( method, &url )
+    .send()
+    .await.unwrap();
+
+    world.status = Some( response.status().as_u16() );
+
+    world.headers = Some( response.headers().clone() );
+
+    let bytes = response.bytes().await.unwrap().to_vec();
+
+    world.body = String::from_utf8( bytes.clone() ).ok();
+
+    world.bytes = Some( bytes );
+}
+
+#[then( expr = "status={string}" )]
+fn check_status( world : &mut ResponsesWorld, status : String )
+{
+    assert!( world.status.is_some(), "No response received" );
+
+    assert_eq!( world.status.unwrap().to_string(), status );
+}
+
+#[then( regex = r#"^header +([a-z-]+)="(.*)"$"# )]
+fn check_header( world : &mut ResponsesWorld, name : String, value : String )
+{
+    assert!( world.headers.is_some(), "No response received" );
+
+    let header = world.headers.as_ref().unwrap().get( &name );
+
+    assert!( header.is_some(), "Header {} not found", name );
+
+    assert_eq!( header.unwrap().to_str().unwrap(), value );
+}
+
+#[then( expr = "body={string}" )]
+fn check_body( world : &mut ResponsesWorld, body : String )
+{
+    assert!( world.body.is_some(), "No response received" );
+
+    assert_eq!( world.body.as_ref().unwrap(), &body );
+}
+
+#[then( expr = "bytes={string}" )]
+fn check_bytes( world : &mut ResponsesWorld, bytes : String )
+{
+    assert!( world.bytes.is_some(), "No response received" );
+
+    let expected = bytes.split( ',' ).map( | b | b.trim().parse::<u8>().unwrap() ).collect::<Vec<u8>>();
+
+    assert_eq!( world.bytes.as_ref().unwrap(), &expected );
+}
+
+#[then( expr = "body line {int}={string}" )]
+fn check_body_line( world : &mut ResponsesWorld, line : usize, value : String )
+{
+    assert!( world.body.is_some(), "No response received" );
+
+    assert_eq!( world.body.as_ref().unwrap().split( '\n' ).nth( line ), Some( value.as_str() ) );
+}
+
+#[tokio::main]
+async fn main()
+{
+    ResponsesWorld::run( "tests/features/test_responses.feature" ).await;
+}
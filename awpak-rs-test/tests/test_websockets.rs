@@ -0,0 +1,180 @@
+use awpak_rs::tokio;
+use cucumber::{then, when, World};
+use futures::{SinkExt, StreamExt};
+use tokio_tungstenite::{connect_async, tungstenite::{client::IntoClientRequest, Message}, MaybeTlsStream, WebSocketStream};
+
+mod util;
+
+#[derive(Debug, Default, World)]
+struct WebSocketsWorld
+{
+    socket : Option<WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>>,
+    status : Option<u16>,
+    headers : Option<reqwest::header::HeaderMap>,
+    body : Option<String>
+}
+
+async fn connect( world : &mut WebSocketsWorld, url : String, protocols : Option<String> )
+{
+    let mut request = format!( "ws://127.0.0.1:3001{}", url ).into_client_request().unwrap();
+
+    if let Some( p ) = protocols
+    {
+        request.headers_mut().insert( "sec-websocket-protocol", p.parse().unwrap() );
+    }
+
+    match connect_async( request ).await
+    {
+        Ok( ( socket, response ) ) => {
+
+            world.status = Some( response.status().as_u16() );
+
+            world.headers = Some( response.headers().clone() );
+
+            world.socket = Some( socket );
+        },
+        Err( tokio_tungstenite::tungstenite::Error::Http( response ) ) => {
+
+            world.status = Some( response.status().as_u16() );
+
+            world.headers = Some( response.headers().clone() );
+        },
+        Err( e ) => panic!( "{}", e )
+    }
+}
+
+#[when( expr = "I connect to {word}" )]
+async fn connect_url( world : &mut WebSocketsWorld, url : String )
+{
+    connect( world, url, None ).await
+}
+
+#[when( expr = "I connect to {word} with protocols {string}" )]
+async fn connect_url_protocols( world : &mut WebSocketsWorld, url : String, protocols : String )
+{
+    connect( world, url, Some( protocols ) ).await
+}
+
+#[when( regex = r"^I +call +([A-Z]+) +(.+)" )]
+async fn call_url( world : &mut WebSocketsWorld, method : String, url : String )
+{
+    let url = format!( "http://127.0.0.1:3001{}", url );
+
+    let method = reqwest::Method::from_bytes( method.as_bytes() ).unwrap();
+
+    let response = reqwest::Client::new().request( method, &url ).send().await.unwrap();
+
+    world.status = Some( response.status().as_u16() );
+
+    world.headers = Some( response.headers().clone() );
+
+    world.body = response.text().await.ok();
+}
+
+#[when( expr = "I send text {string}" )]
+async fn send_text( world : &mut WebSocketsWorld, text : String )
+{
+    world.socket.as_mut().expect( "Not connected" ).send( Message::text( text ) ).await.unwrap();
+}
+
+#[when( expr = "I send bytes {string}" )]
+async fn send_bytes( world : &mut WebSocketsWorld, bytes : String )
+{
+    world.socket.as_mut().expect( "Not connected" ).send( Message::binary( parse_bytes( &bytes ) ) ).await.unwrap();
+}
+
+async fn next_message( world : &mut WebSocketsWorld ) -> Message
+{
+    let socket = world.socket.as_mut().expect( "Not connected" );
+
+    // Pings are answered by the client on the next read
+    loop
+    {
+        match tokio::time::timeout( std::time::Duration::from_secs( 5 ), socket.next() ).await
+        {
+            Ok( Some( Ok( Message::Ping( _ ) | Message::Pong( _ ) ) ) ) => continue,
+            Ok( Some( Ok( m ) ) ) => return m,
+            other => panic!( "No message received: {:?}", other )
+        }
+    }
+}
+
+#[then( expr = "I receive text {string}" )]
+async fn receive_text( world : &mut WebSocketsWorld, text : String )
+{
+    match next_message( world ).await
+    {
+        Message::Text( t ) => assert_eq!( t.as_str(), text ),
+        m => panic!( "Expected text, received {:?}", m )
+    }
+}
+
+#[then( expr = "I receive bytes {string}" )]
+async fn receive_bytes( world : &mut WebSocketsWorld, bytes : String )
+{
+    match next_message( world ).await
+    {
+        Message::Binary( b ) => assert_eq!( b.to_vec(), parse_bytes( &bytes ) ),
+        m => panic!( "Expected bytes, received {:?}", m )
+    }
+}
+
+#[then( expr = "I receive close code={int} reason={string}" )]
+async fn receive_close( world : &mut WebSocketsWorld, code : u16, reason : String )
+{
+    match next_message( world ).await
+    {
+        Message::Close( Some( f ) ) => {
+            assert_eq!( u16::from( f.code ), code );
+            assert_eq!( f.reason.as_str(), reason );
+        },
+        m => panic!( "Expected close, received {:?}", m )
+    }
+}
+
+#[then( expr = "status={string}" )]
+fn check_status( world : &mut WebSocketsWorld, status : String )
+{
+    assert!( world.status.is_some(), "No response received" );
+
+    assert_eq!( world.status.unwrap().to_string(), status );
+}
+
+#[then( regex = r#"^header +([a-z-]+)="(.*)"$"# )]
+fn check_header( world : &mut WebSocketsWorld, name : String, value : String )
+{
+    assert!( world.headers.is_some(), "No response received" );
+
+    let header = world.headers.as_ref().unwrap().get( &name );
+
+    assert!( header.is_some(), "Header {} not found", name );
+
+    assert_eq!( header.unwrap().to_str().unwrap(), value );
+}
+
+#[then( regex = r#"^no header +([a-z-]+)$"# )]
+fn check_no_header( world : &mut WebSocketsWorld, name : String )
+{
+    assert!( world.headers.is_some(), "No response received" );
+
+    assert!( world.headers.as_ref().unwrap().get( &name ).is_none(), "Header {} found", name );
+}
+
+#[then( expr = "body={string}" )]
+fn check_body( world : &mut WebSocketsWorld, body : String )
+{
+    assert!( world.body.is_some(), "No response received" );
+
+    assert_eq!( world.body.as_ref().unwrap(), &body );
+}
+
+fn parse_bytes( bytes : &str ) -> Vec<u8>
+{
+    bytes.split( "," ).map( | b | b.trim().parse::<u8>().unwrap() ).collect()
+}
+
+#[tokio::main]
+async fn main()
+{
+    WebSocketsWorld::run( "tests/features/test_websockets.feature" ).await;
+}
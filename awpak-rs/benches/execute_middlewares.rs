@@ -1,61 +1,97 @@
-// extern crate awpak_rs;
+extern crate awpak_rs;
 
-// // use awpak_rs::benches::bench_execute_middlewares;
+use awpak_rs::benches::bench_execute_middlewares;
 
-// use awpak_rs::{initialize_middlewares, io::{io::IO, response::response_data::ResponseData}, middleware, MiddlewareResponse};
+use awpak_rs::{initialize_middlewares, io::{io::IO, response::response_data::ResponseData}, middleware, MiddlewareResponse};
 
-// use criterion::{
-//     black_box, criterion_group, criterion_main, Criterion
-// };
-// use criterion::async_executor::FuturesExecutor;
+use criterion::{
+    black_box, criterion_group, criterion_main, Criterion
+};
+use criterion::async_executor::FuturesExecutor;
 
-// #[middleware(
-//     urls=[
-//         "^/b.+$"
-//     ]
-// )]
-// pub fn test_middleware( io : IO ) -> MiddlewareResponse
-// {
-//     MiddlewareResponse::Next( io )
-// }
+const PATTERNS : [ &str; 8 ] = [
+    "^/b.+$",
+    "^/api/v1/users/[0-9]+$",
+    "^/api/v1/orders/.*$",
+    "^/admin/.*$",
+    "^/static/.+\\.css$",
+    "^/bench$",
+    "^/other$",
+    "^/private/.*$"
+];
 
-// fn get_io() -> IO
-// {
-//     let mut io = IO::with_response( ResponseData::default() );
+#[middleware( urls = [ "^/b.+$" ] )]
+pub fn test_middleware_1( io : IO ) -> MiddlewareResponse
+{
+    MiddlewareResponse::Next( io )
+}
 
-//     io.request.method = "get".to_string();
+#[middleware( urls = [ "^/api/v1/users/[0-9]+$", "^/api/v1/orders/.*$" ] )]
+pub fn test_middleware_2( io : IO ) -> MiddlewareResponse
+{
+    MiddlewareResponse::Next( io )
+}
 
-//     io.request.uri.path = "/bench".to_string();
+#[middleware( urls = [ "^/admin/.*$" ] )]
+pub fn test_middleware_3( io : IO ) -> MiddlewareResponse
+{
+    MiddlewareResponse::Next( io )
+}
 
-//     io
-// }
+#[middleware( urls = [ "^/static/.+\\.css$" ] )]
+pub fn test_middleware_4( io : IO ) -> MiddlewareResponse
+{
+    MiddlewareResponse::Next( io )
+}
 
-// fn execute_middlewares_bench( c : &mut Criterion )
-// {
-//     initialize_middlewares();
-    
-//     c.bench_function(
-//         "execute_middlewares", 
-//         | b | b.to_async(FuturesExecutor).iter(|| {
-//             let io = black_box( get_io() );
-//             awpak_rs::services::middleware::middleware::pre_middlewares_exec( io ) 
-//         })
-//     );
-// }
-
-// // fn from_elem(c: &mut Criterion) {
+#[middleware( urls = [ "^/bench$", "^/other$" ] )]
+pub fn test_middleware_5( io : IO ) -> MiddlewareResponse
+{
+    MiddlewareResponse::Next( io )
+}
+
+#[middleware( urls = [ "^/private/.*$" ] )]
+pub fn test_middleware_6( io : IO ) -> MiddlewareResponse
+{
+    MiddlewareResponse::Next( io )
+}
+
+fn get_io() -> IO
+{
+    let mut io = IO::with_response( ResponseData::default() );
+
+    io.request.method = "get".to_string();
+
+    io.request.uri.path = "/bench".to_string();
+
+    io
+}
+
+fn execute_middlewares_bench( c : &mut Criterion )
+{
+    initialize_middlewares().unwrap();
     
+    c.bench_function(
+        "execute_middlewares", 
+        | b | b.to_async( FuturesExecutor ).iter( || {
+            let io = black_box( get_io() );
+            bench_execute_middlewares( io ) 
+        } )
+    );
+}
+
+// Reference for `execute_middlewares`: the same patterns compiled on every request,
+// as middlewares were executed before the patterns were cached.
+fn compile_regex_per_request_bench( c : &mut Criterion )
+{
+    c.bench_function(
+        "compile_regex_per_request", 
+        | b | b.iter( || {
+            let path = black_box( "/bench" );
+            PATTERNS.iter().filter( | p | regex::Regex::new( p ).map( | r | r.is_match( path ) ).unwrap_or( false ) ).count()
+        } )
+    );
+}
 
-// //     c.bench_function(
-// //         BenchmarkId::new("input_example" ), |b| {
-// //         // Insert a call to `to_async` to convert the bencher to async mode.
-// //         // The timing loops are the same as with the normal bencher.
-// //         b.to_async(FuturesExecutor).iter(|| {
-// //             let io = black_box( get_io() );
-// //             pre_middlewares_exec( io ) 
-// //         });
-// //     });
-// // }
-
-// criterion_group!( benches, execute_middlewares_bench );
-// criterion_main!( benches );
\ No newline at end of file
+criterion_group!( benches, execute_middlewares_bench, compile_regex_per_request_bench );
+criterion_main!( benches );
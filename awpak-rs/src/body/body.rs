@@ -1,4 +1,6 @@
 
+use hyper::body::Bytes;
+
 #[derive(Clone)]
 pub struct FileData
 {
@@ -36,7 +38,7 @@ pub struct BodyData
     /// ```rust
     /// use awpak_rs::body::body::BodyData;
     /// 
-    /// let body_data = BodyData { value : None, files : vec![] };
+    /// let body_data = BodyData { value : None, files : vec![], raw : None, error : None };
     /// 
     /// if let Some(json_value) = &body_data.value {
     ///     println!("Received JSON: {}", json_value);
@@ -47,14 +49,25 @@ pub struct BodyData
     /// ```rust
     /// use awpak_rs::body::body::BodyData;
     /// 
-    /// let mut body_data = BodyData { value : None, files : vec![] };
+    /// let mut body_data = BodyData { value : None, files : vec![], raw : None, error : None };
     /// 
     /// if let Some(json_value) = &mut body_data.value {
     ///     json_value["new_key"] = serde_json::json!("new_value");
     /// }
     /// ```
     pub value : Option<serde_json::Value>,
-    pub files : Vec<FileData>
+    pub files : Vec<FileData>,
+
+    /// The exact bytes of the request body, before they are parsed.
+    ///
+    /// Useful to verify the signature of a webhook. It's `None` for the endpoints
+    /// that read the body with `#[body_stream]`, because the body is not buffered.
+    pub raw : Option<Bytes>,
+
+    /// The error of the body if it can't be parsed, like `"Invalid json: expected value at line 1 column 1"`.
+    ///
+    /// `value` is `None` then, and `#[request_body]` and `#[body_param]` fail with this error instead of a missing body.
+    pub error : Option<String>
 }
 
 impl BodyData
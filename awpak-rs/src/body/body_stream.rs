@@ -0,0 +1,92 @@
+use std::{pin::Pin, sync::Mutex, task::{Context, Poll}};
+
+use futures::Stream;
+use http_body_util::{BodyDataStream, BodyExt as _, LengthLimitError, Limited};
+use hyper::body::{Bytes, Incoming};
+
+use crate::services::error::Error;
+
+/// The body of a request, read as it arrives from the client.
+///
+/// It's the type of the `#[body_stream]` parameters. The body of these endpoints is not buffered,
+/// so `io.request.body` is empty for them, also in the middlewares.
+///
+/// If the body goes over the `max_body` of the endpoint, the stream returns a `PayloadTooLarge` error.
+///
+/// # Example
+///
+/// ```ignore
+/// use futures::StreamExt;
+///
+/// #[post(url = "/upload")]
+/// async fn upload(#[body_stream] mut body: RequestBodyStream) -> usize {
+///     let mut len = 0;
+///
+///     while let Some(Ok(chunk)) = body.next().await {
+///         len += chunk.len();
+///     }
+///
+///     len
+/// }
+/// ```
+pub struct RequestBodyStream
+{
+    // The `Mutex` keeps the `IO` `Sync`. It's never locked, polling the stream takes a `&mut`.
+    inner : Mutex<Option<BodyDataStream<Limited<Incoming>>>>,
+    max_body : usize
+}
+
+impl RequestBodyStream
+{
+    pub(crate) fn new( body : Incoming, max_body : usize ) -> Self
+    {
+        Self
+        {
+            inner : Mutex::new( Some( Limited::new( body, max_body ).into_data_stream() ) ),
+            max_body
+        }
+    }
+
+    /// A body without data.
+    pub fn empty() -> Self
+    {
+        Self
+        {
+            inner : Mutex::new( None ),
+            max_body : 0
+        }
+    }
+}
+
+impl Stream for RequestBodyStream
+{
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next( self : Pin<&mut Self>, cx : &mut Context<'_> ) -> Poll<Option<Self::Item>>
+    {
+        let max_body = self.max_body;
+
+        let inner = match self.get_mut().inner.get_mut()
+        {
+            Ok( v ) => v,
+            Err( e ) => e.into_inner()
+        };
+
+        match inner
+        {
+            Some( s ) => Pin::new( s ).poll_next( cx ).map( | c | c.map( | c | c.map_err( | e | body_error( e, max_body ) ) ) ),
+            None => Poll::Ready( None )
+        }
+    }
+}
+
+// Error of a body read with a `max_body` limit.
+pub(crate) fn body_error( error : Box<dyn std::error::Error + Send + Sync>, max_body : usize ) -> Error
+{
+    if error.downcast_ref::<LengthLimitError>().is_some()
+    {
+        return Error::PayloadTooLarge( max_body )
+    }
+
+    Error::EndpointExecution( error.to_string() )
+}
@@ -1 +1,2 @@
-pub mod body;
\ No newline at end of file
+pub mod body;
+pub mod body_stream;
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::io::io::IO;
+use crate::{io::io::IO, services::middleware::middleware::Middleware};
 
 use super::types::EndpointReturnType;
 
@@ -7,20 +7,108 @@ pub struct Endpoint
 {
     pub url : &'static str,
     pub method : &'static str,
-    pub fnc : fn( IO ) -> EndpointReturnType
+    pub fnc : fn( IO ) -> EndpointReturnType,
+    pub case_insensitive : bool,
+    /// Path of the handler function, like `my_crate::users::get_user`.
+    pub name : &'static str,
+    /// Source file where the handler is declared.
+    pub file : &'static str,
+    /// Line of the source file where the handler is declared.
+    pub line : u32,
+    /// Middlewares attached to this endpoint with `middleware = [...]` or by a `scope`.
+    pub middlewares : &'static [Middleware],
+    /// The endpoint reads the body with `#[body_stream]`, so the body is not buffered before it runs.
+    pub body_stream : bool,
+    /// Maximum size of the request body, in bytes, set with `max_body`. `None` uses the limit of the server.
+    pub max_body : Option<usize>,
+    /// Media types of the responses of the endpoint, set with `produces`, in order of preference. Empty if not set.
+    pub produces : &'static [&'static str],
+    /// If no type of `produces` is acceptable for the client, the request fails with 406 Not Acceptable.
+    /// If `false`, the first one is used instead.
+    pub not_acceptable : bool,
+    /// Name of the root element of the XML responses, set with `xml_root`. `None` uses `response`.
+    pub xml_root : Option<&'static str>
 }
 
 impl Endpoint
 {
-    pub const fn new( url : &'static str, method : &'static str, fnc : fn( IO ) -> EndpointReturnType ) -> Self
+    pub const fn new( url : &'static str, method : &'static str, fnc : fn( IO ) -> EndpointReturnType, case_insensitive : bool ) -> Self
     {
         Self
         {
             url,
             method,
-            fnc
+            fnc,
+            case_insensitive,
+            name : "",
+            file : "",
+            line : 0,
+            middlewares : &[],
+            body_stream : false,
+            max_body : None,
+            produces : &[],
+            not_acceptable : true,
+            xml_root : None
         }
     }
+
+    /// Sets the handler name and the source location reported by `awpak_rs::routes()` and in route conflicts.
+    pub const fn with_source( mut self, name : &'static str, file : &'static str, line : u32 ) -> Self
+    {
+        self.name = name;
+        self.file = file;
+        self.line = line;
+
+        self
+    }
+
+    /// Sets the middlewares that only run for this endpoint.
+    pub const fn with_middlewares( mut self, middlewares : &'static [Middleware] ) -> Self
+    {
+        self.middlewares = middlewares;
+
+        self
+    }
+
+    /// Marks the endpoint as reading the body with `#[body_stream]`.
+    pub const fn with_body_stream( mut self ) -> Self
+    {
+        self.body_stream = true;
+
+        self
+    }
+
+    /// Sets the maximum size of the request body, in bytes, instead of the limit of the server.
+    pub const fn with_max_body( mut self, max_body : usize ) -> Self
+    {
+        self.max_body = Some( max_body );
+
+        self
+    }
+
+    /// Sets the media types the endpoint can respond with. The type of each response is chosen with the `Accept` header.
+    pub const fn with_produces( mut self, produces : &'static [&'static str] ) -> Self
+    {
+        self.produces = produces;
+
+        self
+    }
+
+    /// Sets if the endpoint fails with 406 Not Acceptable when the client accepts none of its `produces` types.
+    pub const fn with_not_acceptable( mut self, not_acceptable : bool ) -> Self
+    {
+        self.not_acceptable = not_acceptable;
+
+        self
+    }
+
+    /// Sets the name of the root element of the XML responses of the endpoint.
+    pub const fn with_xml_root( mut self, xml_root : &'static str ) -> Self
+    {
+        self.xml_root = Some( xml_root );
+
+        self
+    }
 }
 
 inventory::collect!( Endpoint );
\ No newline at end of file
@@ -1,10 +1,14 @@
 use crate::{io::io::IO, services::error::Error};
 
+/// Future returned by endpoints and `around` middlewares.
+///
+/// `Err` carries the `IO` along with the error, so the request, context and response
+/// still reach the error handlers and the POST middlewares.
 pub type EndpointReturnType = std::pin::Pin<std::boxed::Box<
                                     dyn std::future::Future<
                                         Output = std::result::Result<
                                             IO,
-                                            Error
+                                            ( IO, Error )
                                         >
                                     > 
                                     + std::marker::Send
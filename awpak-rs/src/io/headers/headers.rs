@@ -1,8 +1,6 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::HashMap;
 
-use regex::Regex;
-
-use super::{header_data::HeaderData, mime::Mime};
+use super::{header_data::HeaderData, media_type::MediaType, mime::Mime};
 
 /// Represents the HTTP headers of a request or response.
 ///
@@ -40,7 +38,7 @@ use super::{header_data::HeaderData, mime::Mime};
 pub struct Headers
 {
     headers : HashMap<String, Vec<HeaderData>>,
-    accept : BTreeSet<Mime>
+    accept : Vec<Mime>
 }
 
 impl Headers
@@ -50,64 +48,59 @@ impl Headers
         Self
         {
             headers : HashMap::new(),
-            accept : BTreeSet::new()
+            accept : vec![]
         }
     }
 
-    pub fn set_accept( &mut self, mimes : BTreeSet<Mime> )
+    /// Sets the media ranges of the `Accept` header, in the order of the header.
+    pub fn set_accept( &mut self, mimes : Vec<Mime> )
     {
         self.accept = mimes
     }
 
+    /// The media ranges of the `Accept` header, in the order of the header.
+    pub fn accept( &self ) -> &[Mime]
+    {
+        &self.accept
+    }
+
+    /// Chooses one of `availables` for the response, following the `Accept` header (RFC 9110, section 12.5.1).
+    ///
+    /// The quality of each available type is the one of the most specific range that matches it,
+    /// the first one if several are equally specific. Types with quality 0, or not matched by any range, are not acceptable.
+    /// The type with the highest quality is chosen, and between equal qualities, the first one in `availables`.
+    ///
+    /// Without an `Accept` header, returns the first of `availables`. Returns `None` if none is acceptable.
     pub fn content_negotiation( &self, availables : &[&str] ) -> Option<String>
     {
-        if self.accept.len() == 0 || availables.len() == 0
+        if self.accept.is_empty()
         {
-            return None
+            return availables.first().map( | a | a.to_string() )
         }
 
-        for mime in &self.accept
-        {
-            let mime_type = mime.get_mime_type();
+        let mut best : Option<( &str, f32 )> = None;
 
-            if mime.accept_all()
-            {
-                return Some( availables[ 0 ].to_string() )
-            }
-
-            if availables.contains( &mime_type.as_str() )
+        for available in availables
+        {
+            let media_type = match available.parse::<MediaType>()
             {
-                return Some( mime_type )
-            }
+                Ok( m ) => m,
+                _ => continue
+            };
 
-            if mime.accept_all_media_types()
-            {
-                let re = Regex::new( format!( r"^.+\/{}", mime.subtype ).as_str() ).unwrap();
-
-                for a in availables
-                {
-                    if re.is_match( a )
-                    {
-                        return Some( a.to_string() )
-                    }
-                }
-            }
+            let q = self.accept.iter()
+                .filter( | m | m.matches( &media_type ) )
+                .min_by_key( | m | std::cmp::Reverse( m.specificity() ) )
+                .map( | m | m.q.unwrap_or( 1.0 ) )
+                .unwrap_or( 0.0 );
 
-            if mime.accept_all_subtypes()
+            if q > 0.0 && best.is_none_or( | ( _, b ) | q > b )
             {
-                let re = Regex::new( format!( r"^{}\/.+", mime.media_type ).as_str() ).unwrap();
-
-                for a in availables
-                {
-                    if re.is_match( a )
-                    {
-                        return Some( a.to_string() )
-                    }
-                }
+                best = Some( ( available, q ) );
             }
         }
 
-        None
+        best.map( | ( a, _ ) | a.to_string() )
     }
 
     /// Retrieves the first `HeaderData` entry for a given header name.
@@ -142,6 +135,14 @@ impl Headers
         }
     }
 
+    /// Retrieves all the `HeaderData` entries for a given header name.
+    ///
+    /// Returns `None` if the header does not exist.
+    pub fn get_all( &self, key : &str ) -> Option<&Vec<HeaderData>>
+    {
+        self.headers.get( key )
+    }
+
     /// Checks if a given header exists in the collection.
     ///
     /// Returns `true` if the header exists, otherwise returns `false`.
@@ -182,6 +183,14 @@ impl Headers
         }
     }
 
+    /// Parses the `Content-Type` header.
+    ///
+    /// Returns `None` if the header does not exist or is not a valid media type.
+    pub fn content_type( &self ) -> Option<MediaType>
+    {
+        self.get( "content-type" )?.value.as_ref()?.parse().ok()
+    }
+
     /// Adds a new header entry to the collection.
     ///
     /// This function creates a new `HeaderData` entry and appends it to the existing headers.
@@ -257,6 +266,14 @@ impl Headers
         Ok( () )
     }
 
+    /// Removes all the values of a header.
+    ///
+    /// Returns the removed values, or `None` if the header was not found.
+    pub fn remove( &mut self, key : &str ) -> Option<Vec<HeaderData>>
+    {
+        self.headers.remove( key )
+    }
+
     /// Replaces all headers with a new set, except for `Accept`, which is kept unchanged.
     ///
     /// This method replaces the headers with a new `Headers` instance, except for the `"Accept"` header, which remains unchanged.
@@ -351,10 +368,10 @@ mod tests {
     {
         let mut headers = Headers::new();
 
-        headers.accept.insert( Mime::new( "*".to_string(), "*".to_string(), Some( 0.6 ) ) );
-        headers.accept.insert( Mime::new( "*".to_string(), "json".to_string(), Some( 0.9 ) ) );
-        headers.accept.insert( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
-        headers.accept.insert( Mime::new( "application".to_string(), "*".to_string(), Some( 0.8 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "*".to_string(), Some( 0.6 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "json".to_string(), Some( 0.9 ) ) );
+        headers.accept.push( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
+        headers.accept.push( Mime::new( "application".to_string(), "*".to_string(), Some( 0.8 ) ) );
 
         let media_type = headers.content_negotiation( AVAILABLES );
 
@@ -370,10 +387,10 @@ mod tests {
     {
         let mut headers = Headers::new();
 
-        headers.accept.insert( Mime::new( "*".to_string(), "*".to_string(), Some( 1.2 ) ) );
-        headers.accept.insert( Mime::new( "*".to_string(), "json".to_string(), Some( 0.9 ) ) );
-        headers.accept.insert( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
-        headers.accept.insert( Mime::new( "application".to_string(), "*".to_string(), Some( 0.8 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "*".to_string(), Some( 1.2 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "json".to_string(), Some( 0.9 ) ) );
+        headers.accept.push( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
+        headers.accept.push( Mime::new( "application".to_string(), "*".to_string(), Some( 0.8 ) ) );
 
         let media_type = headers.content_negotiation( AVAILABLES );
 
@@ -389,10 +406,10 @@ mod tests {
     {
         let mut headers = Headers::new();
 
-        headers.accept.insert( Mime::new( "*".to_string(), "*".to_string(), Some( 0.6 ) ) );
-        headers.accept.insert( Mime::new( "*".to_string(), "plain".to_string(), Some( 1.9 ) ) );
-        headers.accept.insert( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
-        headers.accept.insert( Mime::new( "application".to_string(), "*".to_string(), Some( 0.8 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "*".to_string(), Some( 0.6 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "plain".to_string(), Some( 1.9 ) ) );
+        headers.accept.push( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
+        headers.accept.push( Mime::new( "application".to_string(), "*".to_string(), Some( 0.8 ) ) );
 
         let media_type = headers.content_negotiation( AVAILABLES );
 
@@ -408,10 +425,10 @@ mod tests {
     {
         let mut headers = Headers::new();
 
-        headers.accept.insert( Mime::new( "*".to_string(), "*".to_string(), Some( 0.6 ) ) );
-        headers.accept.insert( Mime::new( "*".to_string(), "json".to_string(), Some( 0.9 ) ) );
-        headers.accept.insert( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
-        headers.accept.insert( Mime::new( "text".to_string(), "*".to_string(), Some( 1.2 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "*".to_string(), Some( 0.6 ) ) );
+        headers.accept.push( Mime::new( "*".to_string(), "json".to_string(), Some( 0.9 ) ) );
+        headers.accept.push( Mime::new( "application".to_string(), "json".to_string(), Some( 1.0 ) ) );
+        headers.accept.push( Mime::new( "text".to_string(), "*".to_string(), Some( 1.2 ) ) );
 
         let media_type = headers.content_negotiation( AVAILABLES );
 
@@ -421,4 +438,57 @@ mod tests {
 
         assert_eq!( media_type, "text/html" );
     }
+
+    fn accept_headers( accept : &str ) -> Headers
+    {
+        let mut headers = Headers::new();
+
+        headers.set_accept( accept.split( "," ).filter_map( | m | Mime::from_accept_str( m ).ok() ).collect() );
+
+        headers
+    }
+
+    #[test]
+    fn test_content_negotiation_without_accept()
+    {
+        assert_eq!( Headers::new().content_negotiation( AVAILABLES ).unwrap(), "text/html" );
+
+        assert!( Headers::new().content_negotiation( &[] ).is_none() );
+    }
+
+    #[test]
+    fn test_content_negotiation_specificity()
+    {
+        // The most specific range sets the quality, whatever its position
+        let headers = accept_headers( "text/*;q=0.3, text/html;q=0.7, text/html;level=1, */*;q=0.5" );
+
+        assert_eq!( headers.content_negotiation( AVAILABLES ).unwrap(), "text/html" );
+
+        assert_eq!( headers.content_negotiation( &[ "text/plain", "application/json" ] ).unwrap(), "application/json" );
+
+        assert_eq!( headers.content_negotiation( &[ "text/html;level=1", "text/html" ] ).unwrap(), "text/html;level=1" );
+    }
+
+    #[test]
+    fn test_content_negotiation_equal_q()
+    {
+        // Ranges with the same quality are all kept, the order of `availables` breaks the tie
+        let headers = accept_headers( "application/json;q=0.8, text/plain;q=0.8" );
+
+        assert_eq!( headers.content_negotiation( AVAILABLES ).unwrap(), "text/plain" );
+
+        assert_eq!( headers.content_negotiation( &[ "application/json", "text/plain" ] ).unwrap(), "application/json" );
+    }
+
+    #[test]
+    fn test_content_negotiation_not_acceptable()
+    {
+        let headers = accept_headers( "text/*, text/html;q=0, application/json;q=0" );
+
+        assert_eq!( headers.content_negotiation( AVAILABLES ).unwrap(), "text/plain" );
+
+        assert!( headers.content_negotiation( &[ "text/html", "application/json" ] ).is_none() );
+
+        assert!( accept_headers( "image/png" ).content_negotiation( AVAILABLES ).is_none() );
+    }
 }
\ No newline at end of file
@@ -0,0 +1,318 @@
+use std::{fmt::Display, str::FromStr};
+
+use hyper::body::Bytes;
+
+/// A media type, as used in the `Content-Type` header (RFC 9110, section 8.3.1).
+///
+/// The type, the subtype and the names of the parameters are case-insensitive, so they are stored in lowercase.
+/// The values of the parameters keep their case, without the quotes.
+///
+/// # Example
+///
+/// ```
+/// use awpak_rs::io::headers::media_type::MediaType;
+///
+/// let media_type : MediaType = "Application/JSON; Charset=\"ISO-8859-1\"".parse().unwrap();
+///
+/// assert_eq!( media_type.get_mime_type(), "application/json" );
+/// assert_eq!( media_type.charset(), Some( "ISO-8859-1" ) );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaType
+{
+    pub media_type : String,
+    pub subtype : String,
+    pub parameters : Vec<( String, String )>
+}
+
+impl MediaType
+{
+    pub fn new( media_type : &str, subtype : &str ) -> Self
+    {
+        Self
+        {
+            media_type : media_type.to_lowercase(),
+            subtype : subtype.to_lowercase(),
+            parameters : vec![]
+        }
+    }
+
+    pub fn with_parameter( mut self, name : &str, value : &str ) -> Self
+    {
+        self.parameters.push( ( name.to_lowercase(), value.to_string() ) );
+
+        self
+    }
+
+    /// The media type without parameters, as `type/subtype`.
+    pub fn get_mime_type( &self ) -> String
+    {
+        format!( "{}/{}", self.media_type, self.subtype )
+    }
+
+    /// Returns `true` if the type and subtype are `mime_type`, ignoring the parameters.
+    pub fn is( &self, mime_type : &str ) -> bool
+    {
+        self.get_mime_type().eq_ignore_ascii_case( mime_type.trim() )
+    }
+
+    pub fn get_parameter( &self, name : &str ) -> Option<&str>
+    {
+        self.parameters.iter()
+        .find( | ( n, _ ) | n.eq_ignore_ascii_case( name ) )
+        .map( | ( _, v ) | v.as_str() )
+    }
+
+    pub fn charset( &self ) -> Option<&str>
+    {
+        self.get_parameter( "charset" )
+    }
+
+    /// Decodes `bytes` from the charset of the media type to UTF-8.
+    ///
+    /// Without a charset, or with a UTF-8 one, the bytes are returned as they are.
+    /// Fails if the charset is unknown.
+    pub fn decode( &self, bytes : Bytes ) -> Result<Bytes, String>
+    {
+        let encoding = match self.get_encoding()?
+        {
+            Some( e ) => e,
+            _ => return Ok( bytes )
+        };
+
+        let ( text, _ ) = encoding.decode_without_bom_handling( &bytes );
+
+        Ok( Bytes::from( text.into_owned() ) )
+    }
+
+    /// Encodes the UTF-8 `bytes` in the charset of the media type.
+    ///
+    /// Without a charset, with a UTF-8 or unknown one, or if `bytes` is not valid UTF-8, the bytes are returned as they are.
+    pub fn encode( &self, bytes : Bytes ) -> Bytes
+    {
+        let encoding = match self.get_encoding()
+        {
+            Ok( Some( e ) ) => e,
+            _ => return bytes
+        };
+
+        match std::str::from_utf8( &bytes )
+        {
+            Ok( text ) => Bytes::from( encoding.encode( text ).0.into_owned() ),
+            _ => bytes
+        }
+    }
+
+    // `None` if there is nothing to convert
+    fn get_encoding( &self ) -> Result<Option<&'static encoding_rs::Encoding>, String>
+    {
+        let charset = match self.charset()
+        {
+            Some( c ) => c,
+            _ => return Ok( None )
+        };
+
+        match encoding_rs::Encoding::for_label( charset.as_bytes() )
+        {
+            Some( e ) if e == encoding_rs::UTF_8 => Ok( None ),
+            Some( e ) => Ok( Some( e ) ),
+            _ => Err( format!( "Unknown charset: {}", charset ) )
+        }
+    }
+}
+
+impl FromStr for MediaType
+{
+    type Err = ();
+
+    fn from_str( value : &str ) -> Result<Self, Self::Err>
+    {
+        let mut parts = split_parameters( value ).into_iter();
+
+        let ( media_type, subtype ) = match parts.next().as_deref().and_then( | m | m.split_once( "/" ) )
+        {
+            Some( ( m, s ) ) if is_token( m.trim() ) && is_token( s.trim() ) => ( m.trim().to_string(), s.trim().to_string() ),
+            _ => return Err( () )
+        };
+
+        let mut ret = Self::new( &media_type, &subtype );
+
+        for parameter in parts
+        {
+            // Empty parameters, as in `text/plain;`, are ignored
+            if parameter.trim().is_empty()
+            {
+                continue
+            }
+
+            match parameter.split_once( "=" )
+            {
+                Some( ( n, v ) ) if is_token( n.trim() ) => {
+                    ret = ret.with_parameter( n.trim(), &unquote( v.trim() ) );
+                },
+                _ => return Err( () )
+            }
+        }
+
+        Ok( ret )
+    }
+}
+
+impl Display for MediaType
+{
+    fn fmt( &self, f : &mut std::fmt::Formatter<'_> ) -> std::fmt::Result
+    {
+        write!( f, "{}", self.get_mime_type() )?;
+
+        for ( name, value ) in &self.parameters
+        {
+            if is_token( value )
+            {
+                write!( f, "; {}={}", name, value )?;
+            }
+            else
+            {
+                write!( f, "; {}=\"{}\"", name, value.replace( "\\", "\\\\" ).replace( "\"", "\\\"" ) )?;
+            }
+        }
+
+        Ok( () )
+    }
+}
+
+// Splits by `;`, except inside quoted strings
+fn split_parameters( value : &str ) -> Vec<String>
+{
+    let mut parts = vec![];
+
+    let mut current = String::new();
+
+    let mut quoted = false;
+
+    let mut escaped = false;
+
+    for c in value.chars()
+    {
+        match c
+        {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = ! quoted,
+            ';' if ! quoted => {
+                parts.push( std::mem::take( &mut current ) );
+
+                continue
+            },
+            _ => {}
+        }
+
+        current.push( c );
+    }
+
+    parts.push( current );
+
+    parts
+}
+
+fn unquote( value : &str ) -> String
+{
+    if value.len() < 2 || ! value.starts_with( '"' ) || ! value.ends_with( '"' )
+    {
+        return value.to_string()
+    }
+
+    let mut ret = String::new();
+
+    let mut escaped = false;
+
+    for c in value[ 1..value.len() - 1 ].chars()
+    {
+        match c
+        {
+            '\\' if ! escaped => escaped = true,
+            _ => {
+                escaped = false;
+
+                ret.push( c );
+            }
+        }
+    }
+
+    ret
+}
+
+fn is_token( value : &str ) -> bool
+{
+    ! value.is_empty() && value.chars().all( | c | c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains( c ) )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_type_from_str()
+    {
+        let media_type = "application/json".parse::<MediaType>();
+
+        assert!( media_type.is_ok() );
+
+        let media_type = media_type.unwrap();
+
+        assert_eq!( media_type.get_mime_type(), "application/json" );
+
+        assert!( media_type.parameters.is_empty() );
+
+        assert_eq!( media_type.charset(), None );
+    }
+
+    #[test]
+    fn test_media_type_from_str_parameters()
+    {
+        let media_type = "Text/HTML ; Charset=UTF-8; q=\"a \\\"b\\\"; c\";".parse::<MediaType>();
+
+        assert!( media_type.is_ok() );
+
+        let media_type = media_type.unwrap();
+
+        assert_eq!( media_type.get_mime_type(), "text/html" );
+
+        assert!( media_type.is( "TEXT/html" ) );
+
+        assert_eq!( media_type.charset(), Some( "UTF-8" ) );
+
+        assert_eq!( media_type.get_parameter( "Q" ), Some( "a \"b\"; c" ) );
+
+        assert_eq!( media_type.to_string(), "text/html; charset=UTF-8; q=\"a \\\"b\\\"; c\"" );
+    }
+
+    #[test]
+    fn test_media_type_from_str_invalid()
+    {
+        assert!( "".parse::<MediaType>().is_err() );
+        assert!( "application".parse::<MediaType>().is_err() );
+        assert!( "application/".parse::<MediaType>().is_err() );
+        assert!( "application/json, text/plain".parse::<MediaType>().is_err() );
+        assert!( "application/json; charset".parse::<MediaType>().is_err() );
+    }
+
+    #[test]
+    fn test_media_type_decode()
+    {
+        let media_type = MediaType::new( "text", "plain" ).with_parameter( "charset", "iso-8859-1" );
+
+        let decoded = media_type.decode( Bytes::from_static( b"M\xe1laga" ) );
+
+        assert_eq!( decoded.unwrap(), Bytes::from( "Málaga" ) );
+
+        assert_eq!( media_type.encode( Bytes::from( "Málaga" ) ), Bytes::from_static( b"M\xe1laga" ) );
+
+        let media_type = MediaType::new( "text", "plain" ).with_parameter( "charset", "utf-8" );
+
+        assert_eq!( media_type.decode( Bytes::from( "Málaga" ) ).unwrap(), Bytes::from( "Málaga" ) );
+
+        let media_type = MediaType::new( "text", "plain" ).with_parameter( "charset", "unknown" );
+
+        assert!( media_type.decode( Bytes::from( "Málaga" ) ).is_err() );
+    }
+}
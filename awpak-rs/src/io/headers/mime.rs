@@ -1,11 +1,15 @@
-use std::cmp::Ordering;
+use super::media_type::MediaType;
 
-#[derive(Clone)]
+/// A media range of the `Accept` header, with its quality (RFC 9110, section 12.5.1).
+///
+/// `parameters` are the parameters of the media range, before `q`. The extension parameters after `q` are ignored.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Mime
 {
     pub media_type : String,
     pub subtype : String,
-    pub q : Option<f32>
+    pub q : Option<f32>,
+    pub parameters : Vec<( String, String )>
 }
 
 impl Mime
@@ -16,10 +20,18 @@ impl Mime
         {
             media_type,
             subtype,
-            q
+            q,
+            parameters : vec![]
         }
     }
 
+    pub fn with_parameter( mut self, name : &str, value : &str ) -> Self
+    {
+        self.parameters.push( ( name.to_lowercase(), value.to_string() ) );
+
+        self
+    }
+
     pub fn get_mime_type( &self ) -> String
     {
         format!( "{}/{}", self.media_type, self.subtype )
@@ -40,115 +52,68 @@ impl Mime
         self.subtype == "*"
     }
 
-    pub fn from_accept_str( value : &str ) -> Result<Mime, ()>
+    /// Returns `true` if `media_type` is in the range, including the parameters of the range.
+    ///
+    /// Besides `*/*` and `type/*`, ranges like `*/json` are accepted.
+    pub fn matches( &self, media_type : &MediaType ) -> bool
     {
-        let v = value.trim();
-
-        if v == ""
-        {
-            return Err( () );
-        }
-
-        let q = if v.contains( ";" )
-        {
-            let parts : Vec<&str> = v.split( ";q=" ).collect();
+        ( self.accept_all_media_types() || self.media_type.eq_ignore_ascii_case( &media_type.media_type ) ) &&
+        ( self.accept_all_subtypes() || self.subtype.eq_ignore_ascii_case( &media_type.subtype ) ) &&
+        self.parameters.iter().all( | ( n, v ) | media_type.get_parameter( n ).is_some_and( | p | p.eq_ignore_ascii_case( v ) ) )
+    }
 
-            if parts.len() == 2
-            {
-                let val = parts[ 1 ].parse::<f32>();
-
-                if val.is_ok()
-                {
-                    val.unwrap()
-                }
-                else
-                {
-                    return Err( () )  
-                }
-            }
-            else
-            {
-                return Err( () ) 
-            }
-        }
-        else
-        {
-            1.0
-        };
+    /// How specific the range is. When several ranges match a media type, the most specific one sets its quality.
+    ///
+    /// `type/subtype` is more specific than `type/*` (or `*/subtype`), and this than `*/*`.
+    /// Between ranges with the same type and subtype, the one with more parameters is more specific.
+    pub fn specificity( &self ) -> ( u8, usize )
+    {
+        ( u8::from( ! self.accept_all_media_types() ) + u8::from( ! self.accept_all_subtypes() ), self.parameters.len() )
+    }
 
-        let v = v.split( ";" ).next().unwrap().trim();
+    pub fn from_accept_str( value : &str ) -> Result<Mime, ()>
+    {
+        let media_type = value.parse::<MediaType>()?;
 
-        let parts = v.split( "/" ).collect::<Vec<&str>>();
+        let mut parameters = media_type.parameters;
 
-        if parts.len() != 2
+        let q = match parameters.iter().position( | ( n, _ ) | n == "q" )
         {
-            return Err( () );
-        }
+            Some( pos ) => {
 
-        let media_type = parts[ 0 ].trim();
-        let subtype = parts[ 1 ].trim();
+                let q = parse_qvalue( &parameters[ pos ].1 )?;
 
-        if media_type == "" || subtype == ""
-        {
-            return Err( () );
-        }
+                parameters.truncate( pos );
+
+                q
+            },
+            _ => 1.0
+        };
 
         Ok(
-            Mime::new( media_type.to_string(), subtype.to_string(), Some( q ) )
+            Self
+            {
+                media_type : media_type.media_type,
+                subtype : media_type.subtype,
+                q : Some( q ),
+                parameters
+            }
         )
     }
 }
 
-impl Ord for Mime
+// A quality between 0 and 1, with up to three decimals
+fn parse_qvalue( value : &str ) -> Result<f32, ()>
 {
-    fn cmp( &self, other: &Self ) -> std::cmp::Ordering
-    {
-        if self.q.is_none() && other.q.is_none()
-        {
-            return Ordering::Equal
-        }
+    let decimals = value.split_once( "." ).map( | ( _, d ) | d.len() ).unwrap_or( 0 );
 
-        if self.q.is_none()
-        {
-            return Ordering::Greater
-        }
-
-        if other.q.is_none()
-        {
-            return Ordering::Less
-        }
-
-        other.q.unwrap().total_cmp( &self.q.unwrap() )
-    }
-}
-
-impl PartialOrd for Mime
-{
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl PartialEq for Mime
-{
-    fn eq (&self, other: &Self ) -> bool
+    match value.parse::<f32>()
     {
-        if self.q.is_none() && other.q.is_none()
-        {
-            return true
-        }
-
-        if self.q.is_none() || other.q.is_none()
-        {
-            return false
-        }
-        
-        self.q.unwrap() == other.q.unwrap()
+        Ok( q ) if ( 0.0..=1.0 ).contains( &q ) && decimals <= 3 => Ok( q ),
+        _ => Err( () )
     }
 }
 
-impl Eq for Mime { }
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,13 +176,33 @@ mod tests {
     }
 
     #[test]
-    fn test_from_accept_str_invalid_1()
+    fn test_from_accept_str_parameters()
     {
-        let accept = "*/*;rq=0.6";
+        let accept = "text/html; level=1; q = 0.5; ext=x";
 
         let mime = Mime::from_accept_str( &accept );
 
-        assert!( mime.is_err() );
+        assert!( mime.is_ok() );
+
+        let mime = mime.unwrap();
+
+        assert_eq!( mime.q.unwrap(), 0.5 );
+
+        assert_eq!( mime.get_mime_type(), "text/html" );
+        assert_eq!( mime.parameters, vec![ ( "level".to_string(), "1".to_string() ) ] );
+
+        let mime = Mime::from_accept_str( "*/*;rq=0.6" ).unwrap();
+
+        assert_eq!( mime.q.unwrap(), 1.0 );
+        assert_eq!( mime.parameters, vec![ ( "rq".to_string(), "0.6".to_string() ) ] );
+    }
+
+    #[test]
+    fn test_from_accept_str_invalid_1()
+    {
+        assert!( Mime::from_accept_str( "*/*;q=1.5" ).is_err() );
+        assert!( Mime::from_accept_str( "*/*;q=0.1234" ).is_err() );
+        assert!( Mime::from_accept_str( "*/*;q=-1" ).is_err() );
     }
 
     #[test]
@@ -249,4 +234,23 @@ mod tests {
 
         assert!( mime.is_err() );
     }
+
+    #[test]
+    fn test_matches()
+    {
+        let html = "text/html; level=1; charset=utf-8".parse::<MediaType>().unwrap();
+
+        assert!( Mime::from_accept_str( "text/html" ).unwrap().matches( &html ) );
+        assert!( Mime::from_accept_str( "text/*" ).unwrap().matches( &html ) );
+        assert!( Mime::from_accept_str( "*/html" ).unwrap().matches( &html ) );
+        assert!( Mime::from_accept_str( "*/*" ).unwrap().matches( &html ) );
+        assert!( Mime::from_accept_str( "Text/HTML;Level=1" ).unwrap().matches( &html ) );
+        assert!( ! Mime::from_accept_str( "text/html;level=2" ).unwrap().matches( &html ) );
+        assert!( ! Mime::from_accept_str( "text/plain" ).unwrap().matches( &html ) );
+        assert!( ! Mime::from_accept_str( "application/*" ).unwrap().matches( &html ) );
+
+        assert!( Mime::from_accept_str( "text/html;level=1" ).unwrap().specificity() > Mime::from_accept_str( "text/html" ).unwrap().specificity() );
+        assert!( Mime::from_accept_str( "text/html" ).unwrap().specificity() > Mime::from_accept_str( "text/*" ).unwrap().specificity() );
+        assert!( Mime::from_accept_str( "text/*" ).unwrap().specificity() > Mime::from_accept_str( "*/*" ).unwrap().specificity() );
+    }
 }
\ No newline at end of file
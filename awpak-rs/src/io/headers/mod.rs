@@ -1,3 +1,4 @@
 pub mod headers;
 pub mod header_data;
-pub mod mime;
\ No newline at end of file
+pub mod mime;
+pub mod media_type;
\ No newline at end of file
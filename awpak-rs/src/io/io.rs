@@ -1,5 +1,7 @@
 use std::any::Any;
 
+use crate::services::error::Error;
+
 use super::{request::request_data::RequestData, response::response_data::ResponseData};
 
 /// Represents the input and output data for an HTTP request in `awpak-rs`.
@@ -22,12 +24,15 @@ use super::{request::request_data::RequestData, response::response_data::Respons
 /// - `response`: Holds the data that will be sent back to the client, including:
 ///   - The HTTP status code (`status`).
 ///   - The response headers (`headers`).
-///   - The response body (`body`), which is always serialized into JSON unless set to `None`.
+///   - The response body (`body`), which is serialized into JSON, or sent verbatim if it's raw bytes, unless set to `None`.
 ///   - The response cookies (`cookies`).
 ///
 /// - `context`: An optional field (`Option<Box<dyn Any + Send + Sync>>`) that can store arbitrary data.
 ///   Middlewares and endpoints can use this to share information, such as authentication details.
 ///
+/// - `error`: The error of the request, if the endpoint failed or no endpoint was found.
+///   POST middlewares receive the original request, context and response along with the error.
+///
 /// # Example: Logging Middleware
 ///
 /// This middleware logs the request method and path before passing control to the next middleware or endpoint.
@@ -52,7 +57,7 @@ use super::{request::request_data::RequestData, response::response_data::Respons
 /// #[middleware]
 /// fn modify_response_middleware(mut io: IO) -> MiddlewareResponse {
 ///     io.response.status = 200;
-///     io.response.body = Some(serde_json::json!({"message": "Middleware modified this response"}));
+///     io.response.body = Some(serde_json::json!({"message": "Middleware modified this response"}).into());
 ///     MiddlewareResponse::Next(io)
 /// }
 /// ```
@@ -65,7 +70,10 @@ pub struct IO
     pub response : ResponseData,
 
     /// An optional context that middlewares and endpoints can use to store and retrieve arbitrary data.
-    context : Option<Box<dyn Any + Send + Sync>>
+    context : Option<Box<dyn Any + Send + Sync>>,
+
+    /// The error of the request, if the endpoint failed or no endpoint was found. See `with_error`.
+    pub error : Option<Error>
 }
 
 impl IO
@@ -87,7 +95,8 @@ impl IO
         {
             request,
             response,
-            context
+            context,
+            error : None
         }
     }
 
@@ -106,10 +115,33 @@ impl IO
         {
             request : RequestData::default(),
             response,
-            context : None
+            context : None,
+            error : None
         }
     }
 
+    /// Records an error of the request and prepares the error response.
+    ///
+    /// The status code and the headers of the error (like `Allow` for a 405) are set in the response,
+    /// and the body is replaced with `"Server Error"`, or with the message of the error for a `BadRequest`, a `NotAcceptable` or a `PayloadTooLarge`. The request, the context, and the headers and cookies
+    /// already set in the response are kept, so POST middlewares can still use them.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error of the request.
+    pub fn with_error( mut self, error : Error ) -> Self
+    {
+        self.response.status = error.get_status_code();
+
+        self.response.headers.replace_headers( error.get_headers() );
+
+        self.response.body = Some( error.get_body().into() );
+
+        self.error = Some( error );
+
+        self
+    }
+
     /// Sets the context of the `IO` instance to a given value.
     ///
     /// This allows middlewares or endpoints to store arbitrary data in the context.
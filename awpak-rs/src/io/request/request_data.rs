@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use crate::{body::body::BodyData, io::{cookies::cookies::Cookies, headers::headers::Headers}};
+use hyper::upgrade::OnUpgrade;
+
+use crate::{body::{body::BodyData, body_stream::RequestBodyStream}, io::{cookies::cookies::Cookies, headers::headers::Headers}, services::endpoint::router::ResolvedRoute};
 
 /// Represents an incoming HTTP request.
 ///
@@ -96,7 +98,25 @@ pub struct RequestData
     ///     println!("Session ID: {}", cookie.value());
     /// }
     /// ```
-    pub cookies : Cookies
+    pub cookies : Cookies,
+
+    /// The path variables of the endpoint that handles the request, keyed by name.
+    ///
+    /// Values are percent-decoded, so a request to `/files/my%20report` handled by
+    /// `/files/{name}` will have `path_variables = { "name" => "my report" }`.
+    ///
+    /// The endpoint is resolved after the middlewares marked with `execute_after=false`,
+    /// so this map is empty when they run.
+    pub path_variables : HashMap<String, String>,
+
+    /// The connection upgrade of the request, taken by `#[websocket]` endpoints.
+    pub(crate) upgrade : Option<OnUpgrade>,
+
+    /// The body of a `#[body_stream]` endpoint, not buffered.
+    pub(crate) body_stream : Option<RequestBodyStream>,
+
+    /// The endpoint found for the request when it was read.
+    pub(crate) route : Option<ResolvedRoute>
 }
 
 /// Represents a parsed URI from an incoming HTTP request.
@@ -216,10 +236,43 @@ impl RequestData
             method,
             headers,
             body,
-            cookies
+            cookies,
+            path_variables : HashMap::new(),
+            upgrade : None,
+            body_stream : None,
+            route : None
         }
     }
 
+    pub(crate) fn with_upgrade( mut self, upgrade : Option<OnUpgrade> ) -> Self
+    {
+        self.upgrade = upgrade;
+
+        self
+    }
+
+    pub(crate) fn with_body_stream( mut self, body_stream : Option<RequestBodyStream> ) -> Self
+    {
+        self.body_stream = body_stream;
+
+        self
+    }
+
+    pub(crate) fn with_route( mut self, route : Option<ResolvedRoute> ) -> Self
+    {
+        self.route = route;
+
+        self
+    }
+
+    /// Takes the body of a `#[body_stream]` endpoint.
+    ///
+    /// The body can only be taken once. Later calls, and calls for the endpoints without `#[body_stream]`, return an empty body.
+    pub fn take_body_stream( &mut self ) -> RequestBodyStream
+    {
+        self.body_stream.take().unwrap_or_else( RequestBodyStream::empty )
+    }
+
     pub fn get_headers( &self ) -> Headers
     {
         self.headers.clone()
@@ -240,8 +293,12 @@ impl Default for RequestData
             uri : Uri::default(),
             method : "get".to_string(),
             headers : Headers::new(),
-            body : BodyData { value : None, files : vec![] },
-            cookies : Cookies::new()
+            body : BodyData { value : None, files : vec![], raw : None, error : None },
+            cookies : Cookies::new(),
+            path_variables : HashMap::new(),
+            upgrade : None,
+            body_stream : None,
+            route : None
         }
     }
 }
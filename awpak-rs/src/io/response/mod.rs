@@ -1 +1,4 @@
-pub mod response_data;
\ No newline at end of file
+pub mod response_data;
+pub mod response_types;
+pub mod response_body;
+pub mod sse;
\ No newline at end of file
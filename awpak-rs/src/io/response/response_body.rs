@@ -0,0 +1,106 @@
+use std::{pin::Pin, sync::Mutex};
+
+use futures::Stream;
+use hyper::body::Bytes;
+use serde_json::Value;
+
+/// A stream of chunks of a response body.
+pub type BodyStream = Pin<Box<dyn Stream<Item = Bytes> + Send>>;
+
+/// The stream of a `ResponseBody::Stream`.
+///
+/// The stream is kept in a `Mutex` so the response, and the `IO`, are `Sync` even if the stream is not.
+pub struct ResponseStream( Mutex<BodyStream> );
+
+impl ResponseStream
+{
+    pub fn new( stream : BodyStream ) -> Self
+    {
+        Self( Mutex::new( stream ) )
+    }
+
+    pub fn into_inner( self ) -> BodyStream
+    {
+        match self.0.into_inner()
+        {
+            Ok( s ) => s,
+            Err( e ) => e.into_inner()
+        }
+    }
+}
+
+/// The body of a response.
+///
+/// - `Value`: a value serialized with the content type chosen by content negotiation (`application/json` or `text/plain`).
+///   This is the body of endpoints that return a type that implements `Serialize`.
+/// - `Bytes`: bytes sent verbatim with the given content type.
+///   This is the body of endpoints that return `Vec<u8>`, `Bytes` or `Binary`.
+/// - `Stream`: chunks sent as they are produced, with chunked transfer encoding.
+///   This is the body of endpoints that return `ByteStream` or `Sse`.
+pub enum ResponseBody
+{
+    Value( Value ),
+    Bytes { bytes : Bytes, content_type : String },
+    Stream { stream : ResponseStream, content_type : String }
+}
+
+impl ResponseBody
+{
+    /// Returns the value of the body, if it's not bytes.
+    pub fn as_value( &self ) -> Option<&Value>
+    {
+        match self
+        {
+            ResponseBody::Value( v ) => Some( v ),
+            _ => None
+        }
+    }
+
+    /// Returns the value of the body, if it's not bytes, to be modified.
+    pub fn as_value_mut( &mut self ) -> Option<&mut Value>
+    {
+        match self
+        {
+            ResponseBody::Value( v ) => Some( v ),
+            _ => None
+        }
+    }
+}
+
+impl From<Value> for ResponseBody
+{
+    fn from( value : Value ) -> Self
+    {
+        ResponseBody::Value( value )
+    }
+}
+
+impl std::fmt::Debug for ResponseBody
+{
+    fn fmt( &self, f : &mut std::fmt::Formatter<'_> ) -> std::fmt::Result
+    {
+        match self
+        {
+            ResponseBody::Value( v ) => f.debug_tuple( "Value" ).field( v ).finish(),
+            ResponseBody::Bytes { bytes, content_type } => f.debug_struct( "Bytes" ).field( "bytes", bytes ).field( "content_type", content_type ).finish(),
+            ResponseBody::Stream { content_type, .. } => f.debug_struct( "Stream" ).field( "content_type", content_type ).finish_non_exhaustive()
+        }
+    }
+}
+
+// Streams are never equal, they can only be compared by consuming them.
+impl PartialEq for ResponseBody
+{
+    fn eq( &self, other : &Self ) -> bool
+    {
+        match ( self, other )
+        {
+            ( ResponseBody::Value( a ), ResponseBody::Value( b ) ) => a == b,
+            (
+                ResponseBody::Bytes { bytes : a, content_type : ca },
+                ResponseBody::Bytes { bytes : b, content_type : cb }
+            ) => a == b && ca == cb,
+            _ => false
+        }
+    }
+}
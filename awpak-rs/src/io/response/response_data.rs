@@ -1,6 +1,8 @@
 
 use crate::io::{cookies::cookies::Cookies, headers::headers::Headers};
 
+use super::response_body::ResponseBody;
+
 
 
 /// Represents an HTTP response.
@@ -9,8 +11,8 @@ use crate::io::{cookies::cookies::Cookies, headers::headers::Headers};
 /// It is used to define the response that will be sent back to the client after processing
 /// an HTTP request.
 ///
-/// The response body is stored as a `serde_json::Value`, meaning any type that implements
-/// `Serialize` from `serde` can be used as a response in an endpoint.
+/// The response body is usually stored as a `serde_json::Value`, meaning any type that implements
+/// `Serialize` from `serde` can be used as a response in an endpoint. It can also be raw bytes (see `ResponseBody`).
 ///
 /// Middlewares that run after an endpoint can modify this structure, including changing
 /// the status code, adding headers and cookies, or modifying the response body.
@@ -71,6 +73,9 @@ pub struct ResponseData
     /// If the endpoint returns `None`, the body remains `None`.  
     /// Otherwise, the return value is automatically converted to a `serde_json::Value`.
     ///
+    /// An endpoint that returns `Vec<u8>`, `Bytes` or `Binary` sets a `ResponseBody::Bytes` body instead,
+    /// which is sent verbatim.
+    ///
     /// Middlewares that execute after an endpoint can modify this value using the API provided by `serde_json`.
     ///
     /// # Example: Modifying the response body in a middleware
@@ -79,23 +84,40 @@ pub struct ResponseData
     /// 
     /// let mut response_data = ResponseData::default();
     /// 
-    /// if let Some(json_body) = &mut response_data.body {
+    /// response_data.body = Some(serde_json::json!({}).into());
+    ///
+    /// if let Some(json_body) = response_data.body_value_mut() {
     ///     json_body["message"] = serde_json::Value::String("Modified by middleware".to_string());
     /// }
     /// ```
-    pub body : Option<serde_json::Value>
+    pub body : Option<ResponseBody>,
+
+    /// The name of the root element of an XML response, with the `xml` feature. `None` uses `response`.
+    ///
+    /// It's set from the `xml_root` attribute of the endpoint before it runs, so middlewares can change it.
+    ///
+    /// # Example: Setting the root element
+    /// ```rust
+    /// use awpak_rs::io::response::response_data::ResponseData;
+    /// 
+    /// let mut response_data = ResponseData::default();
+    /// 
+    /// response_data.xml_root = Some("user".to_string());
+    /// ```
+    pub xml_root : Option<String>
 }
 
 impl ResponseData
 {
-    pub fn new( status : u16, headers : Headers, cookies : Cookies, body : Option<serde_json::Value> ) -> Self
+    pub fn new( status : u16, headers : Headers, cookies : Cookies, body : Option<ResponseBody> ) -> Self
     {
         Self
         {
             status,
             headers,
             cookies,
-            body
+            body,
+            xml_root : None
         }
     }
 
@@ -106,7 +128,8 @@ impl ResponseData
             status : 200,
             headers : Headers::new(),
             body : None,
-            cookies : Cookies::new()
+            cookies : Cookies::new(),
+            xml_root : None
         }
     }
 
@@ -119,4 +142,16 @@ impl ResponseData
     {
         self.cookies.clone()
     }
+
+    /// Returns the body, if it's a `serde_json::Value`.
+    pub fn body_value( &self ) -> Option<&serde_json::Value>
+    {
+        self.body.as_ref().and_then( | b | b.as_value() )
+    }
+
+    /// Returns the body, if it's a `serde_json::Value`, to be modified.
+    pub fn body_value_mut( &mut self ) -> Option<&mut serde_json::Value>
+    {
+        self.body.as_mut().and_then( | b | b.as_value_mut() )
+    }
 }
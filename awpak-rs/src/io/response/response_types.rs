@@ -0,0 +1,357 @@
+use futures::Stream;
+use hyper::body::Bytes;
+use serde::Serialize;
+
+use crate::{io::{headers::headers::Headers, io::IO}, services::{error::Error, response::into_response::IntoResponse}, serialize_value};
+
+use super::response_body::{BodyStream, ResponseBody, ResponseStream};
+
+/// A response with a status, headers, cookies and a body, returned by an endpoint.
+///
+/// It allows to build the response in plain functions, without `set_status_code!`, `redirect_to!`
+/// or `#[response_headers]` parameters.
+///
+/// # Example
+///
+/// ```ignore
+/// #[post(url = "/user")]
+/// async fn create_user(#[request_body] user: User) -> Response<User> {
+///     Response::created(user)
+///         .header("location", "/user/1")
+///         .cookie("last_user=1; Path=/")
+/// }
+/// ```
+///
+/// If a cookie can't be parsed, the response is a 500 error.
+pub struct Response<T>
+{
+    status : u16,
+    headers : Headers,
+    cookies : Vec<String>,
+    body : Option<T>
+}
+
+impl<T> Response<T>
+{
+    /// Creates a `200 OK` response with the given body.
+    pub fn new( body : T ) -> Self
+    {
+        Self::with_status( 200, body )
+    }
+
+    /// Creates a `201 Created` response with the given body.
+    pub fn created( body : T ) -> Self
+    {
+        Self::with_status( 201, body )
+    }
+
+    /// Creates a response with the given status and body.
+    pub fn with_status( status : u16, body : T ) -> Self
+    {
+        Self
+        {
+            status,
+            headers : Headers::new(),
+            cookies : vec![],
+            body : Some( body )
+        }
+    }
+
+    /// Sets the status code of the response.
+    pub fn status( mut self, status : u16 ) -> Self
+    {
+        self.status = status;
+
+        self
+    }
+
+    /// Sets a header, replacing previous values with the same name.
+    pub fn header( mut self, name : &str, value : &str ) -> Self
+    {
+        self.headers.replace_header( name.to_string(), value.to_string() );
+
+        self
+    }
+
+    /// Sets a cookie, like `"session_id=abc123; Path=/; HttpOnly"`, replacing previous cookies with the same name.
+    pub fn cookie( mut self, cookie : &str ) -> Self
+    {
+        self.cookies.push( cookie.to_string() );
+
+        self
+    }
+}
+
+impl<T> IntoResponse for Response<T>
+where T: Serialize
+{
+    fn into_response( self, mut io : IO ) -> IO
+    {
+        io.response.status = self.status;
+
+        io.response.headers.replace_headers( self.headers );
+
+        for cookie in &self.cookies
+        {
+            if io.response.cookies.replace_cookie( cookie ).is_err()
+            {
+                return io.with_error( Error::EndpointExecution( format!( "Invalid cookie: {}", cookie ) ) )
+            }
+        }
+
+        io.response.body = match self.body
+        {
+            Some( b ) => serialize_value( b ).map( ResponseBody::from ),
+            _ => None
+        };
+
+        io
+    }
+}
+
+/// A `201 Created` response with the given body.
+pub struct Created<T>( pub T );
+
+impl<T> IntoResponse for Created<T>
+where T: Serialize
+{
+    fn into_response( self, io : IO ) -> IO
+    {
+        Response::created( self.0 ).into_response( io )
+    }
+}
+
+/// A `204 No Content` response, without body.
+pub struct NoContent;
+
+impl IntoResponse for NoContent
+{
+    fn into_response( self, mut io : IO ) -> IO
+    {
+        io.response.status = 204;
+
+        io.response.body = None;
+
+        io
+    }
+}
+
+/// A redirection to another URL, sent in the `Location` header.
+///
+/// # Example
+///
+/// ```ignore
+/// #[post(url = "/login")]
+/// async fn login() -> Redirect {
+///     Redirect::see_other("/home")
+/// }
+/// ```
+pub struct Redirect
+{
+    status : u16,
+    location : String
+}
+
+impl Redirect
+{
+    /// `303 See Other`: the client gets the new URL with `GET`.
+    pub fn see_other( url : &str ) -> Self
+    {
+        Self::with_status( 303, url )
+    }
+
+    /// `307 Temporary Redirect`: the client repeats the request, with the same method, at the new URL.
+    pub fn temporary( url : &str ) -> Self
+    {
+        Self::with_status( 307, url )
+    }
+
+    /// `308 Permanent Redirect`: like `temporary`, but the client can remember the new URL.
+    pub fn permanent( url : &str ) -> Self
+    {
+        Self::with_status( 308, url )
+    }
+
+    /// A redirection with the given status, like `301`.
+    pub fn with_status( status : u16, url : &str ) -> Self
+    {
+        Self
+        {
+            status,
+            location : url.to_string()
+        }
+    }
+}
+
+impl IntoResponse for Redirect
+{
+    fn into_response( self, mut io : IO ) -> IO
+    {
+        io.response.status = self.status;
+
+        io.response.headers.replace_header( "location".to_string(), self.location );
+
+        io.response.body = None;
+
+        io
+    }
+}
+
+/// Bytes sent verbatim with the given content type.
+///
+/// # Example
+///
+/// ```ignore
+/// #[get(url = "/logo.png")]
+/// async fn logo() -> Binary {
+///     Binary::new(tokio::fs::read("logo.png").await.unwrap(), "image/png")
+/// }
+/// ```
+pub struct Binary
+{
+    pub bytes : Bytes,
+    pub content_type : String
+}
+
+impl Binary
+{
+    pub fn new( bytes : impl Into<Bytes>, content_type : &str ) -> Self
+    {
+        Self
+        {
+            bytes : bytes.into(),
+            content_type : content_type.to_string()
+        }
+    }
+}
+
+impl IntoResponse for Binary
+{
+    fn into_response( self, mut io : IO ) -> IO
+    {
+        io.response.body = Some( ResponseBody::Bytes { bytes : self.bytes, content_type : self.content_type } );
+
+        io
+    }
+}
+
+// The content type of `Vec<u8>` and `Bytes` is the one set in the response headers, if any.
+fn bytes_into_response( bytes : Bytes, mut io : IO ) -> IO
+{
+    let content_type = match io.response.headers.get_value( "content-type" )
+    {
+        Some( c ) => c.clone(),
+        _ => "application/octet-stream".to_string()
+    };
+
+    io.response.body = Some( ResponseBody::Bytes { bytes, content_type } );
+
+    io
+}
+
+impl IntoResponse for Bytes
+{
+    fn into_response( self, io : IO ) -> IO
+    {
+        bytes_into_response( self, io )
+    }
+}
+
+impl IntoResponse for Vec<u8>
+{
+    fn into_response( self, io : IO ) -> IO
+    {
+        bytes_into_response( Bytes::from( self ), io )
+    }
+}
+
+/// A body sent in chunks, as the stream produces them, with chunked transfer encoding.
+///
+/// The body is not buffered, so it can be used for large responses.
+///
+/// # Example
+///
+/// ```ignore
+/// #[get(url = "/export.csv")]
+/// async fn export() -> ByteStream {
+///     let rows = futures::stream::iter(0..1_000_000).map(|i| Bytes::from(format!("{},{}\n", i, i * 2)));
+///
+///     ByteStream::new(rows, "text/csv")
+/// }
+/// ```
+pub struct ByteStream
+{
+    stream : BodyStream,
+    content_type : String
+}
+
+impl ByteStream
+{
+    pub fn new<S>( stream : S, content_type : &str ) -> Self
+    where S: Stream<Item = Bytes> + Send + 'static
+    {
+        Self
+        {
+            stream : Box::pin( stream ),
+            content_type : content_type.to_string()
+        }
+    }
+}
+
+impl IntoResponse for ByteStream
+{
+    fn into_response( self, mut io : IO ) -> IO
+    {
+        io.response.body = Some( ResponseBody::Stream { stream : ResponseStream::new( self.stream ), content_type : self.content_type } );
+
+        io
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::response::response_data::ResponseData;
+
+    use super::*;
+
+    #[test]
+    fn test_response_into_response()
+    {
+        let io = Response::created( "hello" )
+            .header( "x-id", "1" )
+            .cookie( "session=abc; Path=/" )
+            .into_response( IO::with_response( ResponseData::default() ) );
+
+        assert_eq!( io.response.status, 201 );
+        assert_eq!( io.response.headers.get_value( "x-id" ).unwrap(), "1" );
+        assert!( io.response.cookies.find_first_by_name( "session" ).is_some() );
+        assert_eq!( io.response.body_value(), Some( &serde_json::Value::from( "hello" ) ) );
+
+        let io = Response::new( 1 ).cookie( "" ).into_response( IO::with_response( ResponseData::default() ) );
+
+        assert_eq!( io.response.status, 500 );
+        assert!( io.error.is_some() );
+    }
+
+    #[test]
+    fn test_redirect_into_response()
+    {
+        let io = Redirect::see_other( "/home" ).into_response( IO::with_response( ResponseData::default() ) );
+
+        assert_eq!( io.response.status, 303 );
+        assert_eq!( io.response.headers.get_value( "location" ).unwrap(), "/home" );
+        assert!( io.response.body.is_none() );
+    }
+
+    #[test]
+    fn test_bytes_into_response()
+    {
+        let io = vec![ 0u8, 255 ].into_response( IO::with_response( ResponseData::default() ) );
+
+        assert_eq!( io.response.body, Some( ResponseBody::Bytes { bytes : Bytes::from_static( &[ 0, 255 ] ), content_type : "application/octet-stream".to_string() } ) );
+
+        let io = Binary::new( "a,b", "text/csv" ).into_response( IO::with_response( ResponseData::default() ) );
+
+        assert_eq!( io.response.body, Some( ResponseBody::Bytes { bytes : Bytes::from_static( b"a,b" ), content_type : "text/csv".to_string() } ) );
+    }
+}
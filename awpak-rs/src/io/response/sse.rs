@@ -0,0 +1,255 @@
+use std::{pin::Pin, task::{Context, Poll}, time::Duration};
+
+use futures::Stream;
+use hyper::body::Bytes;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+use crate::{io::io::IO, services::response::into_response::IntoResponse};
+
+use super::response_body::{ResponseBody, ResponseStream};
+
+const DEFAULT_KEEP_ALIVE : Duration = Duration::from_secs( 15 );
+
+/// An event of a `Sse` response.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use awpak_rs::SseEvent;
+///
+/// let event = SseEvent::new( "{\"cpu\":33}" ).id( "7" ).event( "stats" ).retry( Duration::from_secs( 5 ) );
+///
+/// assert_eq!( event.to_bytes(), "id: 7\nevent: stats\nretry: 5000\ndata: {\"cpu\":33}\n\n" );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SseEvent
+{
+    id : Option<String>,
+    event : Option<String>,
+    data : String,
+    retry : Option<Duration>
+}
+
+impl SseEvent
+{
+    /// Creates an event with the given data. Data with several lines, ended by `\n`, `\r\n` or `\r`, is sent in several `data` fields.
+    pub fn new( data : &str ) -> Self
+    {
+        Self
+        {
+            data : data.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the id of the event. The client sends the last id received in the `Last-Event-ID` header when it reconnects.
+    pub fn id( mut self, id : &str ) -> Self
+    {
+        self.id = Some( id.to_string() );
+
+        self
+    }
+
+    /// Sets the type of the event. Without it, the client receives the event as a `message`.
+    pub fn event( mut self, event : &str ) -> Self
+    {
+        self.event = Some( event.to_string() );
+
+        self
+    }
+
+    /// Sets the time the client waits before reconnecting.
+    pub fn retry( mut self, retry : Duration ) -> Self
+    {
+        self.retry = Some( retry );
+
+        self
+    }
+
+    /// Returns the event in the `text/event-stream` format.
+    pub fn to_bytes( &self ) -> Bytes
+    {
+        let mut out = String::new();
+
+        if let Some( id ) = &self.id
+        {
+            out.push_str( &format!( "id: {}\n", single_line( id ) ) );
+        }
+
+        if let Some( event ) = &self.event
+        {
+            out.push_str( &format!( "event: {}\n", single_line( event ) ) );
+        }
+
+        if let Some( retry ) = &self.retry
+        {
+            out.push_str( &format!( "retry: {}\n", retry.as_millis() ) );
+        }
+
+        for line in normalize_lines( &self.data ).split( '\n' )
+        {
+            out.push_str( &format!( "data: {}\n", line ) );
+        }
+
+        out.push( '\n' );
+
+        Bytes::from( out )
+    }
+}
+
+// A line break in the id or the event type would start a new field
+fn single_line( value : &str ) -> String
+{
+    value.replace( [ '\r', '\n' ], "" )
+}
+
+// The client ends a line at `\r\n`, `\r` or `\n`, so a `\r` in the data would start a new field
+fn normalize_lines( value : &str ) -> String
+{
+    value.replace( "\r\n", "\n" ).replace( '\r', "\n" )
+}
+
+/// A Server-Sent Events response (`text/event-stream`).
+///
+/// The events are sent as the stream produces them. While there are no events,
+/// a comment is sent every 15 seconds, so proxies don't close the connection.
+/// The response ends when the stream ends.
+///
+/// # Example
+///
+/// ```ignore
+/// #[get(url = "/events")]
+/// async fn events() -> Sse {
+///     let events = futures::stream::iter(1..=3).map(|i| SseEvent::new(&i.to_string()).id(&i.to_string()));
+///
+///     Sse::new(events).keep_alive(Some(Duration::from_secs(5)))
+/// }
+/// ```
+pub struct Sse
+{
+    events : Pin<Box<dyn Stream<Item = SseEvent> + Send>>,
+    keep_alive : Option<Duration>
+}
+
+impl Sse
+{
+    pub fn new<S>( events : S ) -> Self
+    where S: Stream<Item = SseEvent> + Send + 'static
+    {
+        Self
+        {
+            events : Box::pin( events ),
+            keep_alive : Some( DEFAULT_KEEP_ALIVE )
+        }
+    }
+
+    /// Sets the interval of the keep-alive comments. `None` disables them.
+    pub fn keep_alive( mut self, keep_alive : Option<Duration> ) -> Self
+    {
+        self.keep_alive = keep_alive;
+
+        self
+    }
+}
+
+impl IntoResponse for Sse
+{
+    fn into_response( self, mut io : IO ) -> IO
+    {
+        io.response.headers.replace_header( "cache-control".to_string(), "no-cache".to_string() );
+
+        let stream = SseStream
+        {
+            events : self.events,
+            keep_alive : self.keep_alive.map( keep_alive_interval )
+        };
+
+        io.response.body = Some( ResponseBody::Stream {
+            stream : ResponseStream::new( Box::pin( stream ) ),
+            content_type : "text/event-stream".to_string()
+        } );
+
+        io
+    }
+}
+
+fn keep_alive_interval( period : Duration ) -> Interval
+{
+    let mut interval = tokio::time::interval_at( Instant::now() + period, period );
+
+    interval.set_missed_tick_behavior( MissedTickBehavior::Delay );
+
+    interval
+}
+
+// Sends the events, and a keep-alive comment when no event was sent during the interval.
+struct SseStream
+{
+    events : Pin<Box<dyn Stream<Item = SseEvent> + Send>>,
+    keep_alive : Option<Interval>
+}
+
+impl Stream for SseStream
+{
+    type Item = Bytes;
+
+    fn poll_next( self : Pin<&mut Self>, cx : &mut Context<'_> ) -> Poll<Option<Self::Item>>
+    {
+        let this = self.get_mut();
+
+        match this.events.as_mut().poll_next( cx )
+        {
+            Poll::Ready( Some( event ) ) => {
+
+                if let Some( interval ) = &mut this.keep_alive
+                {
+                    interval.reset();
+                }
+
+                Poll::Ready( Some( event.to_bytes() ) )
+            },
+            Poll::Ready( None ) => Poll::Ready( None ),
+            Poll::Pending => match &mut this.keep_alive
+            {
+                Some( interval ) => match interval.poll_tick( cx )
+                {
+                    Poll::Ready( _ ) => Poll::Ready( Some( Bytes::from_static( b":\n\n" ) ) ),
+                    Poll::Pending => Poll::Pending
+                },
+                _ => Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+
+    #[test]
+    fn test_sse_event_to_bytes()
+    {
+        assert_eq!( SseEvent::new( "a\nb\r\nc" ).to_bytes(), "data: a\ndata: b\ndata: c\n\n" );
+        assert_eq!( SseEvent::new( "a\rb\r\rc" ).to_bytes(), "data: a\ndata: b\ndata: \ndata: c\n\n" );
+        assert_eq!( SseEvent::new( "" ).event( "x\ny" ).to_bytes(), "event: xy\ndata: \n\n" );
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream()
+    {
+        let events = futures::stream::iter( vec![ SseEvent::new( "1" ), SseEvent::new( "2" ) ] ).chain( futures::stream::pending() );
+
+        let mut stream = SseStream
+        {
+            events : Box::pin( events ),
+            keep_alive : Some( keep_alive_interval( Duration::from_millis( 10 ) ) )
+        };
+
+        assert_eq!( stream.next().await, Some( Bytes::from( "data: 1\n\n" ) ) );
+        assert_eq!( stream.next().await, Some( Bytes::from( "data: 2\n\n" ) ) );
+        assert_eq!( stream.next().await, Some( Bytes::from( ":\n\n" ) ) );
+    }
+}
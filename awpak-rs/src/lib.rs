@@ -1,4 +1,3 @@
-use hyper::body::Bytes;
 use strategy_pattern_rs::strategy_pattern_type;
 pub use tokio;
 use hyper;
@@ -15,20 +14,65 @@ mod parser;
 pub use awpak_rs_macros::*;
 
 pub use services::error::Error;
+pub use services::error::ExtractionError;
+pub use services::error::ExtractionSource;
+pub use services::response::into_error_response::IntoErrorResponse;
+pub use services::response::into_response::IntoResponse;
+#[doc(hidden)]
+pub use services::response::response_kind::ResponseKind;
+#[doc(hidden)]
+pub use services::response::response_kind::IntoResponseKind;
+#[doc(hidden)]
+pub use services::response::response_kind::SerializeKind;
+pub use io::response::response_types::Response;
+pub use io::response::response_types::Created;
+pub use io::response::response_types::NoContent;
+pub use io::response::response_types::Redirect;
+pub use io::response::response_types::Binary;
+pub use io::response::response_types::ByteStream;
+pub use io::response::sse::Sse;
+pub use io::response::sse::SseEvent;
+pub use io::response::response_body::ResponseBody;
+pub use parser::codec::codec::Codec;
+pub use parser::codec::codec::BodyCodec;
+pub use parser::codec::codec::initialize_codecs;
+pub use hyper::body::Bytes;
+pub use body::body_stream::RequestBodyStream;
 pub use services::middleware::middleware::MiddlewareResponse;
 pub use services::middleware::middleware::MiddlewareResponseType;
 pub use services::middleware::middleware::initialize_middlewares;
 pub use services::middleware::middleware::Middleware;
 pub use services::middleware::middleware::MiddlewareExecOrder;
+pub use services::middleware::middleware::MiddlewareFn;
+pub use services::middleware::middleware::Next;
+pub use services::endpoint::endpoint_executor::initialize_endpoints;
+pub use services::endpoint::routes::routes;
+pub use services::endpoint::routes::Route;
+pub use services::error_handler::error_handler::ErrorHandler;
+pub use services::error_handler::error_handler::ErrorHandlerReturnType;
+pub use services::error_handler::error_handler::initialize_error_handlers;
+pub use services::error_handler::problem_details::problem_details;
+pub use services::websocket::websocket::WebSocket;
+pub use services::websocket::websocket::Message;
+pub use services::websocket::websocket::CloseFrame;
+pub use services::websocket::upgrade::WebSocketUpgrade;
 
 pub use parser::parser::parse_value;
 pub use parser::parser::parse_from_value;
 pub use parser::parser::parse_body_param_value;
 pub use parser::parser::serialize_value;
 pub use parser::parser::parse_path_variable;
+pub use parser::parser::parse_path_wildcard;
 pub use parser::parser::parse_query_param_value;
 pub use parser::from_value::from_value;
 pub use parser::from_async_str::from_async_str;
+#[doc(hidden)]
+pub use parser::from_async_str::path_variable_kind::PathVariableKind;
+#[doc(hidden)]
+pub use parser::from_async_str::path_variable_kind::FromStrKind;
+#[doc(hidden)]
+pub use parser::from_async_str::path_variable_kind::FromAsyncStrKind;
+pub use parser::from_path_wildcard::from_path_wildcard;
 pub use serde_json::Value;
 
 #[strategy_pattern_type( search = "IgnoreCase" )]
@@ -38,12 +82,13 @@ pub struct ContentTypeStrategy(fn( Bytes ) -> Result<serde_json::Value, Error>);
 pub struct ResponseContentTypeStrategy(fn( serde_json::Value ) -> Result<Bytes, Error>);
 
 
-// pub mod benches
-// {
-//     use crate::{io::io::IO, services::middleware::middleware::pre_middlewares_exec, MiddlewareResponse};
+#[doc(hidden)]
+pub mod benches
+{
+    use crate::{io::io::IO, services::middleware::middleware::pre_middlewares_exec, MiddlewareResponse};
 
-//     pub async fn bench_execute_middlewares( io : IO ) -> MiddlewareResponse
-//     {
-//         pre_middlewares_exec( io ).await
-//     }
-// }
+    pub async fn bench_execute_middlewares( io : IO ) -> MiddlewareResponse
+    {
+        pre_middlewares_exec( io ).await
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt;
+
+use serde::{de::{EnumAccess, IgnoredAny, MapAccess, SeqAccess, VariantAccess, Visitor}, Deserialize, Deserializer};
+use serde_json::{Map, Number, Value};
+
+/// A `Value` read from a binary format, like MessagePack or CBOR.
+///
+/// These formats have types that JSON doesn't, so they are converted:
+///
+/// - Byte strings are arrays of numbers.
+/// - Map keys that are not strings are written as JSON, like `1` or `[1,2]`.
+/// - Floats that are not finite (`NaN` and infinities) are `null`.
+/// - CBOR tags are ignored, only their content is kept.
+pub struct BinaryValue( pub Value );
+
+impl<'de> Deserialize<'de> for BinaryValue
+{
+    fn deserialize<D>( deserializer : D ) -> Result<Self, D::Error>
+    where D: Deserializer<'de>
+    {
+        deserializer.deserialize_any( BinaryValueVisitor ).map( BinaryValue )
+    }
+}
+
+struct BinaryValueVisitor;
+
+impl<'de> Visitor<'de> for BinaryValueVisitor
+{
+    type Value = Value;
+
+    fn expecting( &self, formatter : &mut fmt::Formatter ) -> fmt::Result
+    {
+        formatter.write_str( "any value" )
+    }
+
+    fn visit_bool<E>( self, v : bool ) -> Result<Value, E>
+    {
+        Ok( Value::Bool( v ) )
+    }
+
+    fn visit_i64<E>( self, v : i64 ) -> Result<Value, E>
+    {
+        Ok( Value::from( v ) )
+    }
+
+    fn visit_u64<E>( self, v : u64 ) -> Result<Value, E>
+    {
+        Ok( Value::from( v ) )
+    }
+
+    // Integers out of the range of `i64` and `u64`, like in CBOR, are written as floats
+    fn visit_i128<E>( self, v : i128 ) -> Result<Value, E>
+    {
+        Ok( i64::try_from( v ).map( Value::from ).unwrap_or_else( | _ | float_value( v as f64 ) ) )
+    }
+
+    fn visit_u128<E>( self, v : u128 ) -> Result<Value, E>
+    {
+        Ok( u64::try_from( v ).map( Value::from ).unwrap_or_else( | _ | float_value( v as f64 ) ) )
+    }
+
+    fn visit_f64<E>( self, v : f64 ) -> Result<Value, E>
+    {
+        Ok( float_value( v ) )
+    }
+
+    fn visit_str<E>( self, v : &str ) -> Result<Value, E>
+    {
+        Ok( Value::String( v.to_string() ) )
+    }
+
+    fn visit_string<E>( self, v : String ) -> Result<Value, E>
+    {
+        Ok( Value::String( v ) )
+    }
+
+    fn visit_bytes<E>( self, v : &[u8] ) -> Result<Value, E>
+    {
+        Ok( Value::Array( v.iter().map( | b | Value::from( *b ) ).collect() ) )
+    }
+
+    fn visit_none<E>( self ) -> Result<Value, E>
+    {
+        Ok( Value::Null )
+    }
+
+    fn visit_unit<E>( self ) -> Result<Value, E>
+    {
+        Ok( Value::Null )
+    }
+
+    fn visit_some<D>( self, deserializer : D ) -> Result<Value, D::Error>
+    where D: Deserializer<'de>
+    {
+        BinaryValue::deserialize( deserializer ).map( | v | v.0 )
+    }
+
+    fn visit_newtype_struct<D>( self, deserializer : D ) -> Result<Value, D::Error>
+    where D: Deserializer<'de>
+    {
+        BinaryValue::deserialize( deserializer ).map( | v | v.0 )
+    }
+
+    fn visit_seq<A>( self, mut seq : A ) -> Result<Value, A::Error>
+    where A: SeqAccess<'de>
+    {
+        let mut values = vec![];
+
+        while let Some( v ) = seq.next_element::<BinaryValue>()?
+        {
+            values.push( v.0 );
+        }
+
+        Ok( Value::Array( values ) )
+    }
+
+    fn visit_map<A>( self, mut map : A ) -> Result<Value, A::Error>
+    where A: MapAccess<'de>
+    {
+        let mut values = Map::new();
+
+        while let Some( ( k, v ) ) = map.next_entry::<BinaryValue, BinaryValue>()?
+        {
+            let key = match k.0
+            {
+                Value::String( s ) => s,
+                k => k.to_string()
+            };
+
+            values.insert( key, v.0 );
+        }
+
+        Ok( Value::Object( values ) )
+    }
+
+    // CBOR tags are read as enums, with the tag as the variant and the tagged value as its content
+    fn visit_enum<A>( self, data : A ) -> Result<Value, A::Error>
+    where A: EnumAccess<'de>
+    {
+        let ( _, variant ) = data.variant::<IgnoredAny>()?;
+
+        variant.newtype_variant::<BinaryValue>().map( | v | v.0 )
+    }
+}
+
+fn float_value( v : f64 ) -> Value
+{
+    match Number::from_f64( v )
+    {
+        Some( n ) => Value::Number( n ),
+        _ => Value::Null
+    }
+}
@@ -0,0 +1 @@
+pub mod binary_value;
\ No newline at end of file
@@ -0,0 +1,73 @@
+use hyper::body::Bytes;
+use strategy_pattern_rs::strategy_pattern_fn;
+use crate::{parser::binary::binary_value::BinaryValue, Error, ContentTypeStrategy, ResponseContentTypeStrategy};
+
+#[strategy_pattern_fn( key = "application/cbor", strategy = ContentTypeStrategy )]
+pub fn cbor_parser( bytes : Bytes ) -> Result<serde_json::Value, Error>
+{
+    match ciborium::from_reader::<BinaryValue, _>( &bytes[..] ) {
+        Ok( v ) => Ok( v.0 ),
+        _ => Err( Error::ParserError( "Invalid cbor".to_string() ) )
+    }
+}
+
+#[strategy_pattern_fn( key = "application/cbor", strategy = ResponseContentTypeStrategy )]
+pub fn cbor_serializer( value : serde_json::Value ) -> Result<Bytes, Error>
+{
+    let mut bytes = vec![];
+
+    match ciborium::into_writer( &value, &mut bytes ) {
+        Ok( _ ) => Ok( bytes.into() ),
+        _ => Err( Error::ParserError( "Invalid response cbor".to_string() ) )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point
+    {
+        x : f32,
+        y : Option<String>,
+        tags : Vec<u8>
+    }
+
+    #[test]
+    fn test_cbor_round_trip()
+    {
+        let point = Point { x : 1.5, y : None, tags : vec![ 1, 2 ] };
+
+        let mut bytes = vec![];
+
+        ciborium::into_writer( &point, &mut bytes ).unwrap();
+
+        let value = cbor_parser( bytes.into() );
+
+        assert!( value.is_ok(), "value is Err" );
+
+        let value = value.unwrap();
+
+        assert_eq!( value, serde_json::json!( { "x" : 1.5, "y" : null, "tags" : [ 1, 2 ] } ) );
+
+        let bytes = cbor_serializer( value );
+
+        assert!( bytes.is_ok(), "bytes is Err" );
+
+        assert_eq!( ciborium::from_reader::<Point, _>( &bytes.unwrap()[..] ).unwrap(), point );
+    }
+
+    #[test]
+    fn test_cbor_binary_types()
+    {
+        // { 1: h'0102', "t": 1(1700000000) }
+        let bytes = Bytes::from_static( &[ 0xa2, 0x01, 0x42, 0x01, 0x02, 0x61, b't', 0xc1, 0x1a, 0x65, 0x53, 0xf1, 0x00 ] );
+
+        assert_eq!( cbor_parser( bytes ).unwrap(), serde_json::json!( { "1" : [ 1, 2 ], "t" : 1700000000 } ) );
+
+        assert!( cbor_parser( Bytes::from_static( &[ 0xff ] ) ).is_err() );
+    }
+}
@@ -0,0 +1 @@
+pub mod cbor_parser;
\ No newline at end of file
@@ -0,0 +1,278 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use hyper::body::Bytes;
+use serde_json::Value;
+
+use crate::{io::headers::media_type::MediaType, services::error::Error, ContentTypeStrategy, ResponseContentTypeStrategy};
+
+/// Built-in request body parsers. The first one is used for bodies without a known content type.
+pub const REQUEST_MIME_TYPES : &[&str] = &[
+    "text/plain",
+    "application/json",
+    "application/x-www-form-urlencoded",
+    #[cfg( feature = "msgpack" )]
+    "application/msgpack",
+    #[cfg( feature = "cbor" )]
+    "application/cbor",
+    #[cfg( feature = "xml" )]
+    "application/xml"
+];
+
+/// Built-in response body serializers. The first one is used when no type is acceptable for the client.
+pub const RESPONSE_MIME_TYPES : &[&str] = &[
+    "text/plain",
+    "application/json",
+    #[cfg( feature = "msgpack" )]
+    "application/msgpack",
+    #[cfg( feature = "cbor" )]
+    "application/cbor",
+    #[cfg( feature = "xml" )]
+    "application/xml"
+];
+
+/// A body format, to read request bodies and write response bodies of a media type.
+///
+/// Register it with the `body_codec` macro. The decoded value is the body used by `#[request_body]`
+/// and `#[body_param]`, and the value encoded is the one returned by the endpoint.
+///
+/// # Example
+///
+/// ```ignore
+/// #[body_codec(mime = "application/x-lines")]
+/// struct Lines;
+///
+/// impl Codec for Lines {
+///     fn decode(bytes: Bytes) -> Result<Value, Error> {
+///         let text = String::from_utf8(bytes.to_vec()).map_err(|e| Error::ParserError(e.to_string()))?;
+///
+///         Ok(Value::from(text.lines().collect::<Vec<&str>>()))
+///     }
+///
+///     fn encode(value: Value) -> Result<Bytes, Error> {
+///         match value {
+///             Value::Array(lines) => Ok(lines.iter().map(|l| l.as_str().unwrap_or_default()).collect::<Vec<&str>>().join("\n").into()),
+///             _ => Err(Error::ParserError("Expected an array".to_string()))
+///         }
+///     }
+/// }
+/// ```
+pub trait Codec
+{
+    /// Reads a request body.
+    fn decode( bytes : Bytes ) -> Result<Value, Error>;
+
+    /// Writes a response body.
+    fn encode( value : Value ) -> Result<Bytes, Error>;
+}
+
+/// A `Codec` registered for a media type. Created by the `body_codec` macro.
+///
+/// The media type is checked by `initialize_codecs` when the server starts.
+pub struct BodyCodec
+{
+    /// The media type, in lowercase and without parameters, like `application/x-protobuf`.
+    pub mime : &'static str,
+    pub decode : fn( Bytes ) -> Result<Value, Error>,
+    pub encode : fn( Value ) -> Result<Bytes, Error>
+}
+
+impl BodyCodec
+{
+    pub const fn new( mime : &'static str, decode : fn( Bytes ) -> Result<Value, Error>, encode : fn( Value ) -> Result<Bytes, Error> ) -> Self
+    {
+        Self
+        {
+            mime,
+            decode,
+            encode
+        }
+    }
+}
+
+inventory::collect!( BodyCodec );
+
+fn codecs() -> &'static HashMap<&'static str, &'static BodyCodec>
+{
+    static CODECS: OnceLock<HashMap<&'static str, &'static BodyCodec>> = OnceLock::new();
+    CODECS.get_or_init(|| inventory::iter::<BodyCodec>.into_iter().map( | c | ( c.mime, c ) ).collect() )
+}
+
+/// Checks the media types of the registered codecs.
+///
+/// Returns an error if the media type of a codec is not a valid type, in lowercase and without parameters,
+/// like `application/x-protobuf`, as it is sent in the `content-type` of the responses.
+/// Also if two codecs have the same media type, or if it is a built-in one, like `application/json`.
+pub fn initialize_codecs() -> Result<(), Error>
+{
+    check_codecs( &inventory::iter::<BodyCodec>.into_iter().collect::<Vec<_>>() )
+}
+
+fn check_codecs( codecs : &[&BodyCodec] ) -> Result<(), Error>
+{
+    for ( i, codec ) in codecs.iter().enumerate()
+    {
+        if ! is_valid_mime( codec.mime )
+        {
+            return Err( Error::ParserError( format!( "Invalid media type of a body codec: \"{}\"", codec.mime ) ) )
+        }
+
+        if REQUEST_MIME_TYPES.contains( &codec.mime ) || RESPONSE_MIME_TYPES.contains( &codec.mime )
+        {
+            return Err( Error::ParserError( format!( "A body codec can't replace the built-in media type \"{}\"", codec.mime ) ) )
+        }
+
+        if codecs[ ..i ].iter().any( | c | c.mime == codec.mime )
+        {
+            return Err( Error::ParserError( format!( "Several body codecs are registered for \"{}\"", codec.mime ) ) )
+        }
+    }
+
+    Ok( () )
+}
+
+fn is_valid_mime( mime : &str ) -> bool
+{
+    matches!( mime.parse::<MediaType>(), Ok( m ) if m.parameters.is_empty() && m.get_mime_type() == mime )
+}
+
+/// The media types of the request bodies that are parsed: the built-in ones and the registered codecs.
+pub fn request_mime_types() -> &'static [&'static str]
+{
+    static MIME_TYPES: OnceLock<Vec<&'static str>> = OnceLock::new();
+    MIME_TYPES.get_or_init(|| with_codecs( REQUEST_MIME_TYPES ) )
+}
+
+/// The media types of the response bodies that can be chosen by content negotiation: the built-in ones and the registered codecs.
+pub fn response_mime_types() -> &'static [&'static str]
+{
+    static MIME_TYPES: OnceLock<Vec<&'static str>> = OnceLock::new();
+    MIME_TYPES.get_or_init(|| with_codecs( RESPONSE_MIME_TYPES ) )
+}
+
+// The registered codecs go after the built-in types, sorted so the order doesn't depend on the registration
+fn with_codecs( built_in : &[&'static str] ) -> Vec<&'static str>
+{
+    let mut registered = codecs().keys().filter( | m | ! built_in.contains( m ) ).copied().collect::<Vec<_>>();
+
+    registered.sort();
+
+    built_in.iter().copied().chain( registered ).collect()
+}
+
+/// Parses a request body of the media type `mime`, without parameters, with its built-in parser or its registered codec.
+pub fn decode( mime : &str, bytes : Bytes ) -> Result<Value, String>
+{
+    if let Some( codec ) = codecs().get( mime )
+    {
+        return ( codec.decode )( bytes ).map_err( | e | e.to_string() )
+    }
+
+    match ContentTypeStrategy::exec( mime, bytes )
+    {
+        Ok( v ) => v.map_err( | e | e.to_string() ),
+        Err( e ) => Err( e )
+    }
+}
+
+/// Serializes a response body as the media type `mime`, without parameters, with its built-in serializer or its registered codec.
+pub fn encode( mime : &str, value : Value ) -> Result<Bytes, String>
+{
+    if let Some( codec ) = codecs().get( mime )
+    {
+        return ( codec.encode )( value ).map_err( | e | e.to_string() )
+    }
+
+    match ResponseContentTypeStrategy::exec( mime, value )
+    {
+        Ok( v ) => v.map_err( | e | format!( "{:?}", e ) ),
+        Err( e ) => Err( e )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Lines;
+
+    impl Codec for Lines
+    {
+        fn decode( bytes : Bytes ) -> Result<Value, Error>
+        {
+            let text = String::from_utf8( bytes.to_vec() ).map_err( | e | Error::ParserError( e.to_string() ) )?;
+
+            Ok( Value::from( text.lines().collect::<Vec<&str>>() ) )
+        }
+
+        fn encode( value : Value ) -> Result<Bytes, Error>
+        {
+            match value
+            {
+                Value::Array( lines ) => Ok( lines.iter().map( | l | l.as_str().unwrap_or_default() ).collect::<Vec<&str>>().join( "\n" ).into() ),
+                _ => Err( Error::ParserError( "Expected an array".to_string() ) )
+            }
+        }
+    }
+
+    inventory::submit! {
+        BodyCodec::new( "application/x-lines", <Lines as Codec>::decode, <Lines as Codec>::encode )
+    }
+
+    #[test]
+    fn test_is_valid_mime()
+    {
+        assert!( is_valid_mime( "application/x-lines" ) );
+        assert!( is_valid_mime( "application/vnd.api+json" ) );
+        assert!( ! is_valid_mime( "Application/X-Lines" ) );
+        assert!( ! is_valid_mime( "application/x-lines; charset=utf-8" ) );
+        assert!( ! is_valid_mime( "application" ) );
+        assert!( ! is_valid_mime( "application/x lines" ) );
+        assert!( initialize_codecs().is_ok() );
+    }
+
+    #[test]
+    fn test_check_codecs()
+    {
+        let lines = BodyCodec::new( "application/x-lines", <Lines as Codec>::decode, <Lines as Codec>::encode );
+        let other = BodyCodec::new( "application/x-other", <Lines as Codec>::decode, <Lines as Codec>::encode );
+        let json = BodyCodec::new( "application/json", <Lines as Codec>::decode, <Lines as Codec>::encode );
+
+        assert!( check_codecs( &[ &lines, &other ] ).is_ok() );
+
+        assert_eq!(
+            check_codecs( &[ &lines, &other, &lines ] ).err().map( | e | e.to_string() ),
+            Some( "Several body codecs are registered for \"application/x-lines\"".to_string() )
+        );
+
+        assert_eq!(
+            check_codecs( &[ &lines, &json ] ).err().map( | e | e.to_string() ),
+            Some( "A body codec can't replace the built-in media type \"application/json\"".to_string() )
+        );
+    }
+
+    #[test]
+    fn test_registered_codec()
+    {
+        assert!( request_mime_types().starts_with( REQUEST_MIME_TYPES ) );
+        assert!( request_mime_types().contains( &"application/x-lines" ) );
+
+        assert!( response_mime_types().starts_with( RESPONSE_MIME_TYPES ) );
+        assert!( response_mime_types().contains( &"application/x-lines" ) );
+
+        assert_eq!( decode( "application/x-lines", Bytes::from( "a\nb" ) ).unwrap(), serde_json::json!( [ "a", "b" ] ) );
+
+        assert_eq!( encode( "application/x-lines", serde_json::json!( [ "a", "b" ] ) ).unwrap(), Bytes::from( "a\nb" ) );
+
+        assert!( encode( "application/x-lines", serde_json::json!( "a" ) ).is_err() );
+    }
+
+    #[test]
+    fn test_built_in_codecs()
+    {
+        assert_eq!( decode( "application/json", Bytes::from( r#"{"x":1}"# ) ).unwrap(), serde_json::json!( { "x" : 1 } ) );
+
+        assert_eq!( encode( "text/plain", serde_json::json!( "a" ) ).unwrap(), Bytes::from( "a" ) );
+
+        assert!( decode( "application/unknown", Bytes::from( "a" ) ).is_err() );
+    }
+}
@@ -0,0 +1 @@
+pub mod codec;
\ No newline at end of file
@@ -0,0 +1,153 @@
+use hyper::body::Bytes;
+use serde_json::{Map, Value};
+use strategy_pattern_rs::strategy_pattern_fn;
+use crate::{Error, ContentTypeStrategy};
+
+/// Parses an `application/x-www-form-urlencoded` body into a JSON object.
+///
+/// - Values are always strings: `age=30` is `{"age": "30"}`.
+/// - Repeated keys are arrays: `tag=a&tag=b` is `{"tag": ["a", "b"]}`.
+/// - Keys ending in `[]` are always arrays: `tag[]=a` is `{"tag": ["a"]}`.
+/// - Keys with brackets are nested objects: `user[name]=ana` is `{"user": {"name": "ana"}}`.
+#[strategy_pattern_fn( key = "application/x-www-form-urlencoded", strategy = ContentTypeStrategy )]
+pub fn form_parser( bytes : Bytes ) -> Result<serde_json::Value, Error>
+{
+    let pairs = match serde_urlencoded::from_bytes::<Vec<( String, String )>>( &bytes )
+    {
+        Ok( v ) => v,
+        _ => return Err( Error::ParserError( "Invalid application/x-www-form-urlencoded".to_string() ) )
+    };
+
+    let mut form = Map::new();
+
+    for ( key, value ) in pairs
+    {
+        insert_value( &mut form, &get_key_path( &key ), value );
+    }
+
+    Ok( Value::Object( form ) )
+}
+
+// Splits `a[b][c]` into `["a", "b", "c"]` and `a[]` into `["a", ""]`.
+// Keys that are not well formed are not split.
+fn get_key_path( key : &str ) -> Vec<&str>
+{
+    let Some( start ) = key.find( '[' ) else
+    {
+        return vec![ key ]
+    };
+
+    let mut path = vec![ &key[ ..start ] ];
+
+    let mut rest = &key[ start.. ];
+
+    while ! rest.is_empty()
+    {
+        let end = match ( rest.starts_with( '[' ), rest.find( ']' ) )
+        {
+            ( true, Some( end ) ) => end,
+            _ => return vec![ key ]
+        };
+
+        path.push( &rest[ 1..end ] );
+
+        rest = &rest[ end + 1.. ];
+    }
+
+    // `[]` is only valid at the end of the key
+    if path[ 0 ].is_empty() || path[ ..path.len() - 1 ].iter().skip( 1 ).any( | s | s.is_empty() )
+    {
+        return vec![ key ]
+    }
+
+    path
+}
+
+fn insert_value( form : &mut Map<String, Value>, path : &[&str], value : String )
+{
+    let key = path[ 0 ].to_string();
+
+    let is_array = path.len() == 2 && path[ 1 ].is_empty();
+
+    if path.len() == 1 || is_array
+    {
+        match form.get_mut( &key )
+        {
+            Some( v ) => push_value( v, value ),
+            None if is_array => { form.insert( key, Value::Array( vec![ Value::String( value ) ] ) ); },
+            None => { form.insert( key, Value::String( value ) ); }
+        }
+
+        return
+    }
+
+    let nested = form.entry( key ).or_insert_with( || Value::Object( Map::new() ) );
+
+    if ! nested.is_object()
+    {
+        *nested = Value::Object( Map::new() );
+    }
+
+    insert_value( nested.as_object_mut().unwrap(), &path[ 1.. ], value );
+}
+
+// A repeated key turns its value into an array
+fn push_value( current : &mut Value, value : String )
+{
+    match current
+    {
+        Value::Array( a ) => a.push( Value::String( value ) ),
+        _ => {
+            let first = current.take();
+
+            *current = Value::Array( vec![ first, Value::String( value ) ] );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse( body : &'static str ) -> Value
+    {
+        let value = form_parser( Bytes::from( body ) );
+
+        assert!( value.is_ok(), "value is Err" );
+
+        value.unwrap()
+    }
+
+    #[test]
+    fn test_form_parser_simple_keys()
+    {
+        assert_eq!( parse( "name=John+Doe&age=30&city=M%C3%A1laga" ), serde_json::json!( { "name" : "John Doe", "age" : "30", "city" : "Málaga" } ) );
+
+        assert_eq!( parse( "" ), serde_json::json!( {} ) );
+    }
+
+    #[test]
+    fn test_form_parser_repeated_keys()
+    {
+        assert_eq!( parse( "tag=a&tag=b&tag=c" ), serde_json::json!( { "tag" : [ "a", "b", "c" ] } ) );
+
+        assert_eq!( parse( "tag[]=a" ), serde_json::json!( { "tag" : [ "a" ] } ) );
+
+        assert_eq!( parse( "tag[]=a&tag[]=b" ), serde_json::json!( { "tag" : [ "a", "b" ] } ) );
+    }
+
+    #[test]
+    fn test_form_parser_nested_keys()
+    {
+        assert_eq!(
+            parse( "user[name]=ana&user[address][city]=Lugo&user[roles][]=admin&user[roles][]=dev" ),
+            serde_json::json!( { "user" : { "name" : "ana", "address" : { "city" : "Lugo" }, "roles" : [ "admin", "dev" ] } } )
+        );
+    }
+
+    #[test]
+    fn test_form_parser_malformed_keys()
+    {
+        assert_eq!( parse( "a[b=1&c]d=2&[e]=3&f[][g]=4" ), serde_json::json!( { "a[b" : "1", "c]d" : "2", "[e]" : "3", "f[][g]" : "4" } ) );
+    }
+}
@@ -0,0 +1 @@
+pub mod form_parser;
\ No newline at end of file
@@ -30,10 +30,41 @@ use crate::io::io::IO;
 /// ```
 ///
 /// A request like `GET /user/42` will trigger an asynchronous database lookup,
-/// fetching the corresponding `User` object.
+/// fetching the corresponding `User` object. If the conversion fails, the response is a 404.
+///
+/// Implement `try_from_async_str` too to add the reason of the error to the 404:
+///
+/// ```ignore
+/// impl FromAsyncStr<User> for User {
+///     async fn from_async_str(io: &IO, s: &str) -> Result<User, ()> {
+///         Self::try_from_async_str(io, s).await.map_err(|_| ())
+///     }
+///
+///     async fn try_from_async_str(_io: &IO, s: &str) -> Result<User, String> {
+///         match get_user_from_db(s).await {
+///             Some(user) => Ok(user),
+///             None => Err(format!("user {} not found", s))
+///         }
+///     }
+/// }
+/// ```
+///
+/// The primitive types and the other types that implement `FromStr` are converted with it,
+/// and the 404 has the message of its error if it implements `Display`.
 pub trait FromAsyncStr<T>
 {
     fn from_async_str( io : &IO, s : &str ) -> impl std::future::Future<Output = Result<T, ()>> + Send;
+
+    /// Like `from_async_str`, with the reason of the error.
+    ///
+    /// The default implementation calls `from_async_str` and fails with `"invalid value"`.
+    fn try_from_async_str( io : &IO, s : &str ) -> impl std::future::Future<Output = Result<T, String>> + Send
+    {
+        async move
+        {
+            Self::from_async_str( io, s ).await.map_err( | _ | "invalid value".to_string() )
+        }
+    }
 }
 
 impl<T: FromStr> FromAsyncStr<T> for T
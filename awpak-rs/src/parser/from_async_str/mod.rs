@@ -1 +1,2 @@
-pub mod from_async_str;
\ No newline at end of file
+pub mod from_async_str;
+pub mod path_variable_kind;
\ No newline at end of file
@@ -0,0 +1,114 @@
+use std::{fmt::Display, marker::PhantomData, str::FromStr};
+
+use crate::{io::io::IO, parse_path_variable};
+
+use super::from_async_str::FromAsyncStr;
+
+// Chooses how a path variable is converted, by the traits of its type:
+// types that implement `FromStr` with a `Display` error are parsed with it, to keep the message of the error,
+// and the other ones with `FromAsyncStr::try_from_async_str`.
+//
+// `( &PathVariableKind::<T>( PhantomData ) ).path_variable_kind()` resolves to `FromStrKind` if `T: FromStr`
+// and `T::Err: Display`, because it needs no autoref, and to `FromAsyncStrKind`, implemented for `&PathVariableKind<T>`, otherwise.
+
+#[doc(hidden)]
+pub struct PathVariableKind<T>( pub PhantomData<T> );
+
+#[doc(hidden)]
+pub trait FromStrKind
+{
+    fn path_variable_kind( &self ) -> FromStrParser
+    {
+        FromStrParser
+    }
+}
+
+impl<T : FromStr> FromStrKind for PathVariableKind<T>
+where T::Err : Display {}
+
+#[doc(hidden)]
+pub trait FromAsyncStrKind
+{
+    fn path_variable_kind( &self ) -> FromAsyncStrParser
+    {
+        FromAsyncStrParser
+    }
+}
+
+impl<T> FromAsyncStrKind for &PathVariableKind<T> {}
+
+#[doc(hidden)]
+pub struct FromStrParser;
+
+impl FromStrParser
+{
+    pub async fn parse<T : FromStr>( self, io : &IO, name : &str ) -> Result<T, String>
+    where T::Err : Display
+    {
+        match io.request.path_variables.get( name )
+        {
+            Some( v ) => T::from_str( v ).map_err( | e | e.to_string() ),
+            _ => Err( "missing path variable".to_string() )
+        }
+    }
+}
+
+#[doc(hidden)]
+pub struct FromAsyncStrParser;
+
+impl FromAsyncStrParser
+{
+    pub async fn parse<T : FromAsyncStr<T>>( self, io : &IO, name : &str ) -> Result<T, String>
+    {
+        parse_path_variable::<T>( io, name ).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::io::{request::request_data::RequestData, response::response_data::ResponseData};
+
+    use super::*;
+
+    struct Id( u8 );
+
+    impl FromAsyncStr<Id> for Id
+    {
+        async fn from_async_str( _io : &IO, s : &str ) -> Result<Id, ()>
+        {
+            if s == "a" { Ok( Id( 1 ) ) } else { Err( () ) }
+        }
+    }
+
+    // The borrow is needless for the types that implement `FromStr`, but it is the call generated for every type
+    #[tokio::test]
+    #[allow(clippy::needless_borrow)]
+    async fn test_path_variable_kind()
+    {
+        let request = RequestData
+        {
+            path_variables : HashMap::from( [ ( "id".to_string(), "300".to_string() ), ( "name".to_string(), "a".to_string() ) ] ),
+            ..RequestData::default()
+        };
+
+        let io = IO::new( request, ResponseData::default(), None );
+
+        let id = ( &PathVariableKind::<u8>( PhantomData ) ).path_variable_kind().parse::<u8>( &io, "id" ).await;
+
+        assert_eq!( id, Err( "number too large to fit in target type".to_string() ) );
+
+        let id = ( &PathVariableKind::<u16>( PhantomData ) ).path_variable_kind().parse::<u16>( &io, "id" ).await;
+
+        assert_eq!( id, Ok( 300 ) );
+
+        let id = ( &PathVariableKind::<Id>( PhantomData ) ).path_variable_kind().parse::<Id>( &io, "name" ).await;
+
+        assert!( matches!( id, Ok( Id( 1 ) ) ) );
+
+        let id = ( &PathVariableKind::<Id>( PhantomData ) ).path_variable_kind().parse::<Id>( &io, "id" ).await;
+
+        assert!( matches!( id, Err( e ) if e == "invalid value" ) );
+    }
+}
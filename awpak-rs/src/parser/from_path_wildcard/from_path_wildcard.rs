@@ -0,0 +1,38 @@
+/// A trait for converting the value of a `{*wildcard}` path segment into a type.
+///
+/// The wildcard value is the percent-decoded rest of the path, without leading or trailing slashes.
+/// It can be received as a `String` (`"css/main.css"`) or as a `Vec<String>` with one item per segment
+/// (`["css", "main.css"]`).
+///
+/// A path with a segment that is `.` or `..`, or has a slash or a backslash, once decoded (like `..%2F`)
+/// is not found, so the wildcard value never leaves the path.
+///
+/// # Example
+///
+/// ```ignore
+/// #[get(url = "/static/{*path}")]
+/// async fn static_file(#[path_variable] path: Vec<String>) -> String {
+///     path.join(",")
+/// }
+/// ```
+pub trait FromPathWildcard : Sized
+{
+    fn from_path_wildcard( s : &str ) -> Option<Self>;
+}
+
+impl FromPathWildcard for String
+{
+    fn from_path_wildcard( s : &str ) -> Option<Self>
+    {
+        Some( s.to_string() )
+    }
+}
+
+impl FromPathWildcard for Vec<String>
+{
+    fn from_path_wildcard( s : &str ) -> Option<Self>
+    {
+        Some( s.split( '/' ).map( | s | s.to_string() ).collect() )
+    }
+}
+
@@ -24,21 +24,25 @@ use super::{from_value_for_from_str::from_value_for_from_str, from_value_for_opt
 /// }
 /// 
 /// let json_value = serde_json::json!({ "name": "Alice", "age": 30 });
-/// let user: Option<User> = User::from_value(&json_value);
-/// assert!(user.is_some());
+/// let user: Result<User, String> = User::from_value(&json_value);
+/// assert!(user.is_ok());
+///
+/// let json_value = serde_json::json!({ "name": "Alice", "age": "x" });
+/// let user: Result<User, String> = User::from_value(&json_value);
+/// assert!(user.err().unwrap().contains("invalid type"));
 /// ```
 pub trait FromValue
 where Self: for<'a> serde::Deserialize<'a>
 {
     /// Converts a `serde_json::Value` into the implementing type.
     ///
-    /// Returns `Some(Self)` if the conversion is successful, otherwise returns `None`.
-    fn from_value( value : &Value ) -> Option<Self> where Self: Sized;
+    /// Returns the error message of the conversion if it fails, like serde's `missing field`.
+    fn from_value( value : &Value ) -> Result<Self, String> where Self: Sized;
 }
 
 impl FromValue for String
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_string( value )
     }
@@ -46,7 +50,7 @@ impl FromValue for String
 
 impl FromValue for bool
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -54,7 +58,7 @@ impl FromValue for bool
 
 impl FromValue for f32
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -62,7 +66,7 @@ impl FromValue for f32
 
 impl FromValue for f64
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -70,7 +74,7 @@ impl FromValue for f64
 
 impl FromValue for i8
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -78,7 +82,7 @@ impl FromValue for i8
 
 impl FromValue for i16
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -86,7 +90,7 @@ impl FromValue for i16
 
 impl FromValue for i32
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -94,7 +98,7 @@ impl FromValue for i32
 
 impl FromValue for i64
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -102,7 +106,7 @@ impl FromValue for i64
 
 impl FromValue for u8
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -110,7 +114,7 @@ impl FromValue for u8
 
 impl FromValue for u16
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -118,7 +122,7 @@ impl FromValue for u16
 
 impl FromValue for u32
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -126,7 +130,7 @@ impl FromValue for u32
 
 impl FromValue for u64
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -134,7 +138,7 @@ impl FromValue for u64
 
 impl FromValue for u128
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -142,7 +146,7 @@ impl FromValue for u128
 
 impl FromValue for char
 {
-    fn from_value( value : &Value ) -> Option<Self>
+    fn from_value( value : &Value ) -> Result<Self, String>
     {
         from_value_for_from_str( value )
     }
@@ -151,7 +155,7 @@ impl FromValue for char
 impl<T> FromValue for Vec<T>
 where T: FromValue
 {
-    fn from_value( value : &Value ) -> Option<Self> where Self: Sized 
+    fn from_value( value : &Value ) -> Result<Self, String> where Self: Sized 
     {
         from_value_for_vec( value )
     }
@@ -160,7 +164,7 @@ where T: FromValue
 impl<T> FromValue for Option<T>
 where T: FromValue
 {
-    fn from_value( value : &Value ) -> Option<Self> where Self: Sized 
+    fn from_value( value : &Value ) -> Result<Self, String> where Self: Sized 
     {
         from_value_for_option( value )
     }
@@ -192,7 +196,7 @@ mod tests {
 
         let value : Value = serde_json::to_value( "asdf" ).unwrap();
 
-        assert_eq!( char::from_value( &value ), None );
+        assert!( char::from_value( &value ).is_err() );
     }
 
     #[test]
@@ -204,7 +208,7 @@ mod tests {
 
         let value : Value = serde_json::to_value( "asdf" ).unwrap();
 
-        assert_eq!( f32::from_value( &value ), None );
+        assert!( f32::from_value( &value ).is_err() );
     }
 
     #[test]
@@ -216,6 +220,6 @@ mod tests {
 
         let value : Value = serde_json::to_value( "asdf" ).unwrap();
 
-        assert_eq!( f32::from_value( &value ), None );
+        assert!( f32::from_value( &value ).is_err() );
     }
 }
\ No newline at end of file
@@ -4,14 +4,14 @@ use serde_json::Value;
 
 use crate::parser::text::text_parser::get_text_from_value;
 
-pub fn from_value_for_from_str<T>( value : &Value ) -> Option<T>
-where T: FromStr
+pub fn from_value_for_from_str<T>( value : &Value ) -> Result<T, String>
+where T: FromStr, T::Err: std::fmt::Display
 {
     match get_text_from_value( value ) {
         Ok( v ) => match v.parse() {
-            Ok( v ) => Some( v ),
-            _ => None
+            Ok( v ) => Ok( v ),
+            Err( e ) => Err( e.to_string() )
         },
-        _ => None
+        Err( e ) => Err( e.to_string() )
     }
 }
\ No newline at end of file
@@ -2,12 +2,13 @@ use serde_json::Value;
 
 use super::from_value::FromValue;
 
-pub fn from_value_for_option<T>( value : &Value ) -> Option<Option<T>>
+// A value that can't be converted is `None`, not an error.
+pub fn from_value_for_option<T>( value : &Value ) -> Result<Option<T>, String>
 where T: FromValue
 {
     match value
     {
-        Value::Null => Some( None ),
-        _ => Some( T::from_value( value ) )  
+        Value::Null => Ok( None ),
+        _ => Ok( T::from_value( value ).ok() )  
     }
 }
\ No newline at end of file
@@ -2,11 +2,10 @@ use serde_json::Value;
 
 use crate::parser::text::text_parser::get_text_from_value;
 
-pub fn from_value_for_string( value : &Value ) -> Option<String>
+pub fn from_value_for_string( value : &Value ) -> Result<String, String>
 {
     match get_text_from_value( value ) {
-        Ok( v ) => Some( v ),
-        _ => None
+        Ok( v ) => Ok( v ),
+        Err( e ) => Err( e.to_string() )
     }
 }
-
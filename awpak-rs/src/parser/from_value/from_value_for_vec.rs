@@ -2,35 +2,35 @@ use serde_json::Value;
 
 use super::from_value::FromValue;
 
-pub fn from_value_for_vec<T>( value : &Value ) -> Option<Vec<T>>
+pub fn from_value_for_vec<T>( value : &Value ) -> Result<Vec<T>, String>
 where T: FromValue
 {
     match value
     {
         Value::Array( v ) => from_value_for_array( v ),
         Value::String( v ) => from_value_for_string( v ),
-        _ => None
+        _ => Err( "invalid type, expected an array".to_string() )
     }
 }
 
-fn from_value_for_array<T>( value : &Vec<Value> ) -> Option<Vec<T>>
+fn from_value_for_array<T>( value : &[Value] ) -> Result<Vec<T>, String>
 where T: FromValue
 {
     let mut ret : Vec<T> = vec![];
 
-    for item in value
+    for ( i, item ) in value.iter().enumerate()
     {
         match T::from_value( item )
         {
-            Some( v ) => ret.push( v ),
-            _ => return None    
+            Ok( v ) => ret.push( v ),
+            Err( e ) => return Err( format!( "item {}: {}", i, e ) )
         }
     }
 
-    Some( ret )
+    Ok( ret )
 }
 
-fn from_value_for_string<T>( value : &String ) -> Option<Vec<T>>
+fn from_value_for_string<T>( value : &String ) -> Result<Vec<T>, String>
 where T: FromValue
 {
     let value = value.trim();
@@ -39,7 +39,7 @@ where T: FromValue
     {
         if value.starts_with( "[" ) || value.ends_with( "]" )
         {
-            return None
+            return Err( "unbalanced brackets".to_string() )
         }
         else
         {
@@ -56,9 +56,9 @@ where T: FromValue
         Ok( v ) => match v
         {
             Value::Array( v ) => from_value_for_array( &v ),
-            _ => None    
+            _ => Err( "invalid type, expected an array".to_string() )
         },
-        _ => None    
+        Err( e ) => Err( e.to_string() )
     }
 }
 
@@ -69,65 +69,65 @@ mod tests {
     #[test]
     fn test_from_value_for_string()
     {
-        let result : Option<Vec<String>> = from_value_for_string( &r#"["hello", "goodbye"]"#.to_string() );
+        let result : Result<Vec<String>, String> = from_value_for_string( &r#"["hello", "goodbye"]"#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ "hello", "goodbye" ] );
 
-        let result : Option<Vec<String>> = from_value_for_string( &r#" [ "hello" , "goodbye" ]  "#.to_string() );
+        let result : Result<Vec<String>, String> = from_value_for_string( &r#" [ "hello" , "goodbye" ]  "#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ "hello", "goodbye" ] );
 
-        let result : Option<Vec<String>> = from_value_for_string( &r#"["hello", "goodbye""#.to_string() );
+        let result : Result<Vec<String>, String> = from_value_for_string( &r#"["hello", "goodbye""#.to_string() );
 
-        assert!( result.is_none() );
+        assert!( result.is_err() );
 
-        let result : Option<Vec<String>> = from_value_for_string( &r#""hello", "goodbye"]"#.to_string() );
+        let result : Result<Vec<String>, String> = from_value_for_string( &r#""hello", "goodbye"]"#.to_string() );
 
-        assert!( result.is_none() );
+        assert!( result.is_err() );
 
-        let result : Option<Vec<String>> = from_value_for_string( &r#""hello", "goodbye""#.to_string() );
+        let result : Result<Vec<String>, String> = from_value_for_string( &r#""hello", "goodbye""#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ "hello", "goodbye" ] );
 
-        let result : Option<Vec<i16>> = from_value_for_string( &r#"1, 7"#.to_string() );
+        let result : Result<Vec<i16>, String> = from_value_for_string( &r#"1, 7"#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ 1, 7 ] );
 
-        let result : Option<Vec<i16>> = from_value_for_string( &r#""1", "7""#.to_string() );
+        let result : Result<Vec<i16>, String> = from_value_for_string( &r#""1", "7""#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ 1, 7 ] );
 
-        let result : Option<Vec<f32>> = from_value_for_string( &r#""1.3", "7.44""#.to_string() );
+        let result : Result<Vec<f32>, String> = from_value_for_string( &r#""1.3", "7.44""#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ 1.3, 7.44 ] );
 
-        let result : Option<Vec<f32>> = from_value_for_string( &r#"1.3, 7.44"#.to_string() );
+        let result : Result<Vec<f32>, String> = from_value_for_string( &r#"1.3, 7.44"#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ 1.3, 7.44 ] );
 
-        let result : Option<Vec<f32>> = from_value_for_string( &r#""1.3", 7.44"#.to_string() );
+        let result : Result<Vec<f32>, String> = from_value_for_string( &r#""1.3", 7.44"#.to_string() );
 
-        assert!( result.is_some() );
+        assert!( result.is_ok() );
 
         assert_eq!( result.unwrap(), vec![ 1.3, 7.44 ] );
 
-        let result : Option<Vec<f32>> = from_value_for_string( &r#"{"x":33,"y":21}"#.to_string() );
+        let result : Result<Vec<f32>, String> = from_value_for_string( &r#"{"x":33,"y":21}"#.to_string() );
 
-        assert!( result.is_none() );
+        assert!( result.is_err() );
 
     }
 }
\ No newline at end of file
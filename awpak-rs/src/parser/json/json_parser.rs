@@ -7,7 +7,7 @@ pub fn json_parser( bytes : Bytes ) -> Result<serde_json::Value, Error>
 {
     match serde_json::from_slice::<serde_json::Value>( &bytes ) {
         Ok( v ) => Ok( v ),
-        _ => Err( Error::ParserError( "Invalid json".to_string() ) )
+        Err( e ) => Err( Error::ParserError( format!( "Invalid json: {}", e ) ) )
     }
 }
 
@@ -22,4 +22,9 @@ pub fn json_serializer( value : serde_json::Value ) -> Result<Bytes, Error>
             _ => Err( Error::ParserError( "Invalid response json".to_string() ) )
         }
     }
-}
\ No newline at end of file
+}
+#[strategy_pattern_fn( key = "application/problem+json", strategy = ResponseContentTypeStrategy )]
+pub fn problem_json_serializer( value : serde_json::Value ) -> Result<Bytes, Error>
+{
+    json_serializer( value )
+}
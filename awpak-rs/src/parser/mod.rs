@@ -1,5 +1,16 @@
 pub mod json;
 pub mod parser;
 pub mod text;
+pub mod form;
+pub mod codec;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+pub mod binary;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+#[cfg(feature = "xml")]
+pub mod xml;
 pub mod from_value;
-pub mod from_async_str;
\ No newline at end of file
+pub mod from_async_str;
+pub mod from_path_wildcard;
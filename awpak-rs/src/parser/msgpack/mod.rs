@@ -0,0 +1 @@
+pub mod msgpack_parser;
\ No newline at end of file
@@ -0,0 +1,68 @@
+use hyper::body::Bytes;
+use strategy_pattern_rs::strategy_pattern_fn;
+use crate::{parser::binary::binary_value::BinaryValue, Error, ContentTypeStrategy, ResponseContentTypeStrategy};
+
+#[strategy_pattern_fn( key = "application/msgpack", strategy = ContentTypeStrategy )]
+pub fn msgpack_parser( bytes : Bytes ) -> Result<serde_json::Value, Error>
+{
+    match rmp_serde::from_slice::<BinaryValue>( &bytes ) {
+        Ok( v ) => Ok( v.0 ),
+        _ => Err( Error::ParserError( "Invalid msgpack".to_string() ) )
+    }
+}
+
+// Objects are written as maps with the names of their fields
+#[strategy_pattern_fn( key = "application/msgpack", strategy = ResponseContentTypeStrategy )]
+pub fn msgpack_serializer( value : serde_json::Value ) -> Result<Bytes, Error>
+{
+    match rmp_serde::to_vec_named( &value ) {
+        Ok( v ) => Ok( v.into() ),
+        _ => Err( Error::ParserError( "Invalid response msgpack".to_string() ) )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point
+    {
+        x : f32,
+        y : Option<String>,
+        tags : Vec<u8>
+    }
+
+    #[test]
+    fn test_msgpack_round_trip()
+    {
+        let point = Point { x : 1.5, y : Some( "a".to_string() ), tags : vec![ 1, 2 ] };
+
+        let value = msgpack_parser( rmp_serde::to_vec_named( &point ).unwrap().into() );
+
+        assert!( value.is_ok(), "value is Err" );
+
+        let value = value.unwrap();
+
+        assert_eq!( value, serde_json::json!( { "x" : 1.5, "y" : "a", "tags" : [ 1, 2 ] } ) );
+
+        let bytes = msgpack_serializer( value );
+
+        assert!( bytes.is_ok(), "bytes is Err" );
+
+        assert_eq!( rmp_serde::from_slice::<Point>( &bytes.unwrap() ).unwrap(), point );
+    }
+
+    #[test]
+    fn test_msgpack_binary_types()
+    {
+        // { 1: bin [1, 2], "f": NaN }
+        let bytes = Bytes::from_static( &[ 0x82, 0x01, 0xc4, 0x02, 0x01, 0x02, 0xa1, b'f', 0xcb, 0x7f, 0xf8, 0, 0, 0, 0, 0, 0 ] );
+
+        assert_eq!( msgpack_parser( bytes ).unwrap(), serde_json::json!( { "1" : [ 1, 2 ], "f" : null } ) );
+
+        assert!( msgpack_parser( Bytes::from_static( &[ 0xc1 ] ) ).is_err() );
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde_json::Value;
 
-use crate::{from_async_str::FromAsyncStr, from_value::FromValue, io::io::IO};
+use crate::{from_async_str::FromAsyncStr, from_path_wildcard::FromPathWildcard, from_value::FromValue, io::io::IO};
 
 pub fn serialize_value<T>( value : T ) -> Option<Value>
 where T: serde::Serialize
@@ -13,54 +13,59 @@ where T: serde::Serialize
     }
 }
 
-pub fn parse_value<T>( io : &IO, from : &str ) -> Option<T>
+pub fn parse_value<T>( io : &IO, from : &str ) -> Result<T, String>
 where T: for<'a> serde::Deserialize<'a> + FromValue
 {
     match from
     {
         "request_body" => parse_value_from_request_body( io ),
         "query_params" => parse_value_from_query_params( io ),
-        _ => None
+        _ => Err( format!( "Unknown source {}", from ) )
     }
 }
 
-pub fn parse_body_param_value<T>( io : &IO, name : &str ) -> Option<T>
+pub fn parse_body_param_value<T>( io : &IO, name : &str ) -> Result<T, String>
 where T: for<'a> serde::Deserialize<'a> + FromValue
 {
-    match io.request.body.get_param( name ) {
-        Some( v ) => T::from_value( v ),
-        _ => None
+    match ( io.request.body.get_param( name ), &io.request.body.error ) {
+        ( Some( v ), _ ) => T::from_value( v ),
+        ( _, Some( e ) ) => Err( e.clone() ),
+        _ => Err( "missing param".to_string() )
     }
 }
 
-pub fn parse_from_value<T>( value : &Value ) -> Option<T>
+/// Deserializes a `Value` into `T`. A string value that doesn't match `T` is parsed as JSON.
+///
+/// Returns the serde error of the value if both fail.
+pub fn parse_from_value<T>( value : &Value ) -> Result<T, String>
 where T: for<'a> serde::Deserialize<'a>
 {
     match serde_json::from_value( value.clone() )
     {
-        Ok( v ) => Some( v ),
-        _ => match value {
+        Ok( v ) => Ok( v ),
+        Err( e ) => match value {
             serde_json::Value::String( v ) => match serde_json::from_str( v )
             {
-                Ok( v ) => Some( v ),
-                _ => None
+                Ok( v ) => Ok( v ),
+                _ => Err( e.to_string() )
             },
-            _ => None
+            _ => Err( e.to_string() )
         }
     }
 }
 
-fn parse_value_from_request_body<T>( io : &IO ) -> Option<T>
+fn parse_value_from_request_body<T>( io : &IO ) -> Result<T, String>
 where T: for<'a> serde::Deserialize<'a> + FromValue
 {
-    match io.request.body.value.as_ref()
+    match ( io.request.body.value.as_ref(), &io.request.body.error )
     {
-        Some( v ) => T::from_value( v ),
-        _ => None
+        ( Some( v ), _ ) => T::from_value( v ),
+        ( _, Some( e ) ) => Err( e.clone() ),
+        _ => Err( "missing body".to_string() )
     }
 }
 
-pub fn parse_query_param_value<T>( io : &IO, name : &str ) -> Option<T>
+pub fn parse_query_param_value<T>( io : &IO, name : &str ) -> Result<T, String>
 where T: for<'a> serde::Deserialize<'a> + FromValue
 {
     match &io.request.uri.query_map
@@ -73,7 +78,7 @@ where T: for<'a> serde::Deserialize<'a> + FromValue
                 _ => match serde_json::to_value( s )
                 {
                     Ok( v ) => T::from_value( &v ),
-                    _ => None    
+                    Err( e ) => Err( e.to_string() )
                 }
             },
             _ => T::from_value( &serde_json::Value::Null )    
@@ -82,7 +87,7 @@ where T: for<'a> serde::Deserialize<'a> + FromValue
     }
 }
 
-fn parse_value_from_query_params<T>( io : &IO ) -> Option<T>
+fn parse_value_from_query_params<T>( io : &IO ) -> Result<T, String>
 where T: for<'a> serde::Deserialize<'a>
 {
     let query_params = &io.request.uri.query;
@@ -91,12 +96,7 @@ where T: for<'a> serde::Deserialize<'a>
     {
         let salida : Result<T, _> = serde_qs::from_str( "" );
 
-        if salida.is_err()
-        {
-            return None;
-        }
-
-        return Some( salida.unwrap() );
+        return salida.map_err( | e | e.to_string() );
     }
 
     let salida : Result<T, _> = serde_qs::from_str( query_params.as_ref().unwrap() );
@@ -106,7 +106,7 @@ where T: for<'a> serde::Deserialize<'a>
         //TODO: Hacer que el query_map sea un HashMap<String, Value>. Habrá que revisar la función parse_query_param_value
         if io.request.uri.query_map.is_none()
         {
-            return None;
+            return salida.map_err( | e | e.to_string() );
         }
 
         let mut map : HashMap<String, serde_json::Value> = HashMap::new();
@@ -128,37 +128,37 @@ where T: for<'a> serde::Deserialize<'a>
             }
         }
 
-        let value = serde_json::to_value( map );
-
-        if value.is_err()
+        let value = match serde_json::to_value( map )
         {
-            return None;
-        }
+            Ok( v ) => v,
+            Err( e ) => return Err( e.to_string() )
+        };
 
-        let salida : Result<T, _> = serde_json::from_value( value.unwrap() );
+        let salida : Result<T, _> = serde_json::from_value( value );
 
-        if salida.is_err()
-        {
-            return None;
-        }
-
-        return Some( salida.unwrap() )
+        return salida.map_err( | e | e.to_string() )
     }
 
-    Some( salida.unwrap() )
+    Ok( salida.unwrap() )
 }
 
-pub async fn parse_path_variable<T>( io : &IO, ind : usize ) -> Option<T>
+pub async fn parse_path_variable<T>( io : &IO, name : &str ) -> Result<T, String>
 where T: FromAsyncStr<T>
 {
-    match io.request.uri.path.split( "/" ).enumerate().find(  | v | v.0 == ind ).map( | v | v.1 )
+    match io.request.path_variables.get( name )
     {
-        Some( v ) => match T::from_async_str( io, v ).await
-        {
-            Ok( v ) => Some( v ),
-            _ => None
-        },
-        _ => None
+        Some( v ) => T::try_from_async_str( io, v ).await,
+        _ => Err( "missing path variable".to_string() )
+    }
+}
+
+pub fn parse_path_wildcard<T>( io : &IO, name : &str ) -> Result<T, String>
+where T: FromPathWildcard
+{
+    match io.request.path_variables.get( name )
+    {
+        Some( v ) => T::from_path_wildcard( v ).ok_or( "invalid value".to_string() ),
+        _ => Err( "missing path variable".to_string() )
     }
 }
 
@@ -175,10 +175,38 @@ mod tests {
 
         let val  = parse_query_param_value::<Option<String>>( &io, "a" );
 
-        assert!( val.is_some() );
+        assert!( val.is_ok() );
 
         let val = val.unwrap();
 
         assert!( val.is_none() );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_query_param_value_error()
+    {
+        let mut io = IO::with_response( ResponseData::default() );
+
+        io.request.uri.query_map = Some( HashMap::from( [ ( "y".to_string(), "abc".to_string() ) ] ) );
+
+        assert_eq!( parse_query_param_value::<f32>( &io, "y" ), Err( "invalid float literal".to_string() ) );
+
+        assert_eq!( parse_query_param_value::<Option<f32>>( &io, "y" ), Ok( None ) );
+
+        assert_eq!( parse_query_param_value::<Vec<u8>>( &io, "y" ).err().unwrap(), "expected value at line 1 column 2" );
+    }
+
+    #[test]
+    fn test_parse_from_value_error()
+    {
+        #[derive(serde::Deserialize, Debug)]
+        struct Point
+        {
+            _x : i32
+        }
+
+        let result = parse_from_value::<Point>( &serde_json::json!( { "y" : 3 } ) );
+
+        assert_eq!( result.err().unwrap(), "missing field `_x`" );
+    }
+}
@@ -0,0 +1 @@
+pub mod xml_parser;
\ No newline at end of file
@@ -0,0 +1,369 @@
+use hyper::body::Bytes;
+use quick_xml::{events::{BytesStart, Event}, escape::escape, Reader};
+use serde_json::{Map, Value};
+use strategy_pattern_rs::strategy_pattern_fn;
+use crate::{Error, ContentTypeStrategy, ResponseContentTypeStrategy};
+
+/// The name of the root element of XML responses when the endpoint doesn't set `xml_root`.
+pub const DEFAULT_XML_ROOT : &str = "response";
+
+/// Parses an `application/xml` body into a JSON value.
+///
+/// - The root element is the body, its name is ignored.
+/// - An element without attributes and child elements is a string with its text: `<name>Ana</name>` is `"Ana"`.
+/// - Other elements are objects:
+///   - Attributes are keys starting with `@`: `<user id="1"/>` is `{"@id": "1"}`.
+///   - Child elements are keys with their name, and repeated elements are arrays:
+///     `<list><tag>a</tag><tag>b</tag></list>` is `{"tag": ["a", "b"]}`. An element that appears once is not an array.
+///   - The text, if it's not only whitespace, is the `#text` key: `<price currency="EUR">10</price>` is `{"@currency": "EUR", "#text": "10"}`.
+/// - Values are always strings, like in `application/x-www-form-urlencoded` bodies.
+/// - Comments, processing instructions and the document type are ignored.
+#[strategy_pattern_fn( key = "application/xml", strategy = ContentTypeStrategy )]
+pub fn xml_parser( bytes : Bytes ) -> Result<serde_json::Value, Error>
+{
+    match parse( &bytes )
+    {
+        Ok( v ) => Ok( v ),
+        Err( e ) => Err( Error::ParserError( format!( "Invalid xml: {}", e ) ) )
+    }
+}
+
+/// Writes a JSON value as an `application/xml` body, inside a root element named `response`.
+///
+/// The mapping is the one of `xml_parser`, the other way around:
+///
+/// - Keys starting with `@` are attributes, `#text` is the text and other keys are child elements.
+/// - Arrays are repeated elements with the name of their key. Arrays in arrays, or as the whole body, are `item` elements.
+/// - `null` is an empty element, and strings, numbers and booleans are text.
+///
+/// Fails if a key is not a valid XML name, or if an attribute or `#text` is an object or an array.
+#[strategy_pattern_fn( key = "application/xml", strategy = ResponseContentTypeStrategy )]
+pub fn xml_serializer( value : serde_json::Value ) -> Result<Bytes, Error>
+{
+    to_xml( value, DEFAULT_XML_ROOT )
+}
+
+/// Writes a JSON value as XML, like `xml_serializer`, inside a root element named `root`.
+pub fn to_xml( value : serde_json::Value, root : &str ) -> Result<Bytes, Error>
+{
+    let mut xml = String::from( "<?xml version=\"1.0\"?>" );
+
+    match write_element( &mut xml, root, &value )
+    {
+        Ok( _ ) => Ok( xml.into() ),
+        Err( e ) => Err( Error::ParserError( format!( "Invalid response xml: {}", e ) ) )
+    }
+}
+
+#[derive(Default)]
+struct Element
+{
+    attributes : Map<String, Value>,
+    children : Map<String, Value>,
+    text : String
+}
+
+impl Element
+{
+    fn new( start : &BytesStart ) -> Result<Self, String>
+    {
+        let mut element = Self::default();
+
+        for attribute in start.attributes()
+        {
+            let attribute = attribute.map_err( | e | e.to_string() )?;
+
+            let name = String::from_utf8_lossy( attribute.key.as_ref() );
+
+            let value = attribute.unescape_value().map_err( | e | e.to_string() )?;
+
+            element.attributes.insert( format!( "@{}", name ), Value::String( value.into_owned() ) );
+        }
+
+        Ok( element )
+    }
+
+    // A repeated child element turns its value into an array
+    fn add_child( &mut self, name : String, value : Value )
+    {
+        match self.children.get_mut( &name )
+        {
+            Some( Value::Array( a ) ) => a.push( value ),
+            Some( current ) => {
+                let first = current.take();
+
+                *current = Value::Array( vec![ first, value ] );
+            },
+            None => { self.children.insert( name, value ); }
+        }
+    }
+
+    fn into_value( self ) -> Value
+    {
+        if self.attributes.is_empty() && self.children.is_empty()
+        {
+            return Value::String( self.text )
+        }
+
+        let mut object = self.attributes;
+
+        object.extend( self.children );
+
+        let text = self.text.trim();
+
+        if ! text.is_empty()
+        {
+            object.insert( "#text".to_string(), Value::String( text.to_string() ) );
+        }
+
+        Value::Object( object )
+    }
+}
+
+fn parse( bytes : &[u8] ) -> Result<Value, String>
+{
+    let mut reader = Reader::from_reader( bytes );
+
+    let mut stack : Vec<( String, Element )> = vec![];
+
+    let mut root = None;
+
+    let mut buffer = vec![];
+
+    loop
+    {
+        match reader.read_event_into( &mut buffer ).map_err( | e | e.to_string() )?
+        {
+            Event::Start( e ) => {
+                let name = get_name( &e, &stack, &root )?;
+
+                stack.push( ( name, Element::new( &e )? ) );
+            },
+            Event::Empty( e ) => {
+                let name = get_name( &e, &stack, &root )?;
+
+                close_element( &mut stack, &mut root, name, Element::new( &e )? );
+            },
+            Event::End( _ ) => match stack.pop()
+            {
+                Some( ( name, element ) ) => close_element( &mut stack, &mut root, name, element ),
+                None => return Err( "unexpected end tag".to_string() )
+            },
+            Event::Text( e ) => {
+                let text = e.unescape().map_err( | e | e.to_string() )?;
+
+                add_text( &mut stack, &text )?;
+            },
+            Event::CData( e ) => add_text( &mut stack, &String::from_utf8_lossy( &e ) )?,
+            Event::Eof => break,
+            _ => {}
+        }
+
+        buffer.clear();
+    }
+
+    match ( root, stack.is_empty() )
+    {
+        ( Some( v ), true ) => Ok( v ),
+        ( _, false ) => Err( "unclosed element".to_string() ),
+        ( None, true ) => Err( "missing root element".to_string() )
+    }
+}
+
+// There can only be one root element
+fn get_name( start : &BytesStart, stack : &[( String, Element )], root : &Option<Value> ) -> Result<String, String>
+{
+    if stack.is_empty() && root.is_some()
+    {
+        return Err( "more than one root element".to_string() )
+    }
+
+    Ok( String::from_utf8_lossy( start.name().as_ref() ).into_owned() )
+}
+
+fn close_element( stack : &mut [( String, Element )], root : &mut Option<Value>, name : String, element : Element )
+{
+    match stack.last_mut()
+    {
+        Some( ( _, parent ) ) => parent.add_child( name, element.into_value() ),
+        None => *root = Some( element.into_value() )
+    }
+}
+
+// Whitespace is allowed around the root element, but not text
+fn add_text( stack : &mut [( String, Element )], text : &str ) -> Result<(), String>
+{
+    match stack.last_mut()
+    {
+        Some( ( _, element ) ) => element.text.push_str( text ),
+        None if text.trim().is_empty() => {},
+        None => return Err( "text outside of the root element".to_string() )
+    }
+
+    Ok( () )
+}
+
+fn write_element( xml : &mut String, name : &str, value : &Value ) -> Result<(), String>
+{
+    check_name( name )?;
+
+    match value
+    {
+        Value::Object( object ) => {
+            xml.push_str( &format!( "<{}", name ) );
+
+            for ( key, value ) in object.iter().filter( | ( k, _ ) | k.starts_with( '@' ) )
+            {
+                check_name( &key[ 1.. ] )?;
+
+                xml.push_str( &format!( " {}=\"{}\"", &key[ 1.. ], escape( &get_text( key, value )? ) ) );
+            }
+
+            xml.push( '>' );
+
+            for ( key, value ) in object.iter().filter( | ( k, _ ) | ! k.starts_with( '@' ) )
+            {
+                match value
+                {
+                    _ if key == "#text" => xml.push_str( &escape( &get_text( key, value )? ) ),
+                    Value::Array( items ) => for item in items
+                    {
+                        write_element( xml, key, item )?;
+                    },
+                    _ => write_element( xml, key, value )?
+                }
+            }
+        },
+        Value::Array( items ) => {
+            xml.push_str( &format!( "<{}>", name ) );
+
+            for item in items
+            {
+                write_element( xml, "item", item )?;
+            }
+        },
+        Value::Null => {
+            xml.push_str( &format!( "<{}/>", name ) );
+
+            return Ok( () )
+        },
+        _ => xml.push_str( &format!( "<{}>{}", name, escape( &get_text( name, value )? ) ) )
+    }
+
+    xml.push_str( &format!( "</{}>", name ) );
+
+    Ok( () )
+}
+
+fn get_text( name : &str, value : &Value ) -> Result<String, String>
+{
+    match value
+    {
+        Value::String( s ) => Ok( s.clone() ),
+        Value::Null => Ok( String::new() ),
+        Value::Bool( _ ) | Value::Number( _ ) => Ok( value.to_string() ),
+        _ => Err( format!( "{} must be a string, a number or a boolean", name ) )
+    }
+}
+
+fn check_name( name : &str ) -> Result<(), String>
+{
+    let mut chars = name.chars();
+
+    match chars.next()
+    {
+        Some( c ) if ( c.is_alphabetic() || c == '_' ) && chars.all( | c | c.is_alphanumeric() || "_-.:".contains( c ) ) => Ok( () ),
+        _ => Err( format!( "invalid element name \"{}\"", name ) )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse( body : &'static str ) -> Value
+    {
+        let value = xml_parser( Bytes::from( body ) );
+
+        assert!( value.is_ok(), "value is Err" );
+
+        value.unwrap()
+    }
+
+    #[test]
+    fn test_xml_parser_elements()
+    {
+        assert_eq!(
+            parse( "<?xml version=\"1.0\"?>\n<user>\n  <name>Ana &amp; Luis</name>\n  <age>30</age>\n  <city/>\n  <!-- a comment -->\n  <note><![CDATA[<b>hi</b>]]></note>\n</user>\n" ),
+            serde_json::json!( { "name" : "Ana & Luis", "age" : "30", "city" : "", "note" : "<b>hi</b>" } )
+        );
+
+        assert_eq!( parse( "<message>hello</message>" ), serde_json::json!( "hello" ) );
+    }
+
+    #[test]
+    fn test_xml_parser_attributes_and_repeated_elements()
+    {
+        assert_eq!(
+            parse( r#"<order id="7"><tag>a</tag><tag>b</tag><price currency="EUR">10</price><item><tag>c</tag></item></order>"# ),
+            serde_json::json!( {
+                "@id" : "7",
+                "tag" : [ "a", "b" ],
+                "price" : { "@currency" : "EUR", "#text" : "10" },
+                "item" : { "tag" : "c" }
+            } )
+        );
+    }
+
+    #[test]
+    fn test_xml_parser_invalid()
+    {
+        assert!( xml_parser( Bytes::from( "" ) ).is_err() );
+        assert!( xml_parser( Bytes::from( "<a><b></a>" ) ).is_err() );
+        assert!( xml_parser( Bytes::from( "<a></a><b></b>" ) ).is_err() );
+        assert!( xml_parser( Bytes::from( "<a></a>text" ) ).is_err() );
+        assert!( xml_parser( Bytes::from( "<a>" ) ).is_err() );
+    }
+
+    #[test]
+    fn test_xml_serializer()
+    {
+        let value = serde_json::json!( {
+            "@id" : 7,
+            "name" : "Ana & Luis",
+            "tags" : [ "a", "b" ],
+            "price" : { "@currency" : "EUR", "#text" : 10.5 },
+            "city" : null,
+            "matrix" : [ [ 1, 2 ] ]
+        } );
+
+        let xml = xml_serializer( value.clone() );
+
+        assert!( xml.is_ok(), "xml is Err" );
+
+        assert_eq!(
+            xml.unwrap(),
+            Bytes::from( concat!(
+                "<?xml version=\"1.0\"?><response id=\"7\"><city/><matrix><item>1</item><item>2</item></matrix>",
+                "<name>Ana &amp; Luis</name><price currency=\"EUR\">10.5</price><tags>a</tags><tags>b</tags></response>"
+            ) )
+        );
+
+        assert_eq!( to_xml( serde_json::json!( [ "a" ] ), "list" ).unwrap(), Bytes::from( "<?xml version=\"1.0\"?><list><item>a</item></list>" ) );
+
+        assert!( xml_serializer( serde_json::json!( { "first name" : "Ana" } ) ).is_err() );
+        assert!( xml_serializer( serde_json::json!( { "@id" : [ 1 ] } ) ).is_err() );
+    }
+
+    #[test]
+    fn test_xml_round_trip()
+    {
+        let value = serde_json::json!( { "@id" : "7", "name" : "Ana", "tag" : [ "a", "b" ], "price" : { "@currency" : "EUR", "#text" : "10" } } );
+
+        let xml = to_xml( value.clone(), "order" );
+
+        assert!( xml.is_ok(), "xml is Err" );
+
+        assert_eq!( xml_parser( xml.unwrap() ).unwrap(), value );
+    }
+}
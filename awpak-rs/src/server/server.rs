@@ -6,11 +6,20 @@ use tokio::net::TcpListener;
 use crate::services::main_service::main_service_fn;
 use crate::util::signals_utils::shutdown_signal;
 
+/// Maximum size of a request body, in bytes, unless changed with `max_body`: 2MB.
+///
+/// Request bodies used to be unlimited. To restore that, set `max_body = "unlimited"` in `awpak_main`,
+/// or call `with_max_body( usize::MAX )`.
+pub const DEFAULT_MAX_BODY : usize = 2 * 1024 * 1024;
+
 #[derive(Clone, Copy)]
 pub struct ServerParams<'a>
 {
     pub ip : &'a str,
-    pub port : &'a str
+    pub port : &'a str,
+    /// Maximum size of a request body, in bytes. Endpoints can change it with `max_body`.
+    /// `DEFAULT_MAX_BODY` (2MB) if not set; `usize::MAX` for no limit.
+    pub max_body : usize
 }
 
 impl<'a> ServerParams<'a>
@@ -20,10 +29,19 @@ impl<'a> ServerParams<'a>
         Self
         {
             ip,
-            port
+            port,
+            max_body : DEFAULT_MAX_BODY
         }
     }
 
+    /// Sets the maximum size of a request body, in bytes.
+    pub fn with_max_body( mut self, max_body : usize ) -> Self
+    {
+        self.max_body = max_body;
+
+        self
+    }
+
     pub fn get_ip_port( &self ) -> String
     {
         format!( "{}:{}", self.ip, self.port )
@@ -32,8 +50,11 @@ impl<'a> ServerParams<'a>
 
 pub async fn server( ip : &'static str, port : &'static str ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
 {
-    let server_params : ServerParams = ServerParams::new( ip, port );
+    server_with_params( ServerParams::new( ip, port ) ).await
+}
 
+pub async fn server_with_params( server_params : ServerParams<'static> ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+{
     let addr = server_params.get_ip_port();
 
     // We create a TcpListener and bind it
@@ -42,8 +63,9 @@ pub async fn server( ip : &'static str, port : &'static str ) -> Result<(), Box<
     // specify our HTTP settings (http1, http2, auto all work)
     let http = http1::Builder::new();
 
-    // the graceful watcher
-    let graceful = hyper_util::server::graceful::GracefulShutdown::new();
+    // the graceful watcher. Every connection holds a receiver until it's closed.
+    // hyper_util's GracefulShutdown can't watch connections with upgrades, so it's done by hand.
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(());
     // when this signal completes, start shutdown
     let mut signal = std::pin::pin!(shutdown_signal());
 
@@ -56,11 +78,22 @@ pub async fn server( ip : &'static str, port : &'static str ) -> Result<(), Box<
 
                 let main_service = main_service_fn( server_params );
                 
-                let conn = http.serve_connection(io, service_fn(main_service));
+                // upgrades are needed by the websocket endpoints
+                let conn = http.serve_connection(io, service_fn(main_service)).with_upgrades();
                 // watch this connection
-                let fut = graceful.watch(conn);
+                let mut shutdown = shutdown_rx.clone();
                 tokio::spawn(async move {
-                    if let Err(e) = fut.await {
+                    let mut conn = std::pin::pin!(conn);
+
+                    let result = tokio::select! {
+                        result = conn.as_mut() => result,
+                        _ = shutdown.changed() => {
+                            conn.as_mut().graceful_shutdown();
+                            conn.await
+                        }
+                    };
+
+                    if let Err(e) = result {
                         eprintln!("Error serving connection: {:?}", e);
                     }
                 });
@@ -76,9 +109,11 @@ pub async fn server( ip : &'static str, port : &'static str ) -> Result<(), Box<
 
     // Now start the shutdown and wait for them to complete
     // Optional: start a timeout to limit how long to wait.
+    drop(shutdown_rx);
+    let _ = shutdown_tx.send(());
 
     tokio::select! {
-        _ = graceful.shutdown() => {
+        _ = shutdown_tx.closed() => {
             eprintln!("all connections gracefully closed");
         },
         _ = tokio::time::sleep(std::time::Duration::from_secs(10)) => {
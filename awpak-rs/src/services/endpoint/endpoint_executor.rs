@@ -1,100 +1,142 @@
 use std::future::IntoFuture as _;
+use std::sync::OnceLock;
 
-use crate::{endpoint::{endpoint::Endpoint, types::EndpointReturnType}, io::io::IO, services::error::Error, util::url_utils::{get_regex, normalize_url}};
+use crate::{endpoint::{endpoint::Endpoint, types::EndpointReturnType}, io::io::IO, services::{error::Error, middleware::middleware::{MiddlewareExecOrder, MiddlewareResponse}}, util::url_utils::normalize_url};
 
-pub async fn endpoint_exec( io : IO ) -> Result<IO, Error>
+use super::router::{ResolvedRoute, ResponseOptions, RouteMatch, Router};
+
+fn router() -> &'static Result<Router, Error> {
+    static ROUTER: OnceLock<Result<Router, Error>> = OnceLock::new();
+    ROUTER.get_or_init(|| Router::from_endpoints( inventory::iter::<Endpoint>.into_iter() ) )
+}
+
+/// Builds the router with every registered endpoint.
+///
+/// Returns an error if an endpoint url is invalid (bad constraint or misplaced wildcard),
+/// if two endpoints conflict or if an attached middleware has an invalid `urls` pattern.
+pub fn initialize_endpoints() -> Result<(), Error>
 {
-    match get_endpoint( &io.request.uri.path, &io.request.method )
+    match router()
     {
-        Ok( e ) => e( io ).into_future().await,
-        Err( e ) => Err( e )
+        Ok( _ ) => Ok( () ),
+        Err( e ) => Err( e.clone() )
     }
 }
 
-fn get_endpoint<'a>( url : &'a str, method : &'a str ) -> Result<fn( IO ) -> EndpointReturnType, Error>
+/// Looks up the endpoint for a request, before its body is read, to know how to read it.
+/// The route is kept in the request, so `endpoint_exec` doesn't search it again. `None` if the router can't be built.
+pub fn resolve_route( path : &str, method : &str ) -> Option<ResolvedRoute>
 {
-    let url = &normalize_url( url );
+    match router()
+    {
+        Ok( r ) => Some( r.resolve( path, method ) ),
+        Err( _ ) => None
+    }
+}
+
+pub async fn endpoint_exec( mut io : IO ) -> Result<IO, ( IO, Error )>
+{
+    let router = match router()
+    {
+        Ok( r ) => r,
+        Err( e ) => return Ok( io.with_error( e.clone() ) )
+    };
 
-    for endpoint in inventory::iter::<Endpoint>
+    let route = match io.request.route.take()
     {
-        match endpoint_url_method_match( endpoint, url, method )
+        Some( r ) if r.path == io.request.uri.path && r.method == io.request.method => r.route,
+        // A PRE middleware changed the path or the method of the request
+        _ => router.find( &io.request.uri.path, &io.request.method )
+    };
+
+    match route
+    {
+        RouteMatch::Found( e, path_variables, middlewares, options ) => {
+
+            io.request.path_variables = path_variables;
+
+            if let Err( e ) = set_produced_content_type( &mut io, options )
+            {
+                return Ok( io.with_error( e ) )
+            }
+
+            io.response.xml_root = options.xml_root.map( | r | r.to_string() );
+
+            if middlewares.is_empty()
+            {
+                return e( io ).into_future().await
+            }
+
+            // A cancelled attached middleware skips the endpoint, but the global POST middlewares still run
+            let io = match middlewares.exec( io, MiddlewareExecOrder::PRE ).await
+            {
+                MiddlewareResponse::Next( v ) => v,
+                MiddlewareResponse::Cancel( v ) => return Ok( v )
+            };
+
+            let io = middlewares.exec_around( io, e ).await?;
+
+            match middlewares.exec( io, MiddlewareExecOrder::POST ).await
+            {
+                MiddlewareResponse::Next( v ) |
+                MiddlewareResponse::Cancel( v ) => Ok( v )
+            }
+        },
+        RouteMatch::MethodNotAllowed( allowed ) => if io.request.method.eq_ignore_ascii_case( "OPTIONS" )
         {
-            Ok( r ) => if r { return Ok( endpoint.fnc ) },
-            Err( e ) => return Err( e )
-        };
-    }
+            Ok( options_response( io, &allowed ) )
+        }
+        else
+        {
+            Ok( io.with_error( Error::MethodNotAllowed( allowed.join( ", " ) ) ) )
+        },
+        RouteMatch::NotFound => {
 
-    // for t in ENDPOINTS
-    // {
-    //     match endpoint_url_method_match( &t.0, url, method )
-    //     {
-    //         Ok( r ) => if r { return Ok( t ) },
-    //         Err( e ) => return Err( e )
-    //     };
-    // }
+            let error = Error::EndpointNotFound( format!( "Endpoint not found: {}", normalize_url( &io.request.uri.path ) ) );
 
-    Err( Error::EndpointNotFound( format!( "Endpoint not found: {}", url ) ) )
+            Ok( io.with_error( error ) )
+        }
+    }
+}
+
+/// `endpoint_exec` as the innermost call of the `around` middlewares.
+pub fn endpoint_exec_boxed( io : IO ) -> EndpointReturnType
+{
+    Box::pin( endpoint_exec( io ) )
 }
 
-fn endpoint_url_method_match( endpoint : &Endpoint, url : &str, method : &str ) -> Result<bool, Error>
+// Chooses the content type of the response among the `produces` types of the endpoint, before it runs,
+// so the endpoint doesn't run if the client accepts none of them.
+fn set_produced_content_type( io : &mut IO, options : ResponseOptions ) -> Result<(), Error>
 {
-    if endpoint.method.to_uppercase().trim() != method.to_uppercase().trim()
+    if options.produces.is_empty()
     {
-        return Ok( false );
+        return Ok( () )
     }
 
-    let endpoint_url = normalize_url( &endpoint.url );
+    let content_type = match io.request.headers.content_negotiation( options.produces )
+    {
+        Some( c ) => c,
+        None if ! options.not_acceptable => options.produces[ 0 ].to_string(),
+        None => return Err( Error::NotAcceptable( options.produces.join( ", " ) ) )
+    };
+
+    io.response.headers.replace_header( "content-type".to_string(), content_type );
 
-    match get_regex( &endpoint_url )
+    if options.produces.len() > 1
     {
-        Ok( r ) => match r {
-            Some( r ) => if r.is_match( &url ) { Ok( true ) } else { Ok( false ) },
-            _ => if endpoint_url == url { Ok( true ) } else { Ok( false ) }
-        },
-        Err( e ) => Err( e ) 
+        io.response.headers.replace_header( "vary".to_string(), "Accept".to_string() );
     }
+
+    Ok( () )
 }
 
-// pub async fn endpoint_exec_2( io : IO ) -> Result<IO, Error>
-// {
-//     match get_endpoint_2( &io.request.uri.path, &io.request.method )
-//     {
-//         Ok( e ) => e.1( io ).into_future().await,
-//         Err( e ) => Err( e )
-//     }
-// }
-
-// fn get_endpoint_2<'a>( url : &'a str, method : &'a str ) -> Result<&'a EndpointType, Error>
-// {
-//     let url = &normalize_url( url );
-
-//     for t in ENDPOINTS
-//     {
-//         match endpoint_url_method_match_2( &t.0, url, method )
-//         {
-//             Ok( r ) => if r { return Ok( t ) },
-//             Err( e ) => return Err( e )
-//         };
-//     }
-
-//     Err( Error::EndpointNotFound( format!( "Endpoint not found: {}", url ) ) )
-// }
-
-// fn endpoint_url_method_match_2( data : &EndpointData, url : &str, method : &str ) -> Result<bool, Error>
-// {
-//     if data.method.to_uppercase().trim() != method.to_uppercase().trim()
-//     {
-//         return Ok( false );
-//     }
-
-//     let endpoint_url = normalize_url( data.url );
-
-//     match get_regex( &endpoint_url )
-//     {
-//         Ok( r ) => match r {
-//             Some( r ) => if r.is_match( &url ) { Ok( true ) } else { Ok( false ) },
-//             _ => if endpoint_url == url { Ok( true ) } else { Ok( false ) }
-//         },
-//         Err( e ) => Err( e ) 
-//     }
-// }
+// Automatic answer to an OPTIONS request when there is no explicit `#[options]` endpoint for the path.
+fn options_response( mut io : IO, allowed : &[String] ) -> IO
+{
+    io.response.status = 204;
+
+    io.response.headers.replace_header( "allow".to_string(), allowed.join( ", " ) );
+
+    io
+}
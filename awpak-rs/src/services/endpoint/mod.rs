@@ -1 +1,4 @@
-pub mod endpoint_executor;
\ No newline at end of file
+pub mod endpoint_executor;
+pub mod route_segment;
+pub mod router;
+pub mod routes;
\ No newline at end of file
@@ -0,0 +1,307 @@
+use std::{collections::HashSet, str::FromStr};
+
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use regex_automata::{dfa::{dense::DFA, Automaton}, util::start::Config, Anchored};
+
+use crate::services::error::Error;
+
+/// A segment of an endpoint url template.
+#[derive(Clone)]
+pub enum RouteSegment
+{
+    /// A literal segment, percent-decoded.
+    Static( String ),
+
+    /// A `{name}` or `{name:constraint}` segment.
+    Variable( String, SegmentConstraint ),
+
+    /// A `{*name}` segment, which matches the rest of the path. It must be the last segment.
+    Wildcard( String )
+}
+
+/// Restriction applied to a `{name:constraint}` segment.
+///
+/// The constraint can be the name of a Rust numeric type (`u64`, `i32`, `f64`...), `bool`,
+/// `int` (same as `i64`), `uint` (same as `u64`), or a regular expression that must match the whole segment.
+#[derive(Clone)]
+pub enum SegmentConstraint
+{
+    Any,
+    Type( String, fn( &str ) -> bool ),
+    Regex( Regex )
+}
+
+impl SegmentConstraint
+{
+    fn parse( constraint : &str ) -> Result<Self, Error>
+    {
+        if let Some( f ) = get_type_constraint( constraint )
+        {
+            return Ok( SegmentConstraint::Type( constraint.to_string(), f ) )
+        }
+
+        match Regex::new( &format!( "^(?:{})$", constraint ) )
+        {
+            Ok( r ) => Ok( SegmentConstraint::Regex( r ) ),
+            Err( e ) => Err( Error::RegexError( e.to_string() ) )
+        }
+    }
+
+    /// Text that identifies the constraint. Two constraints with the same key are the same constraint.
+    pub fn key( &self ) -> &str
+    {
+        match self
+        {
+            SegmentConstraint::Any => "",
+            SegmentConstraint::Type( name, _ ) => name,
+            SegmentConstraint::Regex( r ) => r.as_str()
+        }
+    }
+
+    /// Returns `true` if a segment can match both constraints.
+    ///
+    /// The automata of the regular expressions are compared, and a type is compared as the regular expression of the text it parses.
+    /// If an automaton can't be built, like for a regular expression with a Unicode word boundary, the constraints are considered to overlap.
+    pub fn overlaps( &self, other : &SegmentConstraint ) -> bool
+    {
+        match ( self.pattern(), other.pattern() )
+        {
+            ( Some( a ), Some( b ) ) => patterns_overlap( &a, &b ).unwrap_or( true ),
+            _ => true
+        }
+    }
+
+    // The anchored regular expression of the segments that match, `None` if any segment matches
+    fn pattern( &self ) -> Option<String>
+    {
+        match self
+        {
+            SegmentConstraint::Any => None,
+            SegmentConstraint::Type( name, _ ) => get_type_pattern( name ).map( | p | format!( "^(?:{})$", p ) ),
+            SegmentConstraint::Regex( r ) => Some( r.as_str().to_string() )
+        }
+    }
+
+    pub fn is_match( &self, segment : &str ) -> bool
+    {
+        match self
+        {
+            SegmentConstraint::Any => true,
+            SegmentConstraint::Type( _, f ) => f( segment ),
+            SegmentConstraint::Regex( r ) => r.is_match( segment )
+        }
+    }
+}
+
+/// Splits an endpoint url template in segments.
+///
+/// Empty and `.` segments are discarded. Fails if a constraint has a slash, like `{path:[a-z]+/[a-z]+}`,
+/// because request paths are split in segments before matching them, so no request could reach the endpoint.
+pub fn get_route_segments( url : &str ) -> Result<Vec<RouteSegment>, Error>
+{
+    let mut parts : Vec<String> = vec![];
+
+    let mut current = String::new();
+
+    let mut depth = 0;
+
+    for c in url.chars()
+    {
+        match c
+        {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            '/' if depth == 0 => {
+                parts.push( std::mem::take( &mut current ) );
+
+                continue;
+            },
+            _ => {}
+        };
+
+        current.push( c );
+    }
+
+    parts.push( current );
+
+    let parts = parts.into_iter().filter( | p | ! p.is_empty() && p != "." ).collect::<Vec<String>>();
+
+    let mut segments = vec![];
+
+    for ( i, part ) in parts.iter().enumerate()
+    {
+        let segment = parse_route_segment( part )?;
+
+        if matches!( segment, RouteSegment::Wildcard( _ ) ) && i != parts.len() - 1
+        {
+            return Err( Error::RegexError( format!( "Wildcard {} must be the last segment of {}", part, url ) ) )
+        }
+
+        segments.push( segment );
+    }
+
+    Ok( segments )
+}
+
+fn parse_route_segment( segment : &str ) -> Result<RouteSegment, Error>
+{
+    if segment.len() < 3 || ! segment.starts_with( '{' ) || ! segment.ends_with( '}' )
+    {
+        return Ok( RouteSegment::Static( percent_decode_str( segment ).decode_utf8_lossy().into_owned() ) )
+    }
+
+    let inner = &segment[ 1..( segment.len() - 1 ) ];
+
+    if let Some( name ) = inner.strip_prefix( '*' )
+    {
+        return Ok( RouteSegment::Wildcard( name.trim().to_string() ) )
+    }
+
+    match inner.split_once( ':' )
+    {
+        Some( _ ) if inner.contains( '/' ) => Err( Error::RegexError( format!( "The constraint of {} can't have a slash, it matches a single segment", segment ) ) ),
+        Some( ( name, constraint ) ) => Ok( RouteSegment::Variable( name.trim().to_string(), SegmentConstraint::parse( constraint.trim() )? ) ),
+        _ => Ok( RouteSegment::Variable( inner.trim().to_string(), SegmentConstraint::Any ) )
+    }
+}
+
+fn get_type_constraint( constraint : &str ) -> Option<fn( &str ) -> bool>
+{
+    match constraint
+    {
+        "int" | "i64" => Some( is_type::<i64> ),
+        "uint" | "u64" => Some( is_type::<u64> ),
+        "i8" => Some( is_type::<i8> ),
+        "i16" => Some( is_type::<i16> ),
+        "i32" => Some( is_type::<i32> ),
+        "i128" => Some( is_type::<i128> ),
+        "isize" => Some( is_type::<isize> ),
+        "u8" => Some( is_type::<u8> ),
+        "u16" => Some( is_type::<u16> ),
+        "u32" => Some( is_type::<u32> ),
+        "u128" => Some( is_type::<u128> ),
+        "usize" => Some( is_type::<usize> ),
+        "f32" => Some( is_type::<f32> ),
+        "f64" => Some( is_type::<f64> ),
+        "bool" => Some( is_type::<bool> ),
+        _ => None
+    }
+}
+
+// Regular expressions of the text accepted by `FromStr`, ignoring the ranges of the numbers
+fn get_type_pattern( constraint : &str ) -> Option<&'static str>
+{
+    match constraint
+    {
+        "int" | "i64" | "i8" | "i16" | "i32" | "i128" | "isize" => Some( r"[+-]?[0-9]+" ),
+        "uint" | "u64" | "u8" | "u16" | "u32" | "u128" | "usize" => Some( r"\+?[0-9]+" ),
+        "f32" | "f64" => Some( r"(?i)[+-]?(?:[0-9]+\.?[0-9]*|\.[0-9]+)(?:e[+-]?[0-9]+)?|[+-]?(?:inf|infinity|nan)" ),
+        "bool" => Some( "true|false" ),
+        _ => None
+    }
+}
+
+// Walks both automata at the same time, looking for a text that reaches a match state in both
+fn patterns_overlap( a : &str, b : &str ) -> Result<bool, Box<dyn std::error::Error>>
+{
+    let ( a, b ) = ( DFA::new( a )?, DFA::new( b )? );
+
+    let config = Config::new().anchored( Anchored::Yes );
+
+    let start = ( a.start_state( &config )?, b.start_state( &config )? );
+
+    let mut visited = HashSet::from( [ start ] );
+
+    let mut pending = vec![ start ];
+
+    while let Some( ( state_a, state_b ) ) = pending.pop()
+    {
+        if a.is_match_state( a.next_eoi_state( state_a ) ) && b.is_match_state( b.next_eoi_state( state_b ) )
+        {
+            return Ok( true )
+        }
+
+        for byte in 0..=u8::MAX
+        {
+            let next = ( a.next_state( state_a, byte ), b.next_state( state_b, byte ) );
+
+            if a.is_dead_state( next.0 ) || b.is_dead_state( next.1 ) || ! visited.insert( next )
+            {
+                continue
+            }
+
+            pending.push( next );
+        }
+    }
+
+    Ok( false )
+}
+
+fn is_type<T: FromStr>( segment : &str ) -> bool
+{
+    segment.parse::<T>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_route_segments()
+    {
+        let segments = get_route_segments( "/files/{id:u64}/{code:[A-Z]{3}}/{*rest}" );
+
+        assert!( segments.is_ok() );
+
+        let segments = segments.unwrap();
+
+        assert_eq!( segments.len(), 4 );
+
+        assert!( matches!( &segments[ 0 ], RouteSegment::Static( s ) if s == "files" ) );
+        assert!( matches!( &segments[ 1 ], RouteSegment::Variable( n, c ) if n == "id" && c.key() == "u64" ) );
+        assert!( matches!( &segments[ 2 ], RouteSegment::Variable( n, c ) if n == "code" && c.is_match( "ABC" ) && ! c.is_match( "ABCD" ) ) );
+        assert!( matches!( &segments[ 3 ], RouteSegment::Wildcard( n ) if n == "rest" ) );
+    }
+
+    #[test]
+    fn test_get_route_segments_errors()
+    {
+        assert!( get_route_segments( "/files/{*rest}/other" ).is_err() );
+
+        assert!( get_route_segments( "/files/{id:[a-z}" ).is_err() );
+
+        assert!( get_route_segments( "/files/{re:[a-z]+/[a-z]+}" ).is_err() );
+    }
+
+    #[test]
+    fn test_type_constraint()
+    {
+        let constraint = SegmentConstraint::parse( "int" ).unwrap();
+
+        assert!( constraint.is_match( "-33" ) );
+        assert!( ! constraint.is_match( "3.3" ) );
+
+        let constraint = SegmentConstraint::parse( "u8" ).unwrap();
+
+        assert!( constraint.is_match( "255" ) );
+        assert!( ! constraint.is_match( "256" ) );
+    }
+
+    #[test]
+    fn test_constraint_overlaps()
+    {
+        let overlaps = | a : &str, b : &str | SegmentConstraint::parse( a ).unwrap().overlaps( &SegmentConstraint::parse( b ).unwrap() );
+
+        assert!( overlaps( "[0-9]+", "u64" ) );
+        assert!( overlaps( "i32", "u8" ) );
+        assert!( overlaps( "[a-z]+", "f64" ) );
+        assert!( overlaps( "[a-c]+", "[b-d]+" ) );
+        assert!( overlaps( r"\bx", "u64" ) );
+
+        assert!( ! overlaps( "[a-z]+", "u64" ) );
+        assert!( ! overlaps( "u64", "bool" ) );
+        assert!( ! overlaps( "[a-z]{6,}", "bool" ) );
+        assert!( ! overlaps( "a[0-9]", "[0-9]a" ) );
+    }
+}
@@ -0,0 +1,739 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{endpoint::{endpoint::Endpoint, types::EndpointReturnType}, io::io::IO, services::{error::Error, middleware::middleware::MiddlewareChain}, util::url_utils::get_url_segments};
+
+use super::route_segment::{get_route_segments, RouteSegment, SegmentConstraint};
+
+pub type EndpointFn = fn( IO ) -> EndpointReturnType;
+
+/// Result of looking up a request in the `Router`.
+pub enum RouteMatch<'a>
+{
+    /// An endpoint matches both the path and the method.
+    ///
+    /// Contains the endpoint, its path variables, percent-decoded and keyed by name,
+    /// the middlewares attached to it and the options of its responses.
+    Found( EndpointFn, HashMap<String, String>, &'a MiddlewareChain, ResponseOptions ),
+
+    /// The path exists, but not for the requested method.
+    ///
+    /// Contains the methods allowed for the path, sorted and including `HEAD` and `OPTIONS`.
+    MethodNotAllowed( Vec<String> ),
+
+    /// No endpoint is registered for the path.
+    NotFound
+}
+
+/// Prefix tree with every registered `Endpoint`, keyed by path segments.
+///
+/// Each node stores its static children by name, its `{variable}` children by constraint,
+/// an optional `{*wildcard}` child and the endpoints registered for that path, keyed by HTTP method.
+/// Static segments of case insensitive endpoints are stored lowercased in a separate map.
+///
+/// For each segment of a request, static children are tried first, then constrained variables
+/// (sorted by constraint), then the unconstrained variable and finally the wildcard.
+/// If a candidate does not lead to an endpoint, the next one is tried, so the endpoint found
+/// for a request does not depend on the order in which endpoints were registered.
+#[derive(Default)]
+pub struct Router
+{
+    root : RouteNode,
+    routes : Vec<RouteSource>
+}
+
+/// How the body of a request is read, set by the endpoint that handles it.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyOptions
+{
+    /// The endpoint reads the body with `#[body_stream]`.
+    pub stream : bool,
+    /// The `max_body` of the endpoint, in bytes.
+    pub max_body : Option<usize>
+}
+
+/// A request looked up in the `Router` before its body is read, so the endpoint is only searched once.
+pub struct ResolvedRoute
+{
+    /// The path the route was looked up for.
+    pub path : String,
+    /// The method the route was looked up for.
+    pub method : String,
+    /// The result of the lookup.
+    pub route : RouteMatch<'static>,
+    /// How the endpoint found reads the body. The defaults if no endpoint is found.
+    pub body_options : BodyOptions
+}
+
+/// How the responses of an endpoint are written, set with `produces` and `xml_root`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseOptions
+{
+    /// The types the endpoint can respond with, in order of preference. Empty if not set.
+    pub produces : &'static [&'static str],
+    /// The request fails with 406 Not Acceptable if none of `produces` is acceptable.
+    pub not_acceptable : bool,
+    /// The name of the root element of XML responses. `None` uses the default one.
+    pub xml_root : Option<&'static str>
+}
+
+#[derive(Default)]
+struct RouteNode
+{
+    statics : HashMap<String, RouteNode>,
+    statics_ignore_case : HashMap<String, RouteNode>,
+    variables : Vec<( SegmentConstraint, RouteNode )>,
+    wildcard : Option<Box<RouteNode>>,
+    endpoints : HashMap<String, RouteEndpoint>
+}
+
+// The url of a registered endpoint, to find the ones that match the same requests
+struct RouteSource
+{
+    segments : Vec<RouteSegment>,
+    case_insensitive : bool,
+    method : String,
+    url : &'static str,
+    name : &'static str,
+    file : &'static str,
+    line : u32
+}
+
+struct RouteEndpoint
+{
+    fnc : EndpointFn,
+    variables : Vec<String>,
+    middlewares : MiddlewareChain,
+    body_options : BodyOptions,
+    response_options : ResponseOptions
+}
+
+impl Router
+{
+    pub fn from_endpoints<'a>( endpoints : impl Iterator<Item = &'a Endpoint> ) -> Result<Self, Error>
+    {
+        let mut router = Self::default();
+
+        for endpoint in endpoints
+        {
+            router.insert( endpoint )?;
+        }
+
+        Ok( router )
+    }
+
+    /// Adds an endpoint to the tree.
+    ///
+    /// Returns an error if the url has an invalid constraint or a wildcard that is not the last segment,
+    /// if an attached middleware has an invalid `urls` pattern, or if another endpoint with the same method can match the same requests
+    /// without one of them taking precedence. That happens when, segment by segment, the urls have:
+    ///
+    /// - The same static segment, ignoring case if one of the endpoints is case insensitive, like `/user` and `/User`.
+    /// - Variables without constraint, or with constraints that can match the same value, like `{id:[0-9]+}` and `{id:u64}`.
+    /// - Wildcards.
+    ///
+    /// A static segment takes precedence over a variable, a constrained variable over an unconstrained one,
+    /// and any of them over a wildcard, so those urls don't conflict.
+    pub fn insert( &mut self, endpoint : &Endpoint ) -> Result<(), Error>
+    {
+        let source = RouteSource {
+            segments : get_route_segments( endpoint.url )?,
+            case_insensitive : endpoint.case_insensitive,
+            method : get_method_key( endpoint.method ),
+            url : endpoint.url,
+            name : endpoint.name,
+            file : endpoint.file,
+            line : endpoint.line
+        };
+
+        if let Some( other ) = self.routes.iter().find( | r | r.overlaps( &source ) )
+        {
+            return Err( Error::RouteConflict( format!(
+                "Route conflict: {} {} ({}) conflicts with {} {} ({})",
+                source.method, source.url, get_source( source.name, source.file, source.line ),
+                other.method, other.url, get_source( other.name, other.file, other.line )
+            ) ) )
+        }
+
+        let mut node = &mut self.root;
+
+        let mut variables = vec![];
+
+        for segment in source.segments.iter().cloned()
+        {
+            node = match segment
+            {
+                RouteSegment::Static( s ) if endpoint.case_insensitive => node.statics_ignore_case.entry( s.to_lowercase() ).or_default(),
+                RouteSegment::Static( s ) => node.statics.entry( s ).or_default(),
+                RouteSegment::Variable( name, constraint ) => {
+
+                    variables.push( name );
+
+                    node.variable_child( constraint )
+                },
+                RouteSegment::Wildcard( name ) => {
+
+                    variables.push( name );
+
+                    node.wildcard.get_or_insert_with( Box::default )
+                }
+            };
+        }
+
+        node.endpoints.insert( source.method.clone(), RouteEndpoint {
+            fnc : endpoint.fnc,
+            variables,
+            middlewares : MiddlewareChain::new( endpoint.middlewares.to_vec() )?,
+            body_options : BodyOptions { stream : endpoint.body_stream, max_body : endpoint.max_body },
+            response_options : ResponseOptions { produces : endpoint.produces, not_acceptable : endpoint.not_acceptable, xml_root : endpoint.xml_root }
+        } );
+
+        self.routes.push( source );
+
+        Ok( () )
+    }
+
+    /// Looks up a request once, before its body is read, with the options to read it.
+    pub fn resolve( &'static self, url : &str, method : &str ) -> ResolvedRoute
+    {
+        let ( route, body_options ) = self.lookup( url, method );
+
+        ResolvedRoute { path : url.to_string(), method : method.to_string(), route, body_options }
+    }
+
+    /// Finds the endpoint for a request.
+    ///
+    /// A `HEAD` request is served by the `GET` endpoint of the path if there is no explicit `HEAD` endpoint.
+    /// A path with an encoded segment that could change it, like `..%2F`, is not found.
+    pub fn find( &self, url : &str, method : &str ) -> RouteMatch<'_>
+    {
+        self.lookup( url, method ).0
+    }
+
+    fn lookup( &self, url : &str, method : &str ) -> ( RouteMatch<'_>, BodyOptions )
+    {
+        let Some( segments ) = get_url_segments( url ) else
+        {
+            return ( RouteMatch::NotFound, BodyOptions::default() )
+        };
+
+        let segments = segments.iter().map( | s | s.as_str() ).collect::<Vec<&str>>();
+
+        let mut values = vec![];
+
+        if let Some( endpoint ) = self.root.find( &segments, &get_method_key( method ), &mut values )
+        {
+            let variables = endpoint.variables.iter().cloned().zip( values ).collect();
+
+            return ( RouteMatch::Found( endpoint.fnc, variables, &endpoint.middlewares, endpoint.response_options ), endpoint.body_options )
+        }
+
+        let mut allowed = BTreeSet::new();
+
+        self.root.allowed_methods( &segments, &mut allowed );
+
+        if allowed.is_empty()
+        {
+            return ( RouteMatch::NotFound, BodyOptions::default() )
+        }
+
+        if allowed.contains( "GET" )
+        {
+            allowed.insert( "HEAD".to_string() );
+        }
+
+        allowed.insert( "OPTIONS".to_string() );
+
+        ( RouteMatch::MethodNotAllowed( allowed.into_iter().collect() ), BodyOptions::default() )
+    }
+}
+
+impl RouteNode
+{
+    // Returns the child for a variable segment, creating it if needed.
+    // Constrained children are kept sorted by constraint, before the unconstrained one.
+    fn variable_child( &mut self, constraint : SegmentConstraint ) -> &mut RouteNode
+    {
+        let key = constraint.key().to_string();
+
+        if ! self.variables.iter().any( | v | v.0.key() == key )
+        {
+            self.variables.push( ( constraint, RouteNode::default() ) );
+
+            self.variables.sort_by( | a, b | ( a.0.key().is_empty(), a.0.key() ).cmp( &( b.0.key().is_empty(), b.0.key() ) ) );
+        }
+
+        let pos = self.variables.iter().position( | v | v.0.key() == key ).unwrap_or_default();
+
+        &mut self.variables[ pos ].1
+    }
+
+    fn find( &self, segments : &[&str], method : &str, values : &mut Vec<String> ) -> Option<&RouteEndpoint>
+    {
+        if segments.is_empty()
+        {
+            return match self.endpoints.get( method )
+            {
+                Some( e ) => Some( e ),
+                _ if method == "HEAD" => self.endpoints.get( "GET" ),
+                _ => None
+            }
+        }
+
+        let ( segment, rest ) = ( segments[ 0 ], &segments[ 1.. ] );
+
+        for node in self.static_children( segment )
+        {
+            if let Some( e ) = node.find( rest, method, values )
+            {
+                return Some( e )
+            }
+        }
+
+        for ( constraint, node ) in &self.variables
+        {
+            if ! constraint.is_match( segment )
+            {
+                continue;
+            }
+
+            values.push( segment.to_string() );
+
+            if let Some( e ) = node.find( rest, method, values )
+            {
+                return Some( e )
+            }
+
+            values.pop();
+        }
+
+        if let Some( node ) = &self.wildcard
+        {
+            values.push( segments.join( "/" ) );
+
+            if let Some( e ) = node.find( &[], method, values )
+            {
+                return Some( e )
+            }
+
+            values.pop();
+        }
+
+        None
+    }
+
+    fn allowed_methods( &self, segments : &[&str], allowed : &mut BTreeSet<String> )
+    {
+        if segments.is_empty()
+        {
+            allowed.extend( self.endpoints.keys().cloned() );
+
+            return
+        }
+
+        let ( segment, rest ) = ( segments[ 0 ], &segments[ 1.. ] );
+
+        for node in self.static_children( segment )
+        {
+            node.allowed_methods( rest, allowed );
+        }
+
+        for ( constraint, node ) in &self.variables
+        {
+            if constraint.is_match( segment )
+            {
+                node.allowed_methods( rest, allowed );
+            }
+        }
+
+        if let Some( node ) = &self.wildcard
+        {
+            node.allowed_methods( &[], allowed );
+        }
+    }
+
+    // Static children matching a segment. Case sensitive matches go first.
+    fn static_children( &self, segment : &str ) -> impl Iterator<Item = &RouteNode>
+    {
+        let ignore_case = if self.statics_ignore_case.is_empty()
+        {
+            None
+        }
+        else
+        {
+            self.statics_ignore_case.get( &segment.to_lowercase() )
+        };
+
+        self.statics.get( segment ).into_iter().chain( ignore_case )
+    }
+}
+
+impl RouteSource
+{
+    // Both urls match the same requests with the same method, and none of them takes precedence
+    fn overlaps( &self, other : &RouteSource ) -> bool
+    {
+        let case_insensitive = self.case_insensitive || other.case_insensitive;
+
+        self.method == other.method &&
+        self.segments.len() == other.segments.len() &&
+        self.segments.iter().zip( &other.segments ).all( | ( a, b ) | match ( a, b )
+        {
+            ( RouteSegment::Static( a ), RouteSegment::Static( b ) ) if case_insensitive => a.to_lowercase() == b.to_lowercase(),
+            ( RouteSegment::Static( a ), RouteSegment::Static( b ) ) => a == b,
+            ( RouteSegment::Variable( _, SegmentConstraint::Any ), RouteSegment::Variable( _, SegmentConstraint::Any ) ) => true,
+            ( RouteSegment::Variable( _, SegmentConstraint::Any ), _ ) |
+            ( _, RouteSegment::Variable( _, SegmentConstraint::Any ) ) => false,
+            ( RouteSegment::Variable( _, a ), RouteSegment::Variable( _, b ) ) => a.key() == b.key() || a.overlaps( b ),
+            ( RouteSegment::Wildcard( _ ), RouteSegment::Wildcard( _ ) ) => true,
+            _ => false
+        } )
+    }
+}
+
+fn get_method_key( method : &str ) -> String
+{
+    method.trim().to_uppercase()
+}
+
+fn get_source( name : &str, file : &str, line : u32 ) -> String
+{
+    format!( "{} at {}:{}", name, file, line )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::response::response_data::ResponseData;
+
+    use super::*;
+
+    fn endpoint_static( mut io : IO ) -> EndpointReturnType
+    {
+        Box::pin( async move { io.response.status = 1; Ok( io ) } )
+    }
+
+    fn endpoint_variable( mut io : IO ) -> EndpointReturnType
+    {
+        Box::pin( async move { io.response.status = 2; Ok( io ) } )
+    }
+
+    fn endpoint_post( mut io : IO ) -> EndpointReturnType
+    {
+        Box::pin( async move { io.response.status = 3; Ok( io ) } )
+    }
+
+    async fn find_status( router : &Router, url : &str, method : &str ) -> Option<u16>
+    {
+        match router.find( url, method )
+        {
+            RouteMatch::Found( f, _, _, _ ) => Some( f( IO::with_response( ResponseData::default() ) ).await.ok()?.response.status ),
+            _ => None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_static_segment_wins_over_variable()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/{id}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/me", "get", endpoint_static, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        assert_eq!( find_status( &router, "/user/me", "GET" ).await, Some( 1 ) );
+        assert_eq!( find_status( &router, "/user/33", "GET" ).await, Some( 2 ) );
+
+        let router = Router::from_endpoints( endpoints.iter().rev() ).unwrap();
+
+        assert_eq!( find_status( &router, "/user/me", "GET" ).await, Some( 1 ) );
+        assert_eq!( find_status( &router, "/user/33", "GET" ).await, Some( 2 ) );
+    }
+
+    #[tokio::test]
+    async fn test_backtrack_to_variable_segment()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/me", "get", endpoint_static, false ),
+            Endpoint::new( "/user/{id}", "post", endpoint_post, false ),
+            Endpoint::new( "/{section}/me/detail", "get", endpoint_variable, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        assert_eq!( find_status( &router, "/user/me", "post" ).await, Some( 3 ) );
+        assert_eq!( find_status( &router, "/user/me/detail", "get" ).await, Some( 2 ) );
+        assert_eq!( find_status( &router, "/user/me", "put" ).await, None );
+    }
+
+    #[tokio::test]
+    async fn test_not_found()
+    {
+        let endpoints = [
+            Endpoint::new( "/", "get", endpoint_static, false ),
+            Endpoint::new( "/user/{id}", "get", endpoint_variable, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        assert_eq!( find_status( &router, "/", "get" ).await, Some( 1 ) );
+        assert_eq!( find_status( &router, "/user/", "get" ).await, None );
+        assert_eq!( find_status( &router, "/user//", "get" ).await, None );
+        assert_eq!( find_status( &router, "/user/33/44", "get" ).await, None );
+        assert_eq!( find_status( &router, "/other", "get" ).await, None );
+    }
+
+    #[tokio::test]
+    async fn test_head_served_by_get()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/{id}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/me", "head", endpoint_static, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        assert_eq!( find_status( &router, "/user/33", "HEAD" ).await, Some( 2 ) );
+        assert_eq!( find_status( &router, "/user/me", "HEAD" ).await, Some( 1 ) );
+    }
+
+    #[test]
+    fn test_method_not_allowed()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/me", "get", endpoint_static, false ),
+            Endpoint::new( "/user/{id}", "post", endpoint_post, false ),
+            Endpoint::new( "/user/{id}", "delete", endpoint_post, false ),
+            Endpoint::new( "/other", "put", endpoint_post, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        match router.find( "/user/me", "PUT" )
+        {
+            RouteMatch::MethodNotAllowed( v ) => assert_eq!( v, vec![ "DELETE", "GET", "HEAD", "OPTIONS", "POST" ] ),
+            _ => panic!( "Expected MethodNotAllowed" )
+        };
+
+        match router.find( "/other", "GET" )
+        {
+            RouteMatch::MethodNotAllowed( v ) => assert_eq!( v, vec![ "OPTIONS", "PUT" ] ),
+            _ => panic!( "Expected MethodNotAllowed" )
+        };
+
+        assert!( matches!( router.find( "/not_found", "GET" ), RouteMatch::NotFound ) );
+    }
+
+    #[tokio::test]
+    async fn test_case_sensitive()
+    {
+        let endpoints = [
+            Endpoint::new( "/files/{name}", "get", endpoint_variable, false ),
+            Endpoint::new( "/Files/Static", "get", endpoint_static, false ),
+            Endpoint::new( "/Insensitive/{name}/Static", "get", endpoint_post, true )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        assert_eq!( find_status( &router, "/Files/Static", "get" ).await, Some( 1 ) );
+        assert_eq!( find_status( &router, "/files/Static", "get" ).await, Some( 2 ) );
+        assert_eq!( find_status( &router, "/FILES/static", "get" ).await, None );
+        assert_eq!( find_status( &router, "/insensitive/Name/STATIC", "get" ).await, Some( 3 ) );
+    }
+
+    #[test]
+    fn test_path_variables()
+    {
+        let endpoints = [
+            Endpoint::new( "/files/{name}", "get", endpoint_variable, false ),
+            Endpoint::new( "/files/{dir}/{name}", "get", endpoint_variable, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        match router.find( "/files/Report%20Final.PDF", "GET" )
+        {
+            RouteMatch::Found( _, v, _, _ ) => assert_eq!( v.get( "name" ).unwrap(), "Report Final.PDF" ),
+            _ => panic!( "Expected Found" )
+        };
+
+        match router.find( "/files/a/../b/./%C3%B1", "GET" )
+        {
+            RouteMatch::Found( _, v, _, _ ) => {
+                assert_eq!( v.get( "dir" ).unwrap(), "b" );
+                assert_eq!( v.get( "name" ).unwrap(), "ñ" );
+            },
+            _ => panic!( "Expected Found" )
+        };
+    }
+
+    #[tokio::test]
+    async fn test_constrained_segments()
+    {
+        let endpoints = [
+            Endpoint::new( "/item/{slug}", "get", endpoint_static, false ),
+            Endpoint::new( "/item/{id:u64}", "get", endpoint_variable, false ),
+            Endpoint::new( "/item/{code:[A-Z]{3}}", "get", endpoint_post, false ),
+            Endpoint::new( "/item/{code:[A-Z]{3}}/detail", "get", endpoint_post, false ),
+            Endpoint::new( "/item/{id:u64}/other", "get", endpoint_variable, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        assert_eq!( find_status( &router, "/item/33", "get" ).await, Some( 2 ) );
+        assert_eq!( find_status( &router, "/item/ABC", "get" ).await, Some( 3 ) );
+        assert_eq!( find_status( &router, "/item/ABCD", "get" ).await, Some( 1 ) );
+        assert_eq!( find_status( &router, "/item/-33", "get" ).await, Some( 1 ) );
+        assert_eq!( find_status( &router, "/item/ABC/detail", "get" ).await, Some( 3 ) );
+        assert_eq!( find_status( &router, "/item/33/detail", "get" ).await, None );
+        assert_eq!( find_status( &router, "/item/33/other", "get" ).await, Some( 2 ) );
+
+        assert!( matches!( router.find( "/item/ABC/other", "GET" ), RouteMatch::NotFound ) );
+    }
+
+    #[test]
+    fn test_wildcard_segment()
+    {
+        let endpoints = [
+            Endpoint::new( "/static/{*path}", "get", endpoint_variable, false ),
+            Endpoint::new( "/static/index.html", "get", endpoint_static, false )
+        ];
+
+        let router = Router::from_endpoints( endpoints.iter() ).unwrap();
+
+        match router.find( "/static/css/main%20file.css", "GET" )
+        {
+            RouteMatch::Found( _, v, _, _ ) => assert_eq!( v.get( "path" ).unwrap(), "css/main file.css" ),
+            _ => panic!( "Expected Found" )
+        };
+
+        assert!( matches!( router.find( "/static/index.html", "GET" ), RouteMatch::Found( _, v, _, _ ) if v.is_empty() ) );
+        assert!( matches!( router.find( "/static", "GET" ), RouteMatch::NotFound ) );
+        assert!( matches!( router.find( "/static/a", "POST" ), RouteMatch::MethodNotAllowed( _ ) ) );
+    }
+
+    #[test]
+    fn test_route_conflict()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/{id}", "get", endpoint_variable, false ).with_source( "users::get_by_id", "src/users.rs", 10 ),
+            Endpoint::new( "/user/{name}/", "GET", endpoint_static, false ).with_source( "users::get_by_name", "src/users.rs", 20 )
+        ];
+
+        match Router::from_endpoints( endpoints.iter() )
+        {
+            Err( Error::RouteConflict( e ) ) => {
+                assert!( e.contains( "users::get_by_id at src/users.rs:10" ) );
+                assert!( e.contains( "users::get_by_name at src/users.rs:20" ) );
+            },
+            _ => panic!( "Expected RouteConflict" )
+        };
+
+        let endpoints = [
+            Endpoint::new( "/user/{id}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/{id}", "post", endpoint_post, false ),
+            Endpoint::new( "/user/{id:u64}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/{name:[a-z]+}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/{id:u64}/{*rest}", "get", endpoint_variable, false ),
+            Endpoint::new( "/User/me", "get", endpoint_static, true )
+        ];
+
+        assert!( Router::from_endpoints( endpoints.iter() ).is_ok() );
+    }
+
+    #[test]
+    fn test_route_conflict_case_insensitive()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/{id}", "get", endpoint_variable, false ).with_source( "users::get", "src/users.rs", 10 ),
+            Endpoint::new( "/User/{id}", "get", endpoint_static, true ).with_source( "users::get_any_case", "src/users.rs", 20 )
+        ];
+
+        match Router::from_endpoints( endpoints.iter() )
+        {
+            Err( Error::RouteConflict( e ) ) => {
+                assert!( e.contains( "users::get at src/users.rs:10" ) );
+                assert!( e.contains( "users::get_any_case at src/users.rs:20" ) );
+            },
+            _ => panic!( "Expected RouteConflict" )
+        };
+
+        let endpoints = [
+            Endpoint::new( "/USER/{id}", "get", endpoint_static, true ),
+            Endpoint::new( "/user/{id}", "get", endpoint_variable, true )
+        ];
+
+        assert!( matches!( Router::from_endpoints( endpoints.iter() ), Err( Error::RouteConflict( _ ) ) ) );
+    }
+
+    #[test]
+    fn test_route_conflict_constraints()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/{a:[0-9]+}", "get", endpoint_variable, false ).with_source( "users::get_by_digits", "src/users.rs", 10 ),
+            Endpoint::new( "/user/{b:u64}", "get", endpoint_static, false ).with_source( "users::get_by_id", "src/users.rs", 20 )
+        ];
+
+        match Router::from_endpoints( endpoints.iter() )
+        {
+            Err( Error::RouteConflict( e ) ) => {
+                assert!( e.contains( "users::get_by_digits at src/users.rs:10" ) );
+                assert!( e.contains( "users::get_by_id at src/users.rs:20" ) );
+            },
+            _ => panic!( "Expected RouteConflict" )
+        };
+
+        let endpoints = [
+            Endpoint::new( "/user/{id:i32}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/{id:u8}", "get", endpoint_static, false )
+        ];
+
+        assert!( matches!( Router::from_endpoints( endpoints.iter() ), Err( Error::RouteConflict( _ ) ) ) );
+
+        let endpoints = [
+            Endpoint::new( "/user/{id:f64}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/{name:[a-z]+}", "get", endpoint_static, false )
+        ];
+
+        assert!( matches!( Router::from_endpoints( endpoints.iter() ), Err( Error::RouteConflict( _ ) ) ) );
+
+        let endpoints = [
+            Endpoint::new( "/user/{id:u64}", "get", endpoint_variable, false ),
+            Endpoint::new( "/user/{active:bool}", "get", endpoint_static, false ),
+            Endpoint::new( "/user/{name:[a-z]{6,}}", "get", endpoint_post, false )
+        ];
+
+        assert!( Router::from_endpoints( endpoints.iter() ).is_ok() );
+    }
+
+    #[test]
+    fn test_invalid_url()
+    {
+        let endpoints = [
+            Endpoint::new( "/static/{*path}/other", "get", endpoint_variable, false )
+        ];
+
+        assert!( Router::from_endpoints( endpoints.iter() ).is_err() );
+    }
+
+    #[test]
+    fn test_body_options()
+    {
+        let endpoints = [
+            Endpoint::new( "/upload/{id}", "post", endpoint_post, false ).with_body_stream(),
+            Endpoint::new( "/upload/{id}", "put", endpoint_post, false ).with_max_body( 1024 ),
+            Endpoint::new( "/upload/{id}", "get", endpoint_variable, false )
+        ];
+
+        let router : &'static Router = Box::leak( Box::new( Router::from_endpoints( endpoints.iter() ).unwrap() ) );
+
+        assert_eq!( router.resolve( "/upload/1", "POST" ).body_options, BodyOptions { stream : true, max_body : None } );
+        assert_eq!( router.resolve( "/upload/1", "PUT" ).body_options, BodyOptions { stream : false, max_body : Some( 1024 ) } );
+        assert_eq!( router.resolve( "/upload/1", "GET" ).body_options, BodyOptions::default() );
+        assert_eq!( router.resolve( "/other", "POST" ).body_options, BodyOptions::default() );
+
+        let resolved = router.resolve( "/upload/2", "PUT" );
+
+        assert_eq!( ( resolved.path.as_str(), resolved.method.as_str() ), ( "/upload/2", "PUT" ) );
+        assert!( matches!( resolved.route, RouteMatch::Found( _, v, _, _ ) if v.get( "id" ).is_some_and( | id | id == "2" ) ) );
+    }
+}
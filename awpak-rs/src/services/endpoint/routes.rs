@@ -0,0 +1,76 @@
+use crate::endpoint::endpoint::Endpoint;
+
+/// An entry of the route table returned by `awpak_rs::routes()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route
+{
+    /// HTTP method, uppercase.
+    pub method : String,
+    /// Url template as declared in the endpoint macro, like `/user/{id:u64}`.
+    pub url : &'static str,
+    /// Path of the handler function, like `my_crate::users::get_user`.
+    pub handler : &'static str,
+    /// Source file where the handler is declared.
+    pub file : &'static str,
+    /// Line of the source file where the handler is declared.
+    pub line : u32
+}
+
+/// Returns every registered endpoint, sorted by url and method.
+///
+/// Automatic `HEAD` and `OPTIONS` responses are not included, only the declared endpoints.
+///
+/// # Example
+///
+/// ```ignore
+/// for route in awpak_rs::routes() {
+///     println!("{} {} -> {} ({}:{})", route.method, route.url, route.handler, route.file, route.line);
+/// }
+/// ```
+pub fn routes() -> Vec<Route>
+{
+    get_routes( inventory::iter::<Endpoint>.into_iter() )
+}
+
+fn get_routes<'a>( endpoints : impl Iterator<Item = &'a Endpoint> ) -> Vec<Route>
+{
+    let mut routes = endpoints.map( | e | Route {
+        method : e.method.trim().to_uppercase(),
+        url : e.url,
+        handler : e.name,
+        file : e.file,
+        line : e.line
+    } ).collect::<Vec<Route>>();
+
+    routes.sort_by( | a, b | ( a.url, &a.method ).cmp( &( b.url, &b.method ) ) );
+
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{endpoint::types::EndpointReturnType, io::io::IO};
+
+    use super::*;
+
+    fn endpoint( io : IO ) -> EndpointReturnType
+    {
+        Box::pin( async move { Ok( io ) } )
+    }
+
+    #[test]
+    fn test_get_routes()
+    {
+        let endpoints = [
+            Endpoint::new( "/user/{id}", "post", endpoint, false ).with_source( "users::post_user", "src/users.rs", 20 ),
+            Endpoint::new( "/user/{id}", "get", endpoint, false ).with_source( "users::get_user", "src/users.rs", 10 ),
+            Endpoint::new( "/", "get", endpoint, false )
+        ];
+
+        let routes = get_routes( endpoints.iter() );
+
+        assert_eq!( routes.iter().map( | r | ( r.method.as_str(), r.url ) ).collect::<Vec<_>>(), vec![ ( "GET", "/" ), ( "GET", "/user/{id}" ), ( "POST", "/user/{id}" ) ] );
+
+        assert_eq!( routes[ 1 ], Route { method : "GET".to_string(), url : "/user/{id}", handler : "users::get_user", file : "src/users.rs", line : 10 } );
+    }
+}
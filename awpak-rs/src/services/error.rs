@@ -1,11 +1,92 @@
+use serde_json::Value;
 
-#[derive(Debug)]
+use crate::io::headers::headers::Headers;
+
+/// Where an endpoint parameter is extracted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionSource
+{
+    /// `#[query_param]` and `#[query_params]`.
+    Query,
+    /// `#[body_param]` and `#[request_body]`.
+    Body,
+    /// `#[path_variable]`.
+    Path,
+    /// `#[part_file]` and `#[part_files]`.
+    File,
+    /// The headers of a `#[websocket]` handshake.
+    Header
+}
+
+impl std::fmt::Display for ExtractionSource
+{
+    fn fmt( &self, f : &mut std::fmt::Formatter<'_> ) -> std::fmt::Result
+    {
+        match self
+        {
+            ExtractionSource::Query => write!( f, "Query param" ),
+            ExtractionSource::Body => write!( f, "Body param" ),
+            ExtractionSource::Path => write!( f, "Path variable" ),
+            ExtractionSource::File => write!( f, "File param" ),
+            ExtractionSource::Header => write!( f, "Header" )
+        }
+    }
+}
+
+/// An endpoint parameter that can't be extracted from the request.
+///
+/// `message` is the error of the conversion, like serde's `invalid digit found in string`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionError
+{
+    pub name : String,
+    pub source : ExtractionSource,
+    pub message : String
+}
+
+impl ExtractionError
+{
+    pub fn new( name : &str, source : ExtractionSource, message : String ) -> Self
+    {
+        Self
+        {
+            name : name.to_string(),
+            source,
+            message
+        }
+    }
+}
+
+impl std::fmt::Display for ExtractionError
+{
+    fn fmt( &self, f : &mut std::fmt::Formatter<'_> ) -> std::fmt::Result
+    {
+        write!( f, "{} error: {}: {}", self.source, self.name, self.message )
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum Error
 {
     EndpointNotFound( String ),
+    /// The path exists but not for the requested method. Contains the value of the `Allow` header.
+    MethodNotAllowed( String ),
     EndpointExecution( String ),
     ParserError( String ),
-    RegexError( String )
+    /// A parameter of the endpoint can't be extracted from the request.
+    /// The status is 404 for path variables and 400 for the rest.
+    BadRequest( ExtractionError ),
+    RegexError( String ),
+    /// Two endpoints match the same requests with the same method. Detected when the router is built.
+    RouteConflict( String ),
+    /// Two error handlers are registered for the same variant, the same status or for every error. Detected at startup.
+    ErrorHandlerConflict( String ),
+    /// A websocket connection failed after the handshake, like a protocol error or a closed connection.
+    WebSocket( String ),
+    /// The request body is larger than the limit of the endpoint. Contains the limit, in bytes.
+    PayloadTooLarge( usize ),
+    /// None of the media types the endpoint `produces` is accepted by the client. Contains the types of the endpoint.
+    NotAcceptable( String )
 }
 
 impl Error
@@ -15,7 +96,59 @@ impl Error
         match self
         {
             Error::EndpointNotFound( _ ) => 404,
+            Error::MethodNotAllowed( _ ) => 405,
+            Error::NotAcceptable( _ ) => 406,
+            Error::PayloadTooLarge( _ ) => 413,
+            Error::BadRequest( e ) if e.source == ExtractionSource::Path => 404,
+            Error::BadRequest( _ ) => 400,
             _ => 500
         }
     }
-}
\ No newline at end of file
+
+    pub fn get_headers( &self ) -> Headers
+    {
+        let mut headers = Headers::new();
+
+        if let Error::MethodNotAllowed( allow ) = self
+        {
+            headers.replace_header( "allow".to_string(), allow.clone() );
+        }
+
+        headers
+    }
+
+    /// Body of the response. The details of server errors are not sent to the client.
+    pub fn get_body( &self ) -> Value
+    {
+        match self
+        {
+            Error::BadRequest( _ ) |
+            Error::NotAcceptable( _ ) |
+            Error::PayloadTooLarge( _ ) => Value::from( self.to_string() ),
+            _ => Value::from( "Server Error" )
+        }
+    }
+}
+
+impl std::fmt::Display for Error
+{
+    fn fmt( &self, f : &mut std::fmt::Formatter<'_> ) -> std::fmt::Result
+    {
+        match self
+        {
+            Error::EndpointNotFound( s ) |
+            Error::MethodNotAllowed( s ) |
+            Error::EndpointExecution( s ) |
+            Error::ParserError( s ) |
+            Error::RegexError( s ) |
+            Error::RouteConflict( s ) |
+            Error::ErrorHandlerConflict( s ) |
+            Error::WebSocket( s ) => write!( f, "{}", s ),
+            Error::BadRequest( e ) => write!( f, "{}", e ),
+            Error::PayloadTooLarge( max ) => write!( f, "Request body too large, the limit is {} bytes", max ),
+            Error::NotAcceptable( produces ) => write!( f, "Not acceptable, the available media types are: {}", produces )
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,184 @@
+use std::sync::OnceLock;
+
+use crate::{io::io::IO, services::error::Error};
+
+pub type ErrorHandlerReturnType = std::pin::Pin<std::boxed::Box<
+                                    dyn std::future::Future<
+                                        Output = IO
+                                    > 
+                                    + std::marker::Send
+                                >>;
+
+/// A function registered with `#[error_handler]` that builds the response of a failed request.
+///
+/// A handler can be registered for an `Error` variant, for a status code, or for every error.
+/// When a request fails, the handler registered for its variant runs. If there is none,
+/// the one registered for its status code, and if there is none, the one registered for every error.
+/// Two handlers can't have the same variant, the same status or both be registered for every error:
+/// `initialize_error_handlers` fails at startup.
+#[derive(Copy, Clone)]
+pub struct ErrorHandler
+{
+    pub status : Option<u16>,
+    /// The name of the `Error` variant matched by `error`.
+    pub variant : Option<&'static str>,
+    pub error : Option<fn( &Error ) -> bool>,
+    pub fnc : fn( IO ) -> ErrorHandlerReturnType
+}
+
+impl ErrorHandler
+{
+    pub const fn new(
+        status : Option<u16>,
+        variant : Option<&'static str>,
+        error : Option<fn( &Error ) -> bool>,
+        fnc : fn( IO ) -> ErrorHandlerReturnType
+    ) -> Self
+    {
+        Self
+        {
+            status,
+            variant,
+            error,
+            fnc
+        }
+    }
+}
+
+inventory::collect!( ErrorHandler );
+
+fn error_handlers() -> &'static Result<Vec<ErrorHandler>, Error> {
+    static ARRAY_ERROR_HANDLERS: OnceLock<Result<Vec<ErrorHandler>, Error>> = OnceLock::new();
+    ARRAY_ERROR_HANDLERS.get_or_init(|| {
+        let handlers : Vec<ErrorHandler> = inventory::iter::<ErrorHandler>.into_iter().copied().collect();
+
+        check_error_handlers( &handlers ).map( | _ | handlers )
+    } )
+}
+
+/// Collects the error handlers.
+///
+/// Returns an error if two handlers have the same variant, the same status or are both registered for every error.
+pub fn initialize_error_handlers() -> Result<(), Error>
+{
+    match error_handlers()
+    {
+        Ok( _ ) => Ok( () ),
+        Err( e ) => Err( e.clone() )
+    }
+}
+
+/// Runs the error handler of the request, if the request failed and there is a handler for its error.
+pub async fn error_handlers_exec( io : IO ) -> IO
+{
+    let handler = match ( &io.error, error_handlers() )
+    {
+        ( Some( e ), Ok( handlers ) ) => find_error_handler( handlers, e ),
+        _ => None
+    };
+
+    match handler
+    {
+        Some( h ) => ( h.fnc )( io ).await,
+        _ => io
+    }
+}
+
+fn find_error_handler<'a>( handlers : &'a [ErrorHandler], error : &Error ) -> Option<&'a ErrorHandler>
+{
+    let status = error.get_status_code();
+
+    handlers.iter().find( | h | h.error.is_some_and( | f | f( error ) ) )
+    .or_else( || handlers.iter().find( | h | h.status == Some( status ) ) )
+    .or_else( || handlers.iter().find( | h | h.error.is_none() && h.status.is_none() ) )
+}
+
+fn check_error_handlers( handlers : &[ErrorHandler] ) -> Result<(), Error>
+{
+    for ( i, handler ) in handlers.iter().enumerate()
+    {
+        if handlers[ ..i ].iter().any( | h | h.variant == handler.variant && h.status == handler.status )
+        {
+            let key = match ( handler.variant, handler.status )
+            {
+                ( Some( v ), _ ) => format!( "the variant {}", v ),
+                ( _, Some( s ) ) => format!( "the status {}", s ),
+                _ => "every error".to_string()
+            };
+
+            return Err( Error::ErrorHandlerConflict( format!( "Several error handlers are registered for {}", key ) ) )
+        }
+    }
+
+    Ok( () )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::response::response_data::ResponseData;
+
+    use super::*;
+
+    fn handler_status( mut io : IO ) -> ErrorHandlerReturnType
+    {
+        Box::pin( async move { io.response.status = 1; io } )
+    }
+
+    fn handler_variant( mut io : IO ) -> ErrorHandlerReturnType
+    {
+        Box::pin( async move { io.response.status = 2; io } )
+    }
+
+    fn handler_default( mut io : IO ) -> ErrorHandlerReturnType
+    {
+        Box::pin( async move { io.response.status = 3; io } )
+    }
+
+    async fn exec_status( handlers : &[ErrorHandler], error : Error ) -> Option<u16>
+    {
+        let handler = find_error_handler( handlers, &error )?;
+
+        Some( ( handler.fnc )( IO::with_response( ResponseData::default() ).with_error( error ) ).await.response.status )
+    }
+
+    #[tokio::test]
+    async fn test_find_error_handler()
+    {
+        let handlers = [
+            ErrorHandler::new( None, None, None, handler_default ),
+            ErrorHandler::new( Some( 404 ), None, None, handler_status ),
+            ErrorHandler::new( None, Some( "ParserError" ), Some( | e | matches!( e, Error::ParserError( _ ) ) ), handler_variant )
+        ];
+
+        assert_eq!( exec_status( &handlers, Error::ParserError( "x".to_string() ) ).await, Some( 2 ) );
+        assert_eq!( exec_status( &handlers, Error::EndpointNotFound( "x".to_string() ) ).await, Some( 1 ) );
+        assert_eq!( exec_status( &handlers, Error::EndpointExecution( "x".to_string() ) ).await, Some( 3 ) );
+
+        assert_eq!( exec_status( &handlers[ 1.. ], Error::EndpointExecution( "x".to_string() ) ).await, None );
+    }
+
+    #[test]
+    fn test_check_error_handlers()
+    {
+        let parser_error = ErrorHandler::new( None, Some( "ParserError" ), Some( | e | matches!( e, Error::ParserError( _ ) ) ), handler_variant );
+
+        let handlers = [
+            ErrorHandler::new( None, None, None, handler_default ),
+            ErrorHandler::new( Some( 404 ), None, None, handler_status ),
+            ErrorHandler::new( Some( 500 ), None, None, handler_status ),
+            parser_error
+        ];
+
+        assert!( check_error_handlers( &handlers ).is_ok() );
+
+        let conflict = | handler : ErrorHandler | match check_error_handlers( &[ handlers.as_slice(), &[ handler ] ].concat() )
+        {
+            Err( e ) => e.to_string(),
+            Ok( _ ) => "".to_string()
+        };
+
+        assert_eq!( conflict( parser_error ), "Several error handlers are registered for the variant ParserError" );
+        assert_eq!( conflict( ErrorHandler::new( Some( 404 ), None, None, handler_default ) ), "Several error handlers are registered for the status 404" );
+        assert_eq!( conflict( ErrorHandler::new( None, None, None, handler_status ) ), "Several error handlers are registered for every error" );
+    }
+}
@@ -0,0 +1,2 @@
+pub mod error_handler;
+pub mod problem_details;
@@ -0,0 +1,118 @@
+use serde_json::{Map, Value};
+
+use crate::{io::io::IO, services::error::Error};
+
+/// Content type of the responses built by `problem_details`.
+pub const PROBLEM_JSON : &str = "application/problem+json";
+
+/// Replaces the response of a failed request with an `application/problem+json` document (RFC 7807).
+///
+/// The document has these members:
+///
+/// - `type`: `"about:blank"`.
+/// - `title`: the reason phrase of the status code, like `"Not Found"`.
+/// - `status`: the status code of the response.
+/// - `detail`: the message of the error, like `"Body param error: x: invalid digit found in string"`, if the request has an error
+///   caused by the client (`BadRequest`, `NotAcceptable` or `PayloadTooLarge`). The messages of server errors, like a `ParserError`
+///   of a response that can't be serialized, are not sent, like in the default error responses.
+/// - `instance`: the path of the request.
+///
+/// It is meant to be used from an error handler:
+///
+/// ```ignore
+/// #[error_handler]
+/// fn problems(io: IO) -> IO {
+///     awpak_rs::problem_details(io)
+/// }
+/// ```
+pub fn problem_details( mut io : IO ) -> IO
+{
+    let status = io.response.status;
+
+    let mut problem = Map::new();
+
+    problem.insert( "type".to_string(), Value::from( "about:blank" ) );
+
+    if let Some( title ) = hyper::StatusCode::from_u16( status ).ok().and_then( | s | s.canonical_reason() )
+    {
+        problem.insert( "title".to_string(), Value::from( title ) );
+    }
+
+    problem.insert( "status".to_string(), Value::from( status ) );
+
+    if let Some( e @ ( Error::BadRequest( _ ) | Error::NotAcceptable( _ ) | Error::PayloadTooLarge( _ ) ) ) = &io.error
+    {
+        problem.insert( "detail".to_string(), Value::from( e.to_string() ) );
+    }
+
+    problem.insert( "instance".to_string(), Value::from( io.request.uri.path.clone() ) );
+
+    io.response.body = Some( Value::Object( problem ).into() );
+
+    io.response.headers.replace_header( "content-type".to_string(), PROBLEM_JSON.to_string() );
+
+    io
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{io::response::response_data::ResponseData, services::error::{ExtractionError, ExtractionSource}};
+
+    use super::*;
+
+    #[test]
+    fn test_problem_details()
+    {
+        let mut io = IO::with_response( ResponseData::default() );
+
+        io.request.uri.path = "/user/x".to_string();
+
+        let error = ExtractionError::new( "id", ExtractionSource::Path, "invalid digit found in string".to_string() );
+
+        let io = problem_details( io.with_error( Error::BadRequest( error ) ) );
+
+        assert_eq!(
+            io.response.body_value(),
+            Some( &serde_json::json!( {
+                "type" : "about:blank",
+                "title" : "Not Found",
+                "status" : 404,
+                "detail" : "Path variable error: id: invalid digit found in string",
+                "instance" : "/user/x"
+            } ) )
+        );
+
+        assert_eq!( io.response.headers.get_value( "content-type" ).unwrap(), PROBLEM_JSON );
+    }
+
+    #[test]
+    fn test_problem_details_server_error()
+    {
+        let mut io = IO::with_response( ResponseData::default() );
+
+        io.request.uri.path = "/user/1".to_string();
+
+        let io = problem_details( io.with_error( Error::EndpointExecution( "Connection refused: db.internal:5432".to_string() ) ) );
+
+        assert_eq!(
+            io.response.body_value(),
+            Some( &serde_json::json!( {
+                "type" : "about:blank",
+                "title" : "Internal Server Error",
+                "status" : 500,
+                "instance" : "/user/1"
+            } ) )
+        );
+    }
+
+    #[test]
+    fn test_problem_details_parser_error()
+    {
+        let io = IO::with_response( ResponseData::default() );
+
+        let io = problem_details( io.with_error( Error::ParserError( "Invalid xml: unsupported value".to_string() ) ) );
+
+        assert_eq!( io.response.status, 500 );
+        assert!( io.response.body_value().and_then( | v | v.get( "detail" ) ).is_none() );
+    }
+}
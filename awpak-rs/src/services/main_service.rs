@@ -1,19 +1,17 @@
 use std::future::Future;
 use std::pin::Pin;
 
-use http_body_util::combinators::BoxBody;
+use http_body_util::combinators::UnsyncBoxBody;
 use hyper::body::Bytes;
 use hyper::{Request, Response};
 
-use crate::io::cookies::cookies::Cookies;
-use crate::io::headers::headers::Headers;
 use crate::io::io::IO;
-use crate::io::response::response_data::ResponseData;
 use crate::server::server::ServerParams;
 use crate::services::response::service_response::get_initial_response;
 
-use super::endpoint::endpoint_executor::endpoint_exec;
-use super::middleware::middleware::{post_middlewares_exec, pre_middlewares_exec, MiddlewareResponse};
+use super::endpoint::endpoint_executor::endpoint_exec_boxed;
+use super::error_handler::error_handler::error_handlers_exec;
+use super::middleware::middleware::{around_middlewares_exec, post_middlewares_exec, pre_middlewares_exec, MiddlewareResponse};
 use super::request::service_request::get_request_data;
 use super::response::service_response::generate_response;
 
@@ -21,7 +19,7 @@ pub fn main_service_fn( server_params : ServerParams<'static> ) -> impl Fn(hyper
 -> Pin<
         Box<
             dyn Future<
-                Output = Result<Response<BoxBody<hyper::body::Bytes, hyper::Error>>, hyper::Error>
+                Output = Result<Response<UnsyncBoxBody<hyper::body::Bytes, hyper::Error>>, hyper::Error>
             >
             + std::marker::Send
         >
@@ -38,29 +36,36 @@ pub fn main_service_fn( server_params : ServerParams<'static> ) -> impl Fn(hyper
     }
 }
 
-async fn main_service( req: Request<hyper::body::Incoming>, server_params : ServerParams<'_> ) -> Result<Response<BoxBody<Bytes, hyper::Error>>, hyper::Error>
+async fn main_service( req: Request<hyper::body::Incoming>, server_params : ServerParams<'_> ) -> Result<Response<UnsyncBoxBody<Bytes, hyper::Error>>, hyper::Error>
 {
-    let io = get_initial_io( req, server_params ).await?;
+    let io = get_initial_io( req, server_params ).await;
 
-    let io = match pre_middlewares_exec( io ).await
+    let io = match io.error
     {
-        MiddlewareResponse::Next( v ) => v,
-        MiddlewareResponse::Cancel( mut v ) => return Ok( generate_response( &mut v ) )
-    };
-
-    let endpoint_response = endpoint_exec( io ).await;
-
-    let io = match endpoint_response
-    {
-        Ok( s ) => s,
-        Err( e ) => {
-
-            eprintln!( "{:?}", e );
-            
-            IO::with_response( ResponseData::new( e.get_status_code(), Headers::new(), Cookies::new(), Some( serde_json::to_value( "Server Error" ).unwrap() ) ) )
+        // The request can't be read, like a body over the limit. Only the error handlers and the POST middlewares run.
+        Some( _ ) => io,
+        None => {
+
+            let io = match pre_middlewares_exec( io ).await
+            {
+                MiddlewareResponse::Next( v ) => v,
+                MiddlewareResponse::Cancel( mut v ) => return Ok( generate_response( &mut v ) )
+            };
+
+            let endpoint_response = around_middlewares_exec( io, endpoint_exec_boxed ).await;
+
+            // An `around` middleware that fails returns its `IO` with the error, so the request,
+            // context and response reach the error handlers and the POST middlewares.
+            match endpoint_response
+            {
+                Ok( s ) => s,
+                Err( ( io, e ) ) => io.with_error( e )
+            }
         }
     };
 
+    let io = error_handlers_exec( io ).await;
+
     let mut io = match post_middlewares_exec( io ).await
     {
         MiddlewareResponse::Next( v ) => v,
@@ -70,13 +75,19 @@ async fn main_service( req: Request<hyper::body::Incoming>, server_params : Serv
     Ok( generate_response( &mut io ) )
 }
 
-async fn get_initial_io( req: Request<hyper::body::Incoming>, _server_params : ServerParams<'_> ) -> Result<IO, hyper::Error>
+async fn get_initial_io( req: Request<hyper::body::Incoming>, server_params : ServerParams<'_> ) -> IO
 {
-    let request_data = get_request_data( req ).await?;
+    let ( request_data, error ) = get_request_data( req, server_params.max_body ).await;
 
     let response_data = get_initial_response();
 
-    Ok( IO::new( request_data, response_data, None ) )
+    let io = IO::new( request_data, response_data, None );
+
+    match error
+    {
+        Some( e ) => io.with_error( e ),
+        None => io
+    }
 }
 
 
@@ -1,6 +1,8 @@
 use std::sync::OnceLock;
 
-use crate::io::io::IO;
+use regex::{Regex, RegexSet, SetMatches};
+
+use crate::{endpoint::types::EndpointReturnType, io::io::IO, services::error::Error};
 
 /// Represents the possible outcomes of a middleware execution.
 ///
@@ -30,7 +32,22 @@ pub type MiddlewareResponseType = std::pin::Pin<std::boxed::Box<
 pub enum MiddlewareExecOrder
 {
     PRE,
-    POST
+    POST,
+    /// Wraps the rest of the chain. See `Next`.
+    AROUND
+}
+
+pub type AroundMiddlewareFn = fn( IO, Next ) -> EndpointReturnType;
+
+/// Function of a middleware.
+#[derive(Copy, Clone)]
+pub enum MiddlewareFn
+{
+    /// A `PRE` or `POST` middleware.
+    Simple( fn( IO ) -> MiddlewareResponseType ),
+
+    /// An `AROUND` middleware, which receives the rest of the chain.
+    Around( AroundMiddlewareFn )
 }
 
 #[derive(Copy, Clone)]
@@ -39,7 +56,7 @@ pub struct Middleware
     pub regex : Option<&'static str>,
     pub order : usize,
     pub method : Option<&'static str>,
-    pub fnc : fn( IO ) -> MiddlewareResponseType,
+    pub fnc : MiddlewareFn,
     pub exec_order : MiddlewareExecOrder
 }
 
@@ -58,28 +75,224 @@ impl Middleware
             regex,
             order,
             method,
-            fnc,
+            fnc : MiddlewareFn::Simple( fnc ),
             exec_order
         }
     }
+
+    pub const fn around(
+        regex : Option<&'static str>,
+        order : usize,
+        method : Option<&'static str>,
+        fnc : AroundMiddlewareFn
+    ) -> Self
+    {
+        Self
+        {
+            regex,
+            order,
+            method,
+            fnc : MiddlewareFn::Around( fnc ),
+            exec_order : MiddlewareExecOrder::AROUND
+        }
+    }
+}
+
+/// The rest of the chain of an `around` middleware: the following `around` middlewares and the endpoint.
+///
+/// An `around` middleware receives the `IO` and a `Next`, and decides when to run the rest of the chain
+/// with `next.run( io ).await`. It can run code before and after it, hold a value across it, wrap it
+/// in a `tokio::time::timeout`, or inspect and replace its result.
+///
+/// # Example
+///
+/// ```ignore
+/// #[middleware(around)]
+/// fn timing(io: IO, next: Next) -> Result<IO, (IO, Error)> {
+///     let start = std::time::Instant::now();
+///     let result = next.run(io).await;
+///     println!("{:?}", start.elapsed());
+///     result
+/// }
+/// ```
+pub struct Next
+{
+    // The `around` middlewares left, found once for the request when the chain starts
+    around : std::vec::IntoIter<AroundMiddlewareFn>,
+    endpoint : fn( IO ) -> EndpointReturnType
+}
+
+impl Next
+{
+    /// Runs the rest of the chain and returns its result.
+    ///
+    /// If the endpoint fails, or no endpoint is found, the result is `Ok` with the error in `io.error`
+    /// and the error response already prepared. `Err` is only returned by the `around` middlewares
+    /// that fail, with the `IO` they received so the request and context are kept.
+    pub async fn run( mut self, io : IO ) -> Result<IO, ( IO, Error )>
+    {
+        match self.around.next()
+        {
+            Some( fnc ) => fnc( io, self ).await,
+            None => ( self.endpoint )( io ).await
+        }
+    }
 }
 
 inventory::collect!( Middleware );
 
-fn pre_middlewares() -> &'static Vec<Middleware> {
-    static ARRAY_PRE_MIDDLEWARES: OnceLock<Vec<Middleware>> = OnceLock::new();
-    ARRAY_PRE_MIDDLEWARES.get_or_init(|| get_init_middlewares( MiddlewareExecOrder::PRE ) )
+/// A list of middlewares with their `urls` patterns compiled in a single `RegexSet`.
+///
+/// The patterns are compiled once, when the chain is built, and each request path is matched
+/// against all of them in one pass.
+pub struct MiddlewareChain
+{
+    middlewares : Vec<Middleware>,
+    regex_set : RegexSet,
+    // Index in `regex_set` of the pattern of each middleware, if it has one.
+    regex_ids : Vec<Option<usize>>
 }
 
-fn post_middlewares() -> &'static Vec<Middleware> {
-    static ARRAY_POST_MIDDLEWARES: OnceLock<Vec<Middleware>> = OnceLock::new();
-    ARRAY_POST_MIDDLEWARES.get_or_init(|| get_init_middlewares( MiddlewareExecOrder::POST ) )
+impl MiddlewareChain
+{
+    /// Builds a chain that runs the middlewares in the given order.
+    ///
+    /// Returns an error if a `urls` pattern is not a valid regular expression.
+    pub fn new( middlewares : Vec<Middleware> ) -> Result<Self, Error>
+    {
+        let mut patterns = vec![];
+
+        let mut regex_ids = vec![];
+
+        for middleware in &middlewares
+        {
+            match middleware.regex
+            {
+                Some( r ) => {
+
+                    if let Err( e ) = Regex::new( r )
+                    {
+                        return Err( Error::RegexError( format!( "Invalid middleware url pattern {}: {}", r, e ) ) )
+                    }
+
+                    regex_ids.push( Some( patterns.len() ) );
+
+                    patterns.push( r );
+                },
+                None => regex_ids.push( None )
+            }
+        }
+
+        match RegexSet::new( patterns )
+        {
+            Ok( regex_set ) => Ok( Self { middlewares, regex_set, regex_ids } ),
+            Err( e ) => Err( Error::RegexError( e.to_string() ) )
+        }
+    }
+
+    pub fn is_empty( &self ) -> bool
+    {
+        self.middlewares.is_empty()
+    }
+
+    /// Executes the middlewares of the chain whose `exec_order` is `exec_order`.
+    pub async fn exec( &self, mut io : IO, exec_order : MiddlewareExecOrder ) -> MiddlewareResponse
+    {
+        let matches = self.regex_set.matches( &io.request.uri.path );
+
+        for ( middleware, regex_id ) in self.middlewares.iter().zip( &self.regex_ids )
+        {
+            if middleware.exec_order != exec_order || ! is_applicable( middleware, *regex_id, &matches, &io )
+            {
+                continue;
+            }
+
+            let MiddlewareFn::Simple( fnc ) = middleware.fnc else { continue };
+
+            io =  match fnc( io ).await {
+                MiddlewareResponse::Next( v ) => v,
+                MiddlewareResponse::Cancel( v ) => return MiddlewareResponse::Cancel( v )
+            };
+        }
+
+        MiddlewareResponse::Next( io )
+    }
+
+    /// Runs the `around` middlewares of the chain, nested, with `endpoint` as the innermost call.
+    ///
+    /// The middlewares that apply to the request are found once, before the first one runs,
+    /// so a middleware that changes the path or the method doesn't change which ones follow it.
+    pub async fn exec_around( &self, io : IO, endpoint : fn( IO ) -> EndpointReturnType ) -> Result<IO, ( IO, Error )>
+    {
+        let around = self.get_around( &io );
+
+        Next { around : around.into_iter(), endpoint }.run( io ).await
+    }
+
+    // The `around` middlewares applicable to the request, in order.
+    fn get_around( &self, io : &IO ) -> Vec<AroundMiddlewareFn>
+    {
+        if self.middlewares.is_empty()
+        {
+            return vec![]
+        }
+
+        let matches = self.regex_set.matches( &io.request.uri.path );
+
+        self.middlewares.iter().zip( &self.regex_ids ).filter_map( | ( middleware, regex_id ) | match middleware.fnc
+        {
+            MiddlewareFn::Around( fnc ) if is_applicable( middleware, *regex_id, &matches, io ) => Some( fnc ),
+            _ => None
+        } ).collect()
+    }
+}
+
+fn is_applicable( middleware : &Middleware, regex_id : Option<usize>, matches : &SetMatches, io : &IO ) -> bool
+{
+    if let Some( method ) = middleware.method
+    {
+        if io.request.method.to_lowercase() != method
+        {
+            return false
+        }
+    }
+
+    match regex_id
+    {
+        Some( id ) => matches.matched( id ),
+        None => true
+    }
+}
+
+fn around_middlewares() -> &'static Result<MiddlewareChain, Error> {
+    static ARRAY_AROUND_MIDDLEWARES: OnceLock<Result<MiddlewareChain, Error>> = OnceLock::new();
+    ARRAY_AROUND_MIDDLEWARES.get_or_init(|| MiddlewareChain::new( get_init_middlewares( MiddlewareExecOrder::AROUND ) ) )
+}
+
+fn pre_middlewares() -> &'static Result<MiddlewareChain, Error> {
+    static ARRAY_PRE_MIDDLEWARES: OnceLock<Result<MiddlewareChain, Error>> = OnceLock::new();
+    ARRAY_PRE_MIDDLEWARES.get_or_init(|| MiddlewareChain::new( get_init_middlewares( MiddlewareExecOrder::PRE ) ) )
 }
 
-pub fn initialize_middlewares()
+fn post_middlewares() -> &'static Result<MiddlewareChain, Error> {
+    static ARRAY_POST_MIDDLEWARES: OnceLock<Result<MiddlewareChain, Error>> = OnceLock::new();
+    ARRAY_POST_MIDDLEWARES.get_or_init(|| MiddlewareChain::new( get_init_middlewares( MiddlewareExecOrder::POST ) ) )
+}
+
+/// Builds the PRE, AROUND and POST middleware chains.
+///
+/// Returns an error if the `urls` pattern of a middleware is not a valid regular expression.
+pub fn initialize_middlewares() -> Result<(), Error>
 {
-    let _ = pre_middlewares();
-    let _ = post_middlewares();
+    for chain in [ pre_middlewares(), around_middlewares(), post_middlewares() ]
+    {
+        if let Err( e ) = chain
+        {
+            return Err( e.clone() )
+        }
+    }
+
+    Ok( () )
 }
 
 fn get_init_middlewares( exec_order : MiddlewareExecOrder ) -> Vec<Middleware>
@@ -109,39 +322,148 @@ pub async fn post_middlewares_exec( io : IO ) -> MiddlewareResponse
     exec_middlewares( io, MiddlewareExecOrder::POST ).await
 }
 
+/// Runs the global `around` middlewares with `endpoint` as the innermost call.
+pub async fn around_middlewares_exec( io : IO, endpoint : fn( IO ) -> EndpointReturnType ) -> Result<IO, ( IO, Error )>
+{
+    match around_middlewares()
+    {
+        Ok( m ) => m.exec_around( io, endpoint ).await,
+        Err( e ) => Err( ( io, e.clone() ) )
+    }
+}
+
 async fn exec_middlewares( mut io : IO, exec_order : MiddlewareExecOrder ) -> MiddlewareResponse
 {
     let middlewares = match exec_order
     {
         MiddlewareExecOrder::POST => post_middlewares(),
-        MiddlewareExecOrder::PRE => pre_middlewares()
+        MiddlewareExecOrder::PRE => pre_middlewares(),
+        MiddlewareExecOrder::AROUND => around_middlewares()
     };
 
-    for middleware in middlewares
+    match middlewares
+    {
+        Ok( m ) => m.exec( io, exec_order ).await,
+        Err( e ) => {
+
+            // Only reachable if `initialize_middlewares` was not called at startup
+            eprintln!( "{:?}", e );
+
+            io.response.status = 500;
+
+            MiddlewareResponse::Cancel( io )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::response::response_data::ResponseData;
+
+    use super::*;
+
+    fn middleware_add_one( mut io : IO ) -> MiddlewareResponseType
     {
-        if middleware.method.is_some()
+        Box::pin( async move { io.response.status += 1; MiddlewareResponse::Next( io ) } )
+    }
+
+    fn middleware_add_ten( mut io : IO ) -> MiddlewareResponseType
+    {
+        Box::pin( async move { io.response.status += 10; MiddlewareResponse::Next( io ) } )
+    }
+
+    fn get_io( path : &str ) -> IO
+    {
+        let mut io = IO::with_response( ResponseData::default() );
+
+        io.request.method = "get".to_string();
+
+        io.request.uri.path = path.to_string();
+
+        io.response.status = 0;
+
+        io
+    }
+
+    async fn exec_status( chain : &MiddlewareChain, path : &str, exec_order : MiddlewareExecOrder ) -> u16
+    {
+        match chain.exec( get_io( path ), exec_order ).await
         {
-            if &io.request.method.to_lowercase().as_str() != middleware.method.as_ref().unwrap()
+            MiddlewareResponse::Next( io ) | MiddlewareResponse::Cancel( io ) => io.response.status
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_chain()
+    {
+        let chain = MiddlewareChain::new( vec![
+            Middleware::new( Some( "^/a.*$" ), 1, None, middleware_add_one, MiddlewareExecOrder::PRE ),
+            Middleware::new( None, 2, Some( "get" ), middleware_add_ten, MiddlewareExecOrder::PRE ),
+            Middleware::new( Some( "^/b$" ), 3, None, middleware_add_ten, MiddlewareExecOrder::POST ),
+            Middleware::new( None, 4, Some( "post" ), middleware_add_one, MiddlewareExecOrder::PRE )
+        ] ).unwrap();
+
+        assert_eq!( exec_status( &chain, "/abc", MiddlewareExecOrder::PRE ).await, 11 );
+        assert_eq!( exec_status( &chain, "/b", MiddlewareExecOrder::PRE ).await, 10 );
+        assert_eq!( exec_status( &chain, "/b", MiddlewareExecOrder::POST ).await, 10 );
+        assert_eq!( exec_status( &chain, "/abc", MiddlewareExecOrder::POST ).await, 0 );
+    }
+
+    fn around_double( io : IO, next : Next ) -> EndpointReturnType
+    {
+        Box::pin( async move {
+            let mut io = next.run( io ).await?;
+            io.response.status *= 2;
+            Ok( io )
+        } )
+    }
+
+    fn around_fail( io : IO, next : Next ) -> EndpointReturnType
+    {
+        Box::pin( async move {
+            match next.run( io ).await
             {
-                continue;
+                Ok( io ) => Err( ( io, Error::EndpointExecution( "Fail".to_string() ) ) ),
+                e => e
             }
-        }
+        } )
+    }
+
+    fn endpoint_add_three( mut io : IO ) -> EndpointReturnType
+    {
+        Box::pin( async move { io.response.status += 3; Ok( io ) } )
+    }
+
+    #[tokio::test]
+    async fn test_around_middlewares()
+    {
+        let chain = MiddlewareChain::new( vec![
+            Middleware::around( None, 1, None, around_double ),
+            Middleware::around( Some( "^/fail$" ), 2, None, around_fail ),
+            Middleware::around( None, 3, Some( "get" ), around_double ),
+            Middleware::around( None, 4, Some( "post" ), around_fail )
+        ] ).unwrap();
 
-        if middleware.regex.is_some()
+        let io = chain.exec_around( get_io( "/a" ), endpoint_add_three ).await;
+
+        assert!( matches!( io, Ok( io ) if io.response.status == 12 ) );
+
+        let io = chain.exec_around( get_io( "/fail" ), endpoint_add_three ).await;
+
+        match io
         {
-            match regex::Regex::new( middleware.regex.as_ref().unwrap() ) {
-                
-                Ok( v ) => 
-                    if v.is_match( &io.request.uri.path ) {} else { continue; },
-                _ => { continue; }
-            };
+            Err( ( io, Error::EndpointExecution( _ ) ) ) => assert_eq!( io.request.uri.path, "/fail" ),
+            _ => panic!( "Expected the IO with the error" )
         }
-
-        io =  match ( middleware.fnc )( io ).await {
-            MiddlewareResponse::Next( v ) => v,
-            MiddlewareResponse::Cancel( v ) => return MiddlewareResponse::Cancel( v )
-        };
     }
 
-    MiddlewareResponse::Next( io )
-}
\ No newline at end of file
+    #[test]
+    fn test_invalid_pattern()
+    {
+        let chain = MiddlewareChain::new( vec![
+            Middleware::new( Some( "^/a($" ), 1, None, middleware_add_one, MiddlewareExecOrder::PRE )
+        ] );
+
+        assert!( matches!( chain, Err( Error::RegexError( _ ) ) ) );
+    }
+}
@@ -3,4 +3,6 @@ pub mod request;
 pub mod response;
 pub mod endpoint;
 pub mod middleware;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod error_handler;
+pub mod websocket;
\ No newline at end of file
@@ -1,21 +1,17 @@
-use std::str::FromStr as _;
+use std::{convert::Infallible, str::FromStr as _};
 
-use futures::StreamExt as _;
-use http_body_util::BodyStream;
-use hyper::body::{Bytes, Incoming};
+use hyper::body::Bytes;
 use multer::{Field, Multipart};
 use serde_json::{Map, Value};
 
-use crate::{body::body::{BodyData, FileData}, ContentTypeStrategy};
-
-use super::service_request::REQUEST_MIME_TYPES_AVAILABLES;
+use crate::{body::body::{BodyData, FileData}, parser::codec::codec::{decode, request_mime_types}};
 
 // Process the request body as multipart/form-data.
-pub async fn get_body_from_multipart( body: Incoming, boundary: String ) -> multer::Result<BodyData>
+pub async fn get_body_from_multipart( body: Bytes, boundary: String ) -> multer::Result<BodyData>
 {  
-    let mut multipart = get_multipart( body, boundary );
+    let mut multipart = get_multipart( body.clone(), boundary );
 
-    let mut body_data = BodyData { value : Some( Value::Object( Map::new() ) ), files : vec![] };
+    let mut body_data = BodyData { value : Some( Value::Object( Map::new() ) ), files : vec![], raw : Some( body ), error : None };
 
     // Iterate over the fields, `next_field` method will return the next field if
     // available.
@@ -27,10 +23,9 @@ pub async fn get_body_from_multipart( body: Incoming, boundary: String ) -> mult
     Ok( body_data )
 }
 
-fn get_multipart<'a>( body: Incoming, boundary: String ) -> Multipart<'a>
+fn get_multipart<'a>( body: Bytes, boundary: String ) -> Multipart<'a>
 {
-    let body_stream = BodyStream::new(body)
-        .filter_map(|result| async move { result.map(|frame| frame.into_data().ok()).transpose() });
+    let body_stream = futures::stream::once( async move { Ok::<Bytes, Infallible>( body ) } );
 
     Multipart::new( body_stream, boundary )
 }
@@ -103,16 +98,9 @@ async fn process_part_as_json( field : Field<'_>, body_data : &mut BodyData )
             _ => {
                 let body_bytes = Bytes::from( text );
 
-                let body = ContentTypeStrategy::exec( content_type, body_bytes );
-
-                if body.is_ok()
+                if let Ok( body ) = decode( content_type, body_bytes )
                 {
-                    let body = body.unwrap();
-
-                    if body.is_ok()
-                    {
-                        body_data.value.as_mut().unwrap().as_object_mut().unwrap().insert( name, body.unwrap() );
-                    }
+                    body_data.value.as_mut().unwrap().as_object_mut().unwrap().insert( name, body );
                 }
             }
         };
@@ -127,16 +115,16 @@ fn get_content_type( field : &Field<'_> ) -> String
         {
             let content_type = format!( "{}/{}", v.type_().to_string().to_lowercase(), v.subtype().to_string().to_lowercase() );
 
-            if REQUEST_MIME_TYPES_AVAILABLES.contains( &content_type.as_str() )
+            if request_mime_types().contains( &content_type.as_str() )
             {
                 content_type.to_string()
             }
             else
             {
-                REQUEST_MIME_TYPES_AVAILABLES[ 0 ].to_string()    
+                request_mime_types()[ 0 ].to_string()    
             }
         },
-        _ => REQUEST_MIME_TYPES_AVAILABLES[ 0 ].to_string()
+        _ => request_mime_types()[ 0 ].to_string()
     }
 }
 
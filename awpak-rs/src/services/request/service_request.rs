@@ -1,15 +1,19 @@
 
-use std::collections::BTreeSet;
+use http_body_util::{BodyExt as _, Limited};
+use hyper::header::{HeaderName, HeaderValue, ACCEPT, CONTENT_LENGTH, CONTENT_TYPE};
 
-use http_body_util::BodyExt as _;
-use hyper::header::{HeaderName, HeaderValue, ACCEPT, CONTENT_TYPE};
+use crate::{body::{body::BodyData, body_stream::{body_error, RequestBodyStream}}, io::{cookies::cookies::Cookies, headers::{header_data::HeaderData, headers::Headers, media_type::MediaType, mime::Mime}, request::request_data::{RequestData, Uri}}, parser::codec::codec::{decode, request_mime_types}};
 
-use crate::{body::body::BodyData, io::{cookies::cookies::Cookies, headers::{header_data::HeaderData, headers::Headers, mime::Mime}, request::request_data::{RequestData, Uri}}, ContentTypeStrategy};
+use crate::services::{endpoint::endpoint_executor::resolve_route, error::Error};
 
 use super::multipart::get_body_from_multipart;
 
 
-pub async fn get_request_data( request : hyper::Request<hyper::body::Incoming> ) -> Result<RequestData, hyper::Error>
+/// Reads the request. `max_body` is the limit of the body, unless the endpoint has its own.
+///
+/// A body over the limit is returned with a `PayloadTooLarge` error. If the `Content-Length` is over the limit,
+/// the body is not read at all, so a client waiting for a `100 Continue` doesn't send it.
+pub async fn get_request_data( request : hyper::Request<hyper::body::Incoming>, max_body : usize ) -> ( RequestData, Option<Error> )
 {
     let boundary = request
         .headers()
@@ -17,7 +21,10 @@ pub async fn get_request_data( request : hyper::Request<hyper::body::Incoming> )
         .and_then(|ct| ct.to_str().ok())
         .and_then(|ct| multer::parse_boundary(ct).ok());
 
-    let ( parts, body ) = request.into_parts();
+    let ( mut parts, body ) = request.into_parts();
+
+    // Only present when the connection is served with upgrades and the request asks for one
+    let upgrade = parts.extensions.remove::<hyper::upgrade::OnUpgrade>();
     
     let headers = get_headers( &parts );
 
@@ -25,9 +32,44 @@ pub async fn get_request_data( request : hyper::Request<hyper::body::Incoming> )
 
     let uri = get_uri( &parts );
 
-    let body = get_body( body, &headers, boundary ).await?;
+    let route = resolve_route( &uri.path, parts.method.as_str() );
+
+    let options = route.as_ref().map( | r | r.body_options ).unwrap_or_default();
+
+    let max_body = options.max_body.unwrap_or( max_body );
+
+    let empty_body = || BodyData { value : None, files : vec![], raw : None, error : None };
+
+    let ( body, body_stream, error ) = if get_content_length( &parts ).is_some_and( | l | l > max_body as u64 )
+    {
+        ( empty_body(), None, Some( Error::PayloadTooLarge( max_body ) ) )
+    }
+    else if options.stream
+    {
+        // The body of a `#[body_stream]` endpoint is given to it as it arrives
+        ( empty_body(), Some( RequestBodyStream::new( body, max_body ) ), None )
+    }
+    else
+    {
+        match get_body( body, &headers, boundary, max_body ).await
+        {
+            Ok( b ) => ( b, None, None ),
+            Err( e ) => ( empty_body(), None, Some( e ) )
+        }
+    };
+
+    (
+        RequestData::new( uri, parts.method.to_string(), headers, cookies, body )
+        .with_upgrade( upgrade )
+        .with_body_stream( body_stream )
+        .with_route( route ),
+        error
+    )
+}
 
-    Ok( RequestData::new( uri, parts.method.to_string(), headers, cookies, body ) )
+fn get_content_length( parts : &hyper::http::request::Parts ) -> Option<u64>
+{
+    parts.headers.get( CONTENT_LENGTH )?.to_str().ok()?.trim().parse::<u64>().ok()
 }
 
 fn get_cookies( parts : &hyper::http::request::Parts ) -> Cookies
@@ -47,84 +89,69 @@ fn get_cookies( parts : &hyper::http::request::Parts ) -> Cookies
     cookies
 }
 
-async fn get_body( body : hyper::body::Incoming, headers : &Headers, boundary : Option<String> ) -> Result<BodyData, hyper::Error>
+// Only fails if the body is over `max_body`. The reading is stopped as soon as the limit is crossed.
+// A body that can't be read or parsed is kept with its error, for the extractors of the endpoint.
+async fn get_body( body : hyper::body::Incoming, headers : &Headers, boundary : Option<String>, max_body : usize ) -> Result<BodyData, Error>
 {
+    let bytes = match Limited::new( body, max_body ).collect().await
+    {
+        Ok( v ) => v.to_bytes(),
+        Err( e ) => match body_error( e, max_body )
+        {
+            Error::PayloadTooLarge( m ) => return Err( Error::PayloadTooLarge( m ) ),
+            e => return Ok( BodyData { value : None, files : vec![], raw : None, error : Some( e.to_string() ) } )
+        }
+    };
+
     if boundary.is_some()
     {
-        return match get_body_from_multipart( body, boundary.unwrap() ).await
+        return match get_body_from_multipart( bytes.clone(), boundary.unwrap() ).await
         {
             Ok( b ) => Ok( b ),
-            Err( e ) => 
-            {
-                eprintln!( "{}", e );
-
-                Ok( BodyData { value: None, files: vec![] } )
-            }
+            Err( e ) => Ok( BodyData { value: None, files: vec![], raw : Some( bytes ), error : Some( e.to_string() ) } )
         }
     }
 
-    let value = match body.collect().await
-    {
-        Ok( v ) =>
-        {
-            let content_type = &get_content_type( headers.get( CONTENT_TYPE.as_str() ) );
-
-            match ContentTypeStrategy::exec( content_type, v.to_bytes() )
-            {
-                Ok( v ) => match v
-                {
-                    Ok( v ) => Some( v ),
-                    Err( e ) => 
-                    {
-                        eprintln!( "{:?}", e );
-
-                        None
-                    }
-                },
-                Err( e ) =>
-                {
-                    eprintln!( "{}", e );
-
-                    None
-                }
-            }
-        },
-        Err( e ) =>
-        {
-            eprintln!( "{}", e );
+    let content_type = get_content_type( headers.get( CONTENT_TYPE.as_str() ) );
 
-            None
-        }
+    let ( value, error ) = match parse_body( &content_type, bytes.clone() )
+    {
+        Ok( v ) => ( Some( v ), None ),
+        Err( e ) => ( None, Some( e ) )
     };
 
     Ok( 
         BodyData
         {
             value,
-            files : vec![]
+            files : vec![],
+            raw : Some( bytes ),
+            error
         }
     )
 }
 
-pub const REQUEST_MIME_TYPES_AVAILABLES : &[&str] = &[ "text/plain", "application/json" ];
-
-pub fn get_content_type( header : Option<&HeaderData> ) -> String
+/// The media type of the request body. Types without a parser are read as `text/plain`, keeping their parameters.
+pub fn get_content_type( header : Option<&HeaderData> ) -> MediaType
 {
-    let content_type = match header
-    {
-        Some( v ) => match &v.value {
-            Some( v ) => v,
-            _ => REQUEST_MIME_TYPES_AVAILABLES[ 0 ]
-        },
-        _ => REQUEST_MIME_TYPES_AVAILABLES[ 0 ]
-    };
+    let media_type = header
+        .and_then( | h | h.value.as_ref() )
+        .and_then( | v | v.parse::<MediaType>().ok() );
 
-    if REQUEST_MIME_TYPES_AVAILABLES.contains( &content_type )
+    match media_type
     {
-        return content_type.to_string()
+        Some( m ) if request_mime_types().contains( &m.get_mime_type().as_str() ) => m,
+        Some( m ) => MediaType { parameters : m.parameters, ..MediaType::new( "text", "plain" ) },
+        _ => MediaType::new( "text", "plain" )
     }
+}
 
-    REQUEST_MIME_TYPES_AVAILABLES[ 0 ].to_string()
+// The body is decoded to UTF-8 before parsing it
+fn parse_body( content_type : &MediaType, bytes : hyper::body::Bytes ) -> Result<serde_json::Value, String>
+{
+    let bytes = content_type.decode( bytes )?;
+
+    decode( &content_type.get_mime_type(), bytes )
 }
 
 fn get_uri( parts : &hyper::http::request::Parts ) -> Uri
@@ -153,16 +180,21 @@ fn get_headers( parts : &hyper::http::request::Parts ) -> Headers
 
     let mut ret = Headers::new();
 
+    let mut accept = vec![];
+
     for header in headers
     {
+        // Several `Accept` headers are the same as one with all their values
         if header.0 == ACCEPT
         {
-            ret.set_accept( parse_accept_header( header.1 ) );
+            accept.extend( parse_accept_header( header.1 ) );
         }
 
         let _ = ret.add_header_data( get_header_from( header ) );
     }
 
+    ret.set_accept( accept );
+
     ret
 }
 
@@ -179,9 +211,9 @@ fn get_header_from( data : ( &HeaderName, &HeaderValue ) ) -> HeaderData
     HeaderData::new( name, value_bytes, value )
 }
 
-fn parse_accept_header( value : &HeaderValue ) -> BTreeSet<Mime>
+fn parse_accept_header( value : &HeaderValue ) -> Vec<Mime>
 {
-    let mut mimes : BTreeSet<Mime> = BTreeSet::new();
+    let mut mimes : Vec<Mime> = vec![];
 
     let value = value.to_str();
 
@@ -202,7 +234,7 @@ fn parse_accept_header( value : &HeaderValue ) -> BTreeSet<Mime>
 
         if mime.is_ok()
         {
-            mimes.insert( mime.unwrap() );
+            mimes.push( mime.unwrap() );
         }
 
     } );
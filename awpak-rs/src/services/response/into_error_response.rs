@@ -0,0 +1,76 @@
+use crate::{io::io::IO, services::error::Error};
+
+/// A trait for the error types returned by endpoints.
+///
+/// An endpoint that returns `Result<T, E>` with `E: IntoErrorResponse` sends `T` as the body when it returns `Ok`.
+/// When it returns `Err`, the error sets the status, headers and body of the response.
+/// The `?` operator can be used in the body of the endpoint.
+///
+/// `awpak_rs::Error` implements this trait, so an endpoint can return `Result<T, awpak_rs::Error>`.
+/// The response is then the same as for any other error of the request, and error handlers run.
+/// `String` and `&'static str` implement it as an `Error::EndpointExecution`, a 500 whose message is not sent.
+///
+/// # Migrating from serialized results
+///
+/// Endpoints that returned a `Result<T, E>` used to send it serialized, like `{"Ok": ...}`.
+/// Now the `E` of every `Result<T, E>` must implement this trait. For other error types,
+/// implement it, or convert the error with `map_err` into an `awpak_rs::Error`.
+///
+/// # Example
+///
+/// ```ignore
+/// enum UserError {
+///     NotFound(u64)
+/// }
+///
+/// impl IntoErrorResponse for UserError {
+///     fn into_error_response(self, mut io: IO) -> IO {
+///         match self {
+///             UserError::NotFound(id) => {
+///                 io.response.status = 404;
+///                 io.response.body = Some(Value::from(format!("User {} not found", id)).into());
+///             }
+///         }
+///         io
+///     }
+/// }
+///
+/// #[get(url = "/user/{id}")]
+/// async fn get_user(#[path_variable] id: u64) -> Result<User, UserError> {
+///     let user = find_user(id).await.ok_or(UserError::NotFound(id))?;
+///     Ok(user)
+/// }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` can't be the error of a `Result` returned by an endpoint",
+    note = "implement `awpak_rs::IntoErrorResponse` for `{Self}`, or convert the error into an `awpak_rs::Error`"
+)]
+pub trait IntoErrorResponse
+{
+    /// Sets the response of the request in `io`.
+    fn into_error_response( self, io : IO ) -> IO;
+}
+
+impl IntoErrorResponse for Error
+{
+    fn into_error_response( self, io : IO ) -> IO
+    {
+        io.with_error( self )
+    }
+}
+
+impl IntoErrorResponse for String
+{
+    fn into_error_response( self, io : IO ) -> IO
+    {
+        io.with_error( Error::EndpointExecution( self ) )
+    }
+}
+
+impl IntoErrorResponse for &'static str
+{
+    fn into_error_response( self, io : IO ) -> IO
+    {
+        io.with_error( Error::EndpointExecution( self.to_string() ) )
+    }
+}
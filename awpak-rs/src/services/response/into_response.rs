@@ -0,0 +1,12 @@
+use crate::io::io::IO;
+
+/// A trait for the return types of endpoints that set the whole response, not only the body.
+///
+/// Endpoints that return `Response<T>`, `Created<T>`, `NoContent` or `Redirect` (from `awpak_rs`)
+/// use this trait to set the status, headers, cookies and body in `io.response`.
+/// Headers set with `#[response_headers]` are applied first, so the ones of the return value replace them.
+pub trait IntoResponse
+{
+    /// Sets the response of the request in `io`.
+    fn into_response( self, io : IO ) -> IO;
+}
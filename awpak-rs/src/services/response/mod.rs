@@ -1 +1,4 @@
-pub mod service_response;
\ No newline at end of file
+pub mod service_response;
+pub mod into_error_response;
+pub mod into_response;
+pub mod response_kind;
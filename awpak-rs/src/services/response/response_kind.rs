@@ -0,0 +1,96 @@
+use std::marker::PhantomData;
+
+use crate::{io::{io::IO, response::response_body::ResponseBody}, serialize_value};
+
+use super::into_response::IntoResponse;
+
+// Chooses how the value returned by an endpoint sets the response, by the traits of its type and not by its name:
+// types that implement `IntoResponse` set the whole response, and the other ones are serialized as the body.
+//
+// `( &ResponseKind::<T>( PhantomData ) ).response_kind()` resolves to `IntoResponseKind` if `T: IntoResponse`,
+// because it needs no autoref, and to `SerializeKind`, implemented for `&ResponseKind<T>`, otherwise.
+
+#[doc(hidden)]
+pub struct ResponseKind<T>( pub PhantomData<T> );
+
+#[doc(hidden)]
+pub trait IntoResponseKind
+{
+    fn response_kind( &self ) -> IntoResponseSender
+    {
+        IntoResponseSender
+    }
+}
+
+impl<T : IntoResponse> IntoResponseKind for ResponseKind<T> {}
+
+#[doc(hidden)]
+pub trait SerializeKind
+{
+    fn response_kind( &self ) -> SerializeSender
+    {
+        SerializeSender
+    }
+}
+
+impl<T> SerializeKind for &ResponseKind<T> {}
+
+#[doc(hidden)]
+pub struct IntoResponseSender;
+
+impl IntoResponseSender
+{
+    pub fn send<T : IntoResponse>( self, value : T, io : IO ) -> IO
+    {
+        value.into_response( io )
+    }
+}
+
+#[doc(hidden)]
+pub struct SerializeSender;
+
+impl SerializeSender
+{
+    pub fn send<T : serde::Serialize>( self, value : T, mut io : IO ) -> IO
+    {
+        io.response.body = serialize_value::<T>( value ).map( ResponseBody::from );
+
+        io
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use crate::{io::response::response_data::ResponseData, NoContent};
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Response
+    {
+        id : u8
+    }
+
+    // The borrow is needless for the types that implement `IntoResponse`, but it is the call generated for every type
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn test_response_kind()
+    {
+        let io = IO::with_response( ResponseData::default() );
+
+        let io = ( &ResponseKind::<Response>( PhantomData ) ).response_kind().send( Response { id : 1 }, io );
+
+        assert_eq!( io.response.body.as_ref().and_then( | b | b.as_value() ), Some( &serde_json::json!( { "id" : 1 } ) ) );
+
+        let io = ( &ResponseKind::<NoContent>( PhantomData ) ).response_kind().send( NoContent, io );
+
+        assert_eq!( io.response.status, 204 );
+        assert!( io.response.body.is_none() );
+
+        let io = ( &ResponseKind::<Vec<u16>>( PhantomData ) ).response_kind().send( vec![ 1u16, 2 ], io );
+
+        assert_eq!( io.response.body.as_ref().and_then( | b | b.as_value() ), Some( &serde_json::json!( [ 1, 2 ] ) ) );
+    }
+}
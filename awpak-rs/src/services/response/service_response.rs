@@ -1,23 +1,49 @@
 
-use http_body_util::{combinators::BoxBody, BodyExt, Full};
-use hyper::{body::Bytes, header::{HeaderName, HeaderValue, SET_COOKIE}, Response};
+use futures::StreamExt;
+use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full, StreamBody};
+use hyper::{body::{Bytes, Frame}, header::{HeaderName, HeaderValue, SET_COOKIE}, Response};
 
-use crate::{io::{io::IO, response::response_data::ResponseData}, ResponseContentTypeStrategy};
+use crate::{io::{headers::media_type::MediaType, io::IO, response::{response_body::ResponseBody, response_data::ResponseData}}, parser::codec::codec::{encode, response_mime_types}, services::error_handler::problem_details::PROBLEM_JSON};
+#[cfg( feature = "xml" )]
+use crate::parser::xml::xml_parser::to_xml;
 
-const MIME_TYPES_AVAILABLES : &[&str] = &[ "text/plain", "application/json" ];
+// Content types that are kept when set explicitly in the response, but never chosen by content negotiation.
+const MIME_TYPES_EXPLICIT : &[&str] = &[ PROBLEM_JSON ];
 
 pub fn get_initial_response() -> ResponseData
 {
     ResponseData::default()
 }
 
-pub fn generate_response( io : &mut IO ) -> Response<BoxBody<Bytes, hyper::Error>>
+pub fn generate_response( io : &mut IO ) -> Response<UnsyncBoxBody<Bytes, hyper::Error>>
 {
-    let content_type = set_content_type( io );
+    let body = match io.response.body.take()
+    {
+        // The websocket handshake response has no body, the connection is taken by the websocket after it
+        _ if io.response.status == 101 => full_body( Bytes::new() ),
+        Some( ResponseBody::Stream { stream, content_type } ) => {
 
-    let body = get_body_response( &mut io.response, &content_type );
+            io.response.headers.replace_header( "content-type".to_string(), content_type );
 
-    io.response.headers.replace_header( "content-type".to_string(), content_type.clone() );
+            // Without a content-length, the stream is sent with chunked transfer encoding
+            io.response.headers.remove( "content-length" );
+
+            if io.request.method.eq_ignore_ascii_case( "HEAD" )
+            {
+                full_body( Bytes::new() )
+            }
+            else
+            {
+                StreamBody::new( stream.into_inner().map( | b | Ok::<_, hyper::Error>( Frame::data( b ) ) ) ).boxed_unsync()
+            }
+        },
+        body => {
+
+            io.response.body = body;
+
+            full_body( get_full_body( io ) )
+        }
+    };
 
     let mut response : hyper::http::response::Builder = Response::builder()
         .status( io.response.status );
@@ -26,17 +52,13 @@ pub fn generate_response( io : &mut IO ) -> Response<BoxBody<Bytes, hyper::Error
 
     append_cookies( &mut response, &mut io.response );
 
-    let response = response.body( Full::new( body ).map_err(|never| match never {}).boxed() );
+    let response = response.body( body );
 
     if response.is_err()
     {
         let response = Response::builder()
             .status( 500 )
-            .body( 
-                Full::new( b"".to_vec().into() )
-                    .map_err(|never| match never {})
-                    .boxed() 
-            );
+            .body( full_body( Bytes::new() ) );
 
         return response.unwrap();
     }
@@ -44,28 +66,74 @@ pub fn generate_response( io : &mut IO ) -> Response<BoxBody<Bytes, hyper::Error
     response.unwrap()
 }
 
+fn full_body( bytes : Bytes ) -> UnsyncBoxBody<Bytes, hyper::Error>
+{
+    Full::new( bytes ).map_err(|never| match never {}).boxed_unsync()
+}
 
-
-fn set_content_type( io : &mut IO ) -> String
+fn get_full_body( io : &mut IO ) -> Bytes
 {
-    if io.response.headers.has( "content-type" ) &&
-        MIME_TYPES_AVAILABLES.contains( &io.response.headers.get_value( "content-type" ).as_ref().unwrap().as_str() )
+    // Raw bytes are sent verbatim with their own content type
+    let ( content_type, body ) = match &io.response.body
     {
-        return io.response.headers.get_value( "content-type" ).unwrap().clone()
-    }
+        Some( ResponseBody::Bytes { bytes, content_type } ) => ( content_type.clone(), bytes.clone() ),
+        _ => {
+            let media_type = set_content_type( io );
 
-    let mime_type = io.request.headers.content_negotiation( MIME_TYPES_AVAILABLES );
+            // The serializers write UTF-8, it's converted if the content type has another charset
+            let body = media_type.encode( get_body_response( &io.response, &media_type.get_mime_type() ) );
+
+            ( io.response.headers.get_value( "content-type" ).unwrap().clone(), body )
+        }
+    };
 
-    if mime_type.is_none()
+    io.response.headers.replace_header( "content-type".to_string(), content_type.clone() );
+
+    // A HEAD response keeps the headers of the GET response, including its length, but not the body.
+    // The statuses without body have no length, like in the GET response.
+    if io.request.method.eq_ignore_ascii_case( "HEAD" )
     {
-        io.response.headers.replace_header( "content-type".to_string(), MIME_TYPES_AVAILABLES[ 0 ].to_string() );
+        if ! is_status_without_body( io.response.status )
+        {
+            io.response.headers.replace_header( "content-length".to_string(), body.len().to_string() );
+        }
+
+        Bytes::new()
     }
     else
     {
-        io.response.headers.replace_header( "content-type".to_string(), mime_type.as_ref().unwrap().clone() );
+        body
     }
+}
 
-    io.response.headers.get_value( "content-type" ).unwrap().clone()
+// 1xx, 204 and 304 responses never have a body
+fn is_status_without_body( status : u16 ) -> bool
+{
+    status < 200 || status == 204 || status == 304
+}
+
+// A content type set in the response is kept, with its parameters, if there is a serializer for it.
+fn set_content_type( io : &mut IO ) -> MediaType
+{
+    if let Some( media_type ) = io.response.headers.content_type()
+    {
+        let mime_type = media_type.get_mime_type();
+
+        if response_mime_types().contains( &mime_type.as_str() ) || MIME_TYPES_EXPLICIT.contains( &mime_type.as_str() )
+        {
+            return media_type
+        }
+    }
+
+    let mime_type = io.request.headers.content_negotiation( response_mime_types() )
+        .unwrap_or( response_mime_types()[ 0 ].to_string() );
+
+    // The types of the codecs are checked by `initialize_codecs` at startup
+    let media_type = mime_type.parse().unwrap_or( MediaType::new( "text", "plain" ) );
+
+    io.response.headers.replace_header( "content-type".to_string(), media_type.get_mime_type() );
+
+    media_type
 }
 
 fn append_cookies( response : &mut hyper::http::response::Builder, response_data : &ResponseData )
@@ -111,24 +179,38 @@ fn append_headers( response : &mut hyper::http::response::Builder, response_data
     );
 }
 
-fn get_body_response( response_data : &ResponseData, content_type : &String ) -> Bytes
+fn get_body_response( response_data : &ResponseData, content_type : &str ) -> Bytes
 {
-    let out = ResponseContentTypeStrategy::exec( 
-        &content_type, 
-        match response_data.body.clone()
-        {
-            Some( v ) => v,
-            _ => serde_json::to_value( "" ).unwrap()
+    let value = match response_data.body_value()
+    {
+        Some( v ) => v.clone(),
+        _ => serde_json::to_value( "" ).unwrap()
+    };
+
+    match encode_body( response_data, content_type, value )
+    {
+        Ok( v ) => v,
+        Err( e ) => {
+            eprintln!( "{}", e );
+
+            "".into()
         }
-    );
+    }
+}
 
-    match out
+// The built-in XML serializer writes the root element set by the endpoint
+#[cfg( feature = "xml" )]
+fn encode_body( response_data : &ResponseData, content_type : &str, value : serde_json::Value ) -> Result<Bytes, String>
+{
+    match &response_data.xml_root
     {
-        Ok( v ) => match v
-        {
-            Ok( v ) => v,
-            _ => "".into()    
-        },
-        _ => "".into()
+        Some( root ) if content_type == "application/xml" => to_xml( value, root ).map_err( | e | format!( "{:?}", e ) ),
+        _ => encode( content_type, value )
     }
-}
\ No newline at end of file
+}
+
+#[cfg( not( feature = "xml" ) )]
+fn encode_body( _response_data : &ResponseData, content_type : &str, value : serde_json::Value ) -> Result<Bytes, String>
+{
+    encode( content_type, value )
+}
@@ -0,0 +1,2 @@
+pub mod websocket;
+pub mod upgrade;
\ No newline at end of file
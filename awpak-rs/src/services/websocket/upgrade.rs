@@ -0,0 +1,237 @@
+use std::future::Future;
+
+use hyper::upgrade::OnUpgrade;
+use hyper_util::rt::TokioIo;
+use tokio_tungstenite::{tungstenite::{handshake::derive_accept_key, protocol::Role}, WebSocketStream};
+
+use crate::{io::{headers::headers::Headers, io::IO}, services::error::{Error, ExtractionError, ExtractionSource}};
+
+use super::websocket::WebSocket;
+
+/// A validated websocket handshake, waiting for the response to be sent.
+///
+/// Used by `#[websocket]` endpoints. The handshake is validated after the middlewares,
+/// so a middleware can still reject the request, like any other endpoint.
+pub struct WebSocketUpgrade
+{
+    on_upgrade : OnUpgrade,
+    accept : String,
+    protocol : Option<String>
+}
+
+impl WebSocketUpgrade
+{
+    /// Validates the handshake headers of the request.
+    ///
+    /// `protocols` are the subprotocols supported by the endpoint. The first one of the
+    /// `Sec-WebSocket-Protocol` header that is supported is agreed.
+    ///
+    /// Returns a `BadRequest` error if the request is not a valid websocket handshake.
+    pub fn from_io( io : &mut IO, protocols : &[&str] ) -> Result<Self, Error>
+    {
+        let headers = &io.request.headers;
+
+        if ! has_token( headers, "upgrade", "websocket" )
+        {
+            return Err( handshake_error( "upgrade", "expected websocket" ) )
+        }
+
+        if ! has_token( headers, "connection", "upgrade" )
+        {
+            return Err( handshake_error( "connection", "expected upgrade" ) )
+        }
+
+        if ! has_token( headers, "sec-websocket-version", "13" )
+        {
+            return Err( handshake_error( "sec-websocket-version", "expected 13" ) )
+        }
+
+        let key = match headers.get( "sec-websocket-key" ).and_then( | h | h.value.as_ref() )
+        {
+            Some( k ) if is_valid_key( k.trim() ) => k.trim().to_string(),
+            _ => return Err( handshake_error( "sec-websocket-key", "expected a base64 encoded 16 bytes key" ) )
+        };
+
+        let protocol = select_protocol( headers, protocols );
+
+        let on_upgrade = match io.request.upgrade.take()
+        {
+            Some( u ) => u,
+            _ => return Err( Error::EndpointExecution( "The connection can't be upgraded".to_string() ) )
+        };
+
+        Ok(
+            Self
+            {
+                on_upgrade,
+                accept : derive_accept_key( key.as_bytes() ),
+                protocol
+            }
+        )
+    }
+
+    /// The subprotocol agreed in the handshake, if any.
+    pub fn protocol( &self ) -> Option<&str>
+    {
+        self.protocol.as_deref()
+    }
+
+    /// Sets the `101 Switching Protocols` response and spawns `handler` with the socket,
+    /// once the response is sent and the connection upgraded.
+    pub fn on_upgrade<F, Fut>( self, mut io : IO, handler : F ) -> IO
+    where
+        F: FnOnce( WebSocket ) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static
+    {
+        io.response.status = 101;
+
+        io.response.body = None;
+
+        io.response.headers.replace_header( "upgrade".to_string(), "websocket".to_string() );
+        io.response.headers.replace_header( "connection".to_string(), "Upgrade".to_string() );
+        io.response.headers.replace_header( "sec-websocket-accept".to_string(), self.accept );
+
+        if let Some( p ) = &self.protocol
+        {
+            io.response.headers.replace_header( "sec-websocket-protocol".to_string(), p.clone() );
+        }
+
+        let protocol = self.protocol;
+        let on_upgrade = self.on_upgrade;
+
+        tokio::spawn( async move {
+
+            match on_upgrade.await
+            {
+                Ok( upgraded ) => {
+
+                    let stream = WebSocketStream::from_raw_socket( TokioIo::new( upgraded ), Role::Server, None ).await;
+
+                    handler( WebSocket::new( stream, protocol ) ).await
+                },
+                Err( e ) => eprintln!( "Websocket upgrade error: {}", e )
+            }
+        } );
+
+        io
+    }
+}
+
+fn handshake_error( header : &str, message : &str ) -> Error
+{
+    Error::BadRequest( ExtractionError::new( header, ExtractionSource::Header, message.to_string() ) )
+}
+
+// Header values are comma separated lists of case insensitive tokens, like `Connection: keep-alive, Upgrade`.
+fn header_tokens( headers : &Headers, name : &str ) -> Vec<String>
+{
+    match headers.get_all( name )
+    {
+        Some( v ) => v.iter()
+            .filter_map( | h | h.value.as_ref() )
+            .flat_map( | h | h.split( "," ) )
+            .map( | t | t.trim().to_string() )
+            .filter( | t | ! t.is_empty() )
+            .collect(),
+        _ => vec![]
+    }
+}
+
+fn has_token( headers : &Headers, name : &str, token : &str ) -> bool
+{
+    header_tokens( headers, name ).iter().any( | t | t.eq_ignore_ascii_case( token ) )
+}
+
+// The order of preference is the one of the client.
+fn select_protocol( headers : &Headers, protocols : &[&str] ) -> Option<String>
+{
+    header_tokens( headers, "sec-websocket-protocol" ).into_iter().find( | p | protocols.contains( &p.as_str() ) )
+}
+
+// 16 bytes encoded in base64 are 24 characters, ending with `==`.
+fn is_valid_key( key : &str ) -> bool
+{
+    key.len() == 24 &&
+    key.ends_with( "==" ) &&
+    key[ ..22 ].chars().all( | c | c.is_ascii_alphanumeric() || c == '+' || c == '/' )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::{request::request_data::RequestData, response::response_data::ResponseData};
+
+    use super::*;
+
+    fn handshake_io( headers : &[ ( &str, &str ) ] ) -> IO
+    {
+        let mut request = RequestData::default();
+
+        for ( name, value ) in headers
+        {
+            request.headers.add_header( name.to_string(), value.to_string() );
+        }
+
+        IO::new( request, ResponseData::default(), None )
+    }
+
+    fn valid_headers() -> Vec<( &'static str, &'static str )>
+    {
+        vec![
+            ( "upgrade", "websocket" ),
+            ( "connection", "keep-alive, Upgrade" ),
+            ( "sec-websocket-version", "13" ),
+            ( "sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==" )
+        ]
+    }
+
+    fn error_header( io : &mut IO ) -> Option<String>
+    {
+        match WebSocketUpgrade::from_io( io, &[] )
+        {
+            Err( Error::BadRequest( e ) ) => Some( e.name ),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn test_handshake_headers()
+    {
+        let mut headers = valid_headers();
+        headers[ 0 ] = ( "upgrade", "h2c" );
+        assert_eq!( error_header( &mut handshake_io( &headers ) ), Some( "upgrade".to_string() ) );
+
+        let mut headers = valid_headers();
+        headers[ 1 ] = ( "connection", "keep-alive" );
+        assert_eq!( error_header( &mut handshake_io( &headers ) ), Some( "connection".to_string() ) );
+
+        let mut headers = valid_headers();
+        headers[ 2 ] = ( "sec-websocket-version", "8" );
+        assert_eq!( error_header( &mut handshake_io( &headers ) ), Some( "sec-websocket-version".to_string() ) );
+
+        let mut headers = valid_headers();
+        headers[ 3 ] = ( "sec-websocket-key", "short" );
+        assert_eq!( error_header( &mut handshake_io( &headers ) ), Some( "sec-websocket-key".to_string() ) );
+
+        // Valid headers, but a request that is not served with upgrades
+        let result = WebSocketUpgrade::from_io( &mut handshake_io( &valid_headers() ), &[] );
+        assert!( matches!( result, Err( Error::EndpointExecution( _ ) ) ) );
+    }
+
+    #[test]
+    fn test_accept_key()
+    {
+        // Example of RFC 6455
+        assert_eq!( derive_accept_key( b"dGhlIHNhbXBsZSBub25jZQ==" ), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=" );
+    }
+
+    #[test]
+    fn test_select_protocol()
+    {
+        let io = handshake_io( &[ ( "sec-websocket-protocol", "chat.v2, chat.v1" ) ] );
+
+        assert_eq!( select_protocol( &io.request.headers, &[ "chat.v1", "chat.v2" ] ), Some( "chat.v2".to_string() ) );
+        assert_eq!( select_protocol( &io.request.headers, &[ "chat.v1" ] ), Some( "chat.v1".to_string() ) );
+        assert_eq!( select_protocol( &io.request.headers, &[ "other" ] ), None );
+        assert_eq!( select_protocol( &handshake_io( &[] ).request.headers, &[ "chat.v1" ] ), None );
+    }
+}
@@ -0,0 +1,184 @@
+use futures::{SinkExt, StreamExt};
+use hyper::{body::Bytes, upgrade::Upgraded};
+use hyper_util::rt::TokioIo;
+use tokio_tungstenite::{tungstenite::{self, protocol::frame::coding::CloseCode}, WebSocketStream};
+
+use crate::services::error::Error;
+
+/// A message sent or received through a [`WebSocket`].
+///
+/// Pings are answered automatically with a pong, but they are also returned by `recv`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message
+{
+    Text( String ),
+    Binary( Bytes ),
+    Ping( Bytes ),
+    Pong( Bytes ),
+    /// A close message, with an optional status code and reason.
+    Close( Option<CloseFrame> )
+}
+
+impl Message
+{
+    pub fn text( text : impl Into<String> ) -> Self
+    {
+        Message::Text( text.into() )
+    }
+
+    pub fn binary( bytes : impl Into<Bytes> ) -> Self
+    {
+        Message::Binary( bytes.into() )
+    }
+}
+
+/// The status code and the reason of a close message, like `1000` (normal closure).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseFrame
+{
+    pub code : u16,
+    pub reason : String
+}
+
+impl CloseFrame
+{
+    pub fn new( code : u16, reason : &str ) -> Self
+    {
+        Self
+        {
+            code,
+            reason : reason.to_string()
+        }
+    }
+}
+
+/// The connection of a `#[websocket]` endpoint, once the handshake is done.
+///
+/// # Example
+///
+/// ```ignore
+/// #[websocket( url = "/echo" )]
+/// async fn echo( mut socket : WebSocket )
+/// {
+///     while let Some( Ok( message ) ) = socket.recv().await
+///     {
+///         match message
+///         {
+///             Message::Text( _ ) | Message::Binary( _ ) => if socket.send( message ).await.is_err() { break },
+///             Message::Close( _ ) => break,
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+pub struct WebSocket
+{
+    stream : WebSocketStream<TokioIo<Upgraded>>,
+    protocol : Option<String>
+}
+
+impl WebSocket
+{
+    pub(crate) fn new( stream : WebSocketStream<TokioIo<Upgraded>>, protocol : Option<String> ) -> Self
+    {
+        Self
+        {
+            stream,
+            protocol
+        }
+    }
+
+    /// The subprotocol agreed in the handshake, if any.
+    pub fn protocol( &self ) -> Option<&str>
+    {
+        self.protocol.as_deref()
+    }
+
+    /// Sends a message to the client.
+    pub async fn send( &mut self, message : Message ) -> Result<(), Error>
+    {
+        self.stream.send( message.into() ).await.map_err( | e | Error::WebSocket( e.to_string() ) )
+    }
+
+    /// Receives the next message of the client.
+    ///
+    /// Returns `None` once the connection is closed.
+    pub async fn recv( &mut self ) -> Option<Result<Message, Error>>
+    {
+        loop
+        {
+            return match self.stream.next().await?
+            {
+                Ok( tungstenite::Message::Frame( _ ) ) => continue,
+                Ok( message ) => Some( Ok( message.into() ) ),
+                Err( tungstenite::Error::ConnectionClosed ) => None,
+                Err( e ) => Some( Err( Error::WebSocket( e.to_string() ) ) )
+            }
+        }
+    }
+
+    /// Sends a close message. The connection is closed when the client answers it.
+    pub async fn close( &mut self, frame : Option<CloseFrame> ) -> Result<(), Error>
+    {
+        self.send( Message::Close( frame ) ).await
+    }
+}
+
+impl From<Message> for tungstenite::Message
+{
+    fn from( message : Message ) -> Self
+    {
+        match message
+        {
+            Message::Text( t ) => tungstenite::Message::Text( t.into() ),
+            Message::Binary( b ) => tungstenite::Message::Binary( b ),
+            Message::Ping( b ) => tungstenite::Message::Ping( b ),
+            Message::Pong( b ) => tungstenite::Message::Pong( b ),
+            Message::Close( f ) => tungstenite::Message::Close(
+                f.map( | f | tungstenite::protocol::CloseFrame { code : CloseCode::from( f.code ), reason : f.reason.into() } )
+            )
+        }
+    }
+}
+
+impl From<tungstenite::Message> for Message
+{
+    fn from( message : tungstenite::Message ) -> Self
+    {
+        match message
+        {
+            tungstenite::Message::Text( t ) => Message::Text( t.to_string() ),
+            tungstenite::Message::Binary( b ) => Message::Binary( b ),
+            tungstenite::Message::Ping( b ) => Message::Ping( b ),
+            tungstenite::Message::Pong( b ) => Message::Pong( b ),
+            tungstenite::Message::Close( f ) => Message::Close(
+                f.map( | f | CloseFrame { code : f.code.into(), reason : f.reason.to_string() } )
+            ),
+            tungstenite::Message::Frame( f ) => Message::Binary( f.into_payload() )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_conversion()
+    {
+        let messages = vec![
+            Message::text( "hello" ),
+            Message::binary( vec![ 0, 1 ] ),
+            Message::Ping( Bytes::from_static( b"ping" ) ),
+            Message::Close( Some( CloseFrame::new( 1000, "bye" ) ) ),
+            Message::Close( None )
+        ];
+
+        for message in messages
+        {
+            let converted : tungstenite::Message = message.clone().into();
+
+            assert_eq!( Message::from( converted ), message );
+        }
+    }
+}
@@ -1,9 +1,8 @@
-use crate::Error;
-
+use percent_encoding::percent_decode_str;
 
 pub fn normalize_url( url : &str ) -> String
 {
-    let mut url = url.to_lowercase();
+    let mut url = url.to_string();
 
     if ! url.starts_with( "/" )
     {
@@ -18,57 +17,57 @@ pub fn normalize_url( url : &str ) -> String
     return url;
 }
 
-pub fn get_regex( url : &str ) -> Result<Option<regex::Regex>, Error>
+/// Splits a url path in percent-decoded segments.
+///
+/// Empty and `.` segments are discarded and `..` removes the previous segment,
+/// so the result never goes above the root. Segments are decoded after removing
+/// the dot segments, so an encoded segment can't change the path: it returns `None`
+/// if a decoded segment has a slash (`%2F`) or a backslash (`%5C`), or is `.` or `..` (`%2E%2E`).
+pub fn get_url_segments( url : &str ) -> Option<Vec<String>>
 {
-    match get_regex_str( url )
-    {
-        Some( r ) => match regex::Regex::new( &r )
-        {
-            Ok( r ) => Ok( Some( r ) ),
-            Err( e ) => Err( Error::RegexError( e.to_string() ) )
-        },
-        _ => Ok( None )
-    }
-}
-
-fn get_regex_str( url : &str ) -> Option<String>
-{
-    if url.len() < 3
-    {
-        return None;
-    }
-
-    let mut str_ret = String::from( "" );
-
-    let parts = url.split( "/" ).collect::<Vec<&str>>();
+    let mut segments : Vec<String> = vec![];
 
-    let re = regex::Regex::new( r"^\{.+\}$" ).unwrap();
-
-    let mut is_regex = false;
-
-    for part in parts
+    for segment in url.split( '/' )
     {
-        if part.trim() == ""
+        match segment
         {
-            continue;
+            "" | "." => continue,
+            ".." => { segments.pop(); },
+            _ => {
+                let segment = percent_decode_str( segment ).decode_utf8_lossy();
+
+                if segment == "." || segment == ".." || segment.contains( [ '/', '\\' ] )
+                {
+                    return None
+                }
+
+                segments.push( segment.into_owned() )
+            }
         }
+    }
 
-        if re.is_match( part.trim() )
-        {
-            is_regex = true;
+    Some( segments )
+}
 
-            str_ret = format!( r"{}\/[^\/]+", str_ret );
-        }
-        else
-        {
-            str_ret = format!( r"{}\/{}", str_ret, part );    
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if ! is_regex
+    #[test]
+    fn test_get_url_segments()
     {
-        return None
+        assert_eq!( get_url_segments( "/" ), Some( vec![] ) );
+        assert_eq!( get_url_segments( "/files/Report.PDF/" ), Some( vec![ "files".to_string(), "Report.PDF".to_string() ] ) );
+        assert_eq!( get_url_segments( "//files///a" ), Some( vec![ "files".to_string(), "a".to_string() ] ) );
+        assert_eq!( get_url_segments( "/files/./a/../b" ), Some( vec![ "files".to_string(), "b".to_string() ] ) );
+        assert_eq!( get_url_segments( "/../../files/b" ), Some( vec![ "files".to_string(), "b".to_string() ] ) );
+        assert_eq!( get_url_segments( "/files/hello%20world/%C3%B1" ), Some( vec![ "files".to_string(), "hello world".to_string(), "ñ".to_string() ] ) );
+
+        assert_eq!( get_url_segments( "/files/a%2Fb" ), None );
+        assert_eq!( get_url_segments( "/files/..%2F..%2Fetc%2Fpasswd" ), None );
+        assert_eq!( get_url_segments( "/files/%2E%2E/b" ), None );
+        assert_eq!( get_url_segments( "/files/%2e%2e%2fetc" ), None );
+        assert_eq!( get_url_segments( "/files/%2e" ), None );
+        assert_eq!( get_url_segments( "/files/..%5Cetc" ), None );
     }
-
-    Some( format!( r"^{}$", str_ret ) )
-}
\ No newline at end of file
+}